@@ -0,0 +1,34 @@
+use ecs_rust::component::Component;
+
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
+pub struct AmmoComponent {
+    ammo: u32
+}
+
+impl AmmoComponent {
+    pub fn new(ammo: u32) -> Self {
+        Self { ammo }
+    }
+
+    pub fn ammo(&self) -> u32 {
+        self.ammo
+    }
+}
+
+impl Component for AmmoComponent {
+}
+
+impl SavedComponent for AmmoComponent {
+    const NAME: &'static str = "ammo";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        fields.push(self.ammo);
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        Ok(Self::new(fields.next()?))
+    }
+}
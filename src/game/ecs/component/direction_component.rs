@@ -1,14 +1,31 @@
 use ecs_rust::component::Component;
 
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
 pub struct DirectionComponent {
-    pub direction: f32 // Angle
+    pub direction: f32, // Angle
+    pub pitch: f32 // Vertical look angle, positive is up
 }
 
 impl DirectionComponent {
     pub fn new(direction: f32) -> Self {
-        Self { direction }
+        Self { direction, pitch: 0.0 }
     }
 }
 
 impl Component for DirectionComponent {
 }
+
+impl SavedComponent for DirectionComponent {
+    const NAME: &'static str = "direction";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        fields.push(self.direction).push(self.pitch);
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        Ok(Self { direction: fields.next()?, pitch: fields.next()? })
+    }
+}
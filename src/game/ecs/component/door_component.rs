@@ -0,0 +1,237 @@
+use std::time::Duration;
+
+use ecs_rust::component::Component;
+use glm::{vec2, UVec2, Vec2};
+
+use crate::game::game_state::Repositories;
+use crate::game::model::object_color::ObjectColor;
+use crate::game::model::tile::{TileAxis, TileShape};
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
+const DEFAULT_DOOR_SPEED: f32 = 1.5; // Open fraction per second
+const DEFAULT_AUTO_CLOSE_DELAY: Duration = Duration::from_secs(3);
+
+// Door can be passed through when it is opened at least on this fraction
+pub const DOOR_PASSABLE_OPEN_FRACTION: f32 = 0.8;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DoorKind {
+    // Slides along its own axis into the frame
+    Sliding,
+    // Rotates around its first point
+    Swinging
+}
+
+impl DoorKind {
+    pub fn from_name(name: &str) -> Option<DoorKind> {
+        match name {
+            "sliding" => Some(DoorKind::Sliding),
+            "swinging" => Some(DoorKind::Swinging),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DoorKind::Sliding => "sliding",
+            DoorKind::Swinging => "swinging"
+        }
+    }
+}
+
+// Where the door is placed. Geometry of the closed door is taken from the binding
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DoorBinding {
+    // Centered thin wall in the tilemap cell. Cell itself should be passable
+    Tile {
+        position: UVec2,
+        axis: TileAxis
+    },
+
+    Line {
+        from: Vec2,
+        to: Vec2
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DoorState {
+    Closed,
+    Opening,
+    Open {
+        elapsed: Duration
+    },
+    Closing
+}
+
+pub struct DoorComponent {
+    kind: DoorKind,
+    binding: DoorBinding,
+    color: ObjectColor,
+    state: DoorState,
+    open_fraction: f32,
+    speed: f32,
+    auto_close_delay: Duration,
+    is_locked: bool,
+    required_key: Option<String>
+}
+
+impl DoorComponent {
+    pub fn new(kind: DoorKind, binding: DoorBinding, color: ObjectColor) -> Self {
+        Self {
+            kind,
+            binding,
+            color,
+            state: DoorState::Closed,
+            open_fraction: 0.0,
+            speed: DEFAULT_DOOR_SPEED,
+            auto_close_delay: DEFAULT_AUTO_CLOSE_DELAY,
+            is_locked: false,
+            required_key: None
+        }
+    }
+
+    // Locked door can be opened only by the player which has required key. Door without required key can't be opened by the player
+    pub fn with_lock(mut self, required_key: Option<&str>) -> Self {
+        self.is_locked = true;
+        self.required_key = required_key.map(str::to_string);
+        self
+    }
+
+    pub fn color(&self) -> &ObjectColor {
+        &self.color
+    }
+    pub fn state(&self) -> DoorState {
+        self.state
+    }
+    pub fn set_state(&mut self, state: DoorState) {
+        self.state = state;
+    }
+    pub fn open_fraction(&self) -> f32 {
+        self.open_fraction
+    }
+    pub fn set_open_fraction(&mut self, open_fraction: f32) {
+        self.open_fraction = open_fraction.clamp(0.0, 1.0);
+    }
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+    pub fn auto_close_delay(&self) -> Duration {
+        self.auto_close_delay
+    }
+    pub fn is_locked(&self) -> bool {
+        self.is_locked
+    }
+    pub fn set_locked(&mut self, is_locked: bool) {
+        self.is_locked = is_locked;
+    }
+    pub fn required_key(&self) -> Option<&String> {
+        self.required_key.as_ref()
+    }
+
+    pub fn is_passable(&self) -> bool {
+        self.open_fraction >= DOOR_PASSABLE_OPEN_FRACTION
+    }
+
+    // Door segment in world coordinates when the door is closed
+    pub fn closed_segment(&self) -> (Vec2, Vec2) {
+        match self.binding {
+            DoorBinding::Tile { position, axis } => {
+                let tile_origin = vec2(position.x as f32, position.y as f32);
+                let (from, to) = TileShape::CenteredWall { axis }.segment().unwrap();
+                (tile_origin + from, tile_origin + to)
+            }
+
+            DoorBinding::Line { from, to } => (from, to)
+        }
+    }
+
+    // Door segment in world coordinates with current open fraction
+    pub fn visible_segment(&self) -> (Vec2, Vec2) {
+        let (from, to) = self.closed_segment();
+        let direction = to - from;
+
+        match self.kind {
+            DoorKind::Sliding => (from + direction * self.open_fraction, to),
+
+            DoorKind::Swinging => {
+                let angle = self.open_fraction * std::f32::consts::FRAC_PI_2;
+                let rotated = vec2(
+                    direction.x * angle.cos() - direction.y * angle.sin(),
+                    direction.x * angle.sin() + direction.y * angle.cos()
+                );
+
+                (from, from + rotated)
+            }
+        }
+    }
+}
+
+impl Component for DoorComponent {
+}
+
+impl SavedComponent for DoorComponent {
+    const NAME: &'static str = "door";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        fields.push(self.kind.name()).push_color(&self.color)?;
+
+        match self.binding {
+            DoorBinding::Tile { position, axis } => fields.push("tile").push_uvec2(position).push(axis.name()),
+            DoorBinding::Line { from, to } => fields.push("line").push_vec2(from).push_vec2(to)
+        };
+
+        match self.state {
+            DoorState::Closed => fields.push("closed"),
+            DoorState::Opening => fields.push("opening"),
+            DoorState::Open { elapsed } => fields.push("open").push_duration(elapsed),
+            DoorState::Closing => fields.push("closing")
+        };
+
+        fields
+            .push(self.open_fraction)
+            .push(self.speed)
+            .push_duration(self.auto_close_delay)
+            .push_bool(self.is_locked)
+            .push_optional_string(self.required_key.as_ref());
+
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        let kind = fields.next_word()?;
+        let kind = DoorKind::from_name(kind).ok_or_else(|| format!("unknown door kind \"{}\"", kind))?;
+        let color = fields.next_color()?;
+
+        let binding = match fields.next_word()? {
+            "tile" => {
+                let position = fields.next_uvec2()?;
+                let axis = fields.next_word()?;
+
+                DoorBinding::Tile { position, axis: TileAxis::from_name(axis).ok_or_else(|| format!("unknown axis \"{}\"", axis))? }
+            }
+            "line" => DoorBinding::Line { from: fields.next_vec2()?, to: fields.next_vec2()? },
+            binding => return Err(format!("unknown door binding \"{}\"", binding))
+        };
+
+        let state = match fields.next_word()? {
+            "closed" => DoorState::Closed,
+            "opening" => DoorState::Opening,
+            "open" => DoorState::Open { elapsed: fields.next_duration()? },
+            "closing" => DoorState::Closing,
+            state => return Err(format!("unknown door state \"{}\"", state))
+        };
+
+        Ok(Self {
+            kind,
+            binding,
+            color,
+            state,
+            open_fraction: fields.next()?,
+            speed: fields.next()?,
+            auto_close_delay: fields.next_duration()?,
+            is_locked: fields.next_bool()?,
+            required_key: fields.next_optional_string()?
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use ecs_rust::component::Component;
+
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
+pub struct HealthComponent {
+    health: u32,
+    max_health: u32,
+
+    // Damage since the last feedback, e.g. controller rumble
+    recent_damage: u32
+}
+
+impl HealthComponent {
+    pub fn new(max_health: u32) -> Self {
+        Self { health: max_health, max_health, recent_damage: 0 }
+    }
+
+    pub fn health(&self) -> u32 {
+        self.health
+    }
+    pub fn max_health(&self) -> u32 {
+        self.max_health
+    }
+
+    pub fn damage(&mut self, amount: u32) {
+        let damage = amount.min(self.health);
+
+        self.health -= damage;
+        self.recent_damage += damage;
+    }
+
+    pub fn take_recent_damage(&mut self) -> u32 {
+        std::mem::take(&mut self.recent_damage)
+    }
+}
+
+impl Component for HealthComponent {
+}
+
+// Recent damage is not saved, the feedback of the old damage is not repeated after loading
+impl SavedComponent for HealthComponent {
+    const NAME: &'static str = "health";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        fields.push(self.health).push(self.max_health);
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        let (health, max_health) = (fields.next()?, fields.next()?);
+
+        if health > max_health {
+            return Err("health is above the maximal health".to_string());
+        }
+
+        Ok(Self { health, max_health, recent_damage: 0 })
+    }
+}
@@ -0,0 +1,44 @@
+use ecs_rust::component::Component;
+
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
+// Keys collected by the entity, used to unlock doors
+pub struct KeysComponent {
+    keys: Vec<String>
+}
+
+impl KeysComponent {
+    pub fn new(keys: Vec<String>) -> Self {
+        Self { keys }
+    }
+
+    pub fn has_key(&self, key: &str) -> bool {
+        self.keys.iter().any(|owned_key| owned_key == key)
+    }
+}
+
+impl Component for KeysComponent {
+}
+
+impl SavedComponent for KeysComponent {
+    const NAME: &'static str = "keys";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        for key in &self.keys {
+            fields.push_string(key);
+        }
+
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        let mut keys = vec![];
+
+        while !fields.is_empty() {
+            keys.push(fields.next_string()?);
+        }
+
+        Ok(Self::new(keys))
+    }
+}
@@ -1,5 +1,7 @@
 use ecs_rust::component::Component;
 use crate::game::model::ResourceId;
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
 
 pub struct LinemapComponent {
     linemap: ResourceId
@@ -17,3 +19,16 @@ impl LinemapComponent {
 
 impl Component for LinemapComponent {
 }
+
+impl SavedComponent for LinemapComponent {
+    const NAME: &'static str = "linemap";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        fields.push(self.linemap);
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        Ok(Self::new(fields.next()?))
+    }
+}
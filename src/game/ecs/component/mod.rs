@@ -3,3 +3,13 @@ pub mod player_flag_component;
 pub mod direction_component;
 pub mod tilemap_component;
 pub mod linemap_component;
+pub mod door_component;
+pub mod keys_component;
+pub mod push_wall_component;
+pub mod moving_segment_component;
+pub mod trigger_component;
+pub mod health_component;
+pub mod ammo_component;
+pub mod previous_pose_component;
+pub mod velocity_component;
+pub mod vertical_motion_component;
@@ -0,0 +1,101 @@
+use ecs_rust::component::Component;
+use glm::Vec2;
+
+use crate::game::game_state::Repositories;
+use crate::game::model::ResourceId;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
+const DEFAULT_SEGMENT_SPEED: f32 = 1.0; // Units per second
+
+pub enum MovingSegmentState {
+    Idle,
+    Triggered,
+    Moving {
+        // Line position before moving
+        from: Vec2,
+        to: Vec2,
+        travelled: f32
+    },
+    Finished
+}
+
+// Line of the linemap, which slides by the offset when used
+pub struct MovingSegmentComponent {
+    linemap: ResourceId,
+    line_index: usize,
+    offset: Vec2,
+    speed: f32,
+    state: MovingSegmentState
+}
+
+impl MovingSegmentComponent {
+    pub fn new(linemap: ResourceId, line_index: usize, offset: Vec2) -> Self {
+        Self {
+            linemap,
+            line_index,
+            offset,
+            speed: DEFAULT_SEGMENT_SPEED,
+            state: MovingSegmentState::Idle
+        }
+    }
+
+    pub fn linemap(&self) -> ResourceId {
+        self.linemap
+    }
+    pub fn line_index(&self) -> usize {
+        self.line_index
+    }
+    pub fn offset(&self) -> Vec2 {
+        self.offset
+    }
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+    pub fn state(&self) -> &MovingSegmentState {
+        &self.state
+    }
+    pub fn set_state(&mut self, state: MovingSegmentState) {
+        self.state = state;
+    }
+
+    // Segment starts moving only once
+    pub fn trigger(&mut self) {
+        if let MovingSegmentState::Idle = self.state {
+            self.state = MovingSegmentState::Triggered;
+        }
+    }
+}
+
+impl Component for MovingSegmentComponent {
+}
+
+impl SavedComponent for MovingSegmentComponent {
+    const NAME: &'static str = "moving_segment";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        fields.push(self.linemap).push(self.line_index).push_vec2(self.offset).push(self.speed);
+
+        match &self.state {
+            MovingSegmentState::Idle => fields.push("idle"),
+            MovingSegmentState::Triggered => fields.push("triggered"),
+            MovingSegmentState::Moving { from, to, travelled } => fields.push("moving").push_vec2(*from).push_vec2(*to).push(travelled),
+            MovingSegmentState::Finished => fields.push("finished")
+        };
+
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        let (linemap, line_index, offset, speed) = (fields.next()?, fields.next()?, fields.next_vec2()?, fields.next()?);
+
+        let state = match fields.next_word()? {
+            "idle" => MovingSegmentState::Idle,
+            "triggered" => MovingSegmentState::Triggered,
+            "moving" => MovingSegmentState::Moving { from: fields.next_vec2()?, to: fields.next_vec2()?, travelled: fields.next()? },
+            "finished" => MovingSegmentState::Finished,
+            state => return Err(format!("unknown moving segment state \"{}\"", state))
+        };
+
+        Ok(Self { linemap, line_index, offset, speed, state })
+    }
+}
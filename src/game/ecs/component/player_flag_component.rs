@@ -1,5 +1,8 @@
 use ecs_rust::component::Component;
 
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
 pub struct PlayerFlagComponent;
 
 impl PlayerFlagComponent {
@@ -10,3 +13,15 @@ impl PlayerFlagComponent {
 
 impl Component for PlayerFlagComponent {
 }
+
+impl SavedComponent for PlayerFlagComponent {
+    const NAME: &'static str = "player";
+
+    fn save(&self, _: &mut FieldWriter) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn load(_: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        Ok(Self::new())
+    }
+}
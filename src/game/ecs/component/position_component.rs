@@ -1,6 +1,9 @@
 use ecs_rust::component::Component;
 use glm::Vec2;
 
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
 pub struct PositionComponent {
     pub position: Vec2
 }
@@ -13,3 +16,16 @@ impl PositionComponent {
 
 impl Component for PositionComponent {
 }
+
+impl SavedComponent for PositionComponent {
+    const NAME: &'static str = "position";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        fields.push_vec2(self.position);
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        Ok(Self::new(fields.next_vec2()?))
+    }
+}
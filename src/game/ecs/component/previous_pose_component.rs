@@ -0,0 +1,36 @@
+use ecs_rust::component::Component;
+use glm::Vec2;
+
+use crate::game::ecs::component::vertical_motion_component::STANDING_EYE_HEIGHT;
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
+// Pose before the last simulation step, rendering interpolates from it to the current pose
+pub struct PreviousPoseComponent {
+    pub position: Vec2,
+    pub direction: f32,
+    pub pitch: f32,
+    pub eye_height: f32
+}
+
+impl PreviousPoseComponent {
+    pub fn new(position: Vec2, direction: f32) -> Self {
+        Self { position, direction, pitch: 0.0, eye_height: STANDING_EYE_HEIGHT }
+    }
+}
+
+impl Component for PreviousPoseComponent {
+}
+
+impl SavedComponent for PreviousPoseComponent {
+    const NAME: &'static str = "previous_pose";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        fields.push_vec2(self.position).push(self.direction).push(self.pitch).push(self.eye_height);
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        Ok(Self { position: fields.next_vec2()?, direction: fields.next()?, pitch: fields.next()?, eye_height: fields.next()? })
+    }
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use ecs_rust::component::Component;
+use glm::UVec2;
+
+use crate::game::game_state::Repositories;
+use crate::game::model::ResourceId;
+use crate::game::model::repository::Resource;
+use crate::game::model::tile::{Tile, TileEdge};
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
+const DEFAULT_PUSH_WALL_SPEED: f32 = 1.0; // Tiles per second
+
+pub enum PushWallState {
+    Idle,
+    Triggered,
+    Moving {
+        tile: Arc<Tile>,
+        distance: u32,
+        travelled: f32
+    },
+    Finished
+}
+
+// Secret wall of the tilemap, which slides away when used
+pub struct PushWallComponent {
+    tilemap: ResourceId,
+    cell: UVec2,
+    direction: TileEdge,
+    distance: u32,
+    // Tile left in the cells passed by the wall
+    empty_tile: ResourceId,
+    speed: f32,
+    state: PushWallState
+}
+
+impl PushWallComponent {
+    pub fn new(tilemap: ResourceId, cell: UVec2, direction: TileEdge, distance: u32, empty_tile: ResourceId) -> Self {
+        Self {
+            tilemap,
+            cell,
+            direction,
+            distance,
+            empty_tile,
+            speed: DEFAULT_PUSH_WALL_SPEED,
+            state: PushWallState::Idle
+        }
+    }
+
+    pub fn tilemap(&self) -> ResourceId {
+        self.tilemap
+    }
+    pub fn cell(&self) -> UVec2 {
+        self.cell
+    }
+    pub fn direction(&self) -> TileEdge {
+        self.direction
+    }
+    pub fn distance(&self) -> u32 {
+        self.distance
+    }
+    pub fn empty_tile(&self) -> ResourceId {
+        self.empty_tile
+    }
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+    pub fn state(&self) -> &PushWallState {
+        &self.state
+    }
+    pub fn set_state(&mut self, state: PushWallState) {
+        self.state = state;
+    }
+
+    // Wall starts moving only once
+    pub fn trigger(&mut self) {
+        if let PushWallState::Idle = self.state {
+            self.state = PushWallState::Triggered;
+        }
+    }
+}
+
+impl Component for PushWallComponent {
+}
+
+impl SavedComponent for PushWallComponent {
+    const NAME: &'static str = "push_wall";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        fields
+            .push(self.tilemap)
+            .push_uvec2(self.cell)
+            .push(self.direction.name())
+            .push(self.distance)
+            .push(self.empty_tile)
+            .push(self.speed);
+
+        match &self.state {
+            PushWallState::Idle => fields.push("idle"),
+            PushWallState::Triggered => fields.push("triggered"),
+            PushWallState::Moving { tile, distance, travelled } => fields.push("moving").push(tile.id()).push(distance).push(travelled),
+            PushWallState::Finished => fields.push("finished")
+        };
+
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, repositories: &Repositories) -> Result<Self, String> {
+        let tilemap = fields.next()?;
+        let cell = fields.next_uvec2()?;
+
+        let direction = fields.next_word()?;
+        let direction = TileEdge::from_name(direction).ok_or_else(|| format!("unknown edge \"{}\"", direction))?;
+
+        let (distance, empty_tile, speed) = (fields.next()?, fields.next()?, fields.next()?);
+
+        let state = match fields.next_word()? {
+            "idle" => PushWallState::Idle,
+            "triggered" => PushWallState::Triggered,
+            "moving" => {
+                let tile_id: ResourceId = fields.next()?;
+                let tile = repositories.tiles_repository().borrow().get_resource(&tile_id).cloned()
+                    .ok_or_else(|| format!("tile {} is not found", tile_id))?;
+
+                PushWallState::Moving { tile, distance: fields.next()?, travelled: fields.next()? }
+            }
+            "finished" => PushWallState::Finished,
+            state => return Err(format!("unknown push-wall state \"{}\"", state))
+        };
+
+        Ok(Self { tilemap, cell, direction, distance, empty_tile, speed, state })
+    }
+}
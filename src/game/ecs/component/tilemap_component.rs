@@ -1,5 +1,7 @@
 use ecs_rust::component::Component;
 use crate::game::model::ResourceId;
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
 
 pub struct TilemapComponent {
     tilemap_id: ResourceId
@@ -17,3 +19,16 @@ impl TilemapComponent {
 
 impl Component for TilemapComponent {
 }
+
+impl SavedComponent for TilemapComponent {
+    const NAME: &'static str = "tilemap";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        fields.push(self.tilemap_id);
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        Ok(Self::new(fields.next()?))
+    }
+}
@@ -0,0 +1,128 @@
+use ecs_rust::component::Component;
+use glm::{UVec2, Vec2};
+
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
+pub enum TriggerRegion {
+    Rect {
+        from: Vec2,
+        to: Vec2
+    },
+
+    Tiles {
+        tiles: Vec<UVec2>
+    }
+}
+
+impl TriggerRegion {
+    pub fn contains(&self, point: Vec2) -> bool {
+        match self {
+            TriggerRegion::Rect { from, to } =>
+                from.x <= point.x && point.x <= to.x && from.y <= point.y && point.y <= to.y,
+
+            TriggerRegion::Tiles { tiles } => point.x >= 0.0 && point.y >= 0.0 && tiles.iter()
+                .any(|tile| tile.x == point.x as u32 && tile.y == point.y as u32)
+        }
+    }
+}
+
+// Region firing named events when the player enters, exits or uses it
+pub struct TriggerComponent {
+    region: TriggerRegion,
+    enter_event: Option<String>,
+    exit_event: Option<String>,
+    use_event: Option<String>,
+    is_player_inside: bool
+}
+
+impl TriggerComponent {
+    pub fn new(region: TriggerRegion) -> Self {
+        Self { region, enter_event: None, exit_event: None, use_event: None, is_player_inside: false }
+    }
+
+    pub fn on_enter(mut self, event_name: &str) -> Self {
+        self.enter_event = Some(event_name.to_string());
+        self
+    }
+    pub fn on_exit(mut self, event_name: &str) -> Self {
+        self.exit_event = Some(event_name.to_string());
+        self
+    }
+    pub fn on_use(mut self, event_name: &str) -> Self {
+        self.use_event = Some(event_name.to_string());
+        self
+    }
+
+    pub fn region(&self) -> &TriggerRegion {
+        &self.region
+    }
+    pub fn enter_event(&self) -> Option<&String> {
+        self.enter_event.as_ref()
+    }
+    pub fn exit_event(&self) -> Option<&String> {
+        self.exit_event.as_ref()
+    }
+    pub fn use_event(&self) -> Option<&String> {
+        self.use_event.as_ref()
+    }
+    pub fn is_player_inside(&self) -> bool {
+        self.is_player_inside
+    }
+    pub fn set_player_inside(&mut self, is_player_inside: bool) {
+        self.is_player_inside = is_player_inside;
+    }
+}
+
+impl Component for TriggerComponent {
+}
+
+impl SavedComponent for TriggerComponent {
+    const NAME: &'static str = "trigger";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        match &self.region {
+            TriggerRegion::Rect { from, to } => {
+                fields.push("rect").push_vec2(*from).push_vec2(*to);
+            }
+            TriggerRegion::Tiles { tiles } => {
+                fields.push("tiles").push(tiles.len());
+
+                for tile in tiles {
+                    fields.push_uvec2(*tile);
+                }
+            }
+        }
+
+        fields
+            .push_optional_string(self.enter_event.as_ref())
+            .push_optional_string(self.exit_event.as_ref())
+            .push_optional_string(self.use_event.as_ref())
+            .push_bool(self.is_player_inside);
+
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        let region = match fields.next_word()? {
+            "rect" => TriggerRegion::Rect { from: fields.next_vec2()?, to: fields.next_vec2()? },
+            "tiles" => {
+                let tiles_count: usize = fields.next()?;
+                let tiles = (0..tiles_count)
+                    .map(|_| fields.next_uvec2())
+                    .collect::<Result<Vec<UVec2>, String>>()?;
+
+                TriggerRegion::Tiles { tiles }
+            }
+            region => return Err(format!("unknown trigger region \"{}\"", region))
+        };
+
+        Ok(Self {
+            region,
+            enter_event: fields.next_optional_string()?,
+            exit_event: fields.next_optional_string()?,
+            use_event: fields.next_optional_string()?,
+            is_player_inside: fields.next_bool()?
+        })
+    }
+}
@@ -0,0 +1,32 @@
+use ecs_rust::component::Component;
+use glm::{vec2, Vec2};
+
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
+// Tiles per second, changed by the moving system with acceleration and friction
+pub struct VelocityComponent {
+    pub velocity: Vec2
+}
+
+impl VelocityComponent {
+    pub fn new() -> Self {
+        Self { velocity: vec2(0.0, 0.0) }
+    }
+}
+
+impl Component for VelocityComponent {
+}
+
+impl SavedComponent for VelocityComponent {
+    const NAME: &'static str = "velocity";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        fields.push_vec2(self.velocity);
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        Ok(Self { velocity: fields.next_vec2()? })
+    }
+}
@@ -0,0 +1,45 @@
+use ecs_rust::component::Component;
+
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
+// Eye heights are given in wall heights
+pub const STANDING_EYE_HEIGHT: f32 = 0.5;
+pub const CROUCHING_EYE_HEIGHT: f32 = 0.3;
+
+// Jumping and crouching state, the map itself is flat
+pub struct VerticalMotionComponent {
+    pub elevation: f32, // Feet height above the floor
+    pub vertical_velocity: f32,
+    pub crouching: f32 // 0 is standing, 1 is crouching, changes smoothly
+}
+
+impl VerticalMotionComponent {
+    pub fn new() -> Self {
+        Self { elevation: 0.0, vertical_velocity: 0.0, crouching: 0.0 }
+    }
+
+    pub fn eye_height(&self) -> f32 {
+        self.elevation + STANDING_EYE_HEIGHT + (CROUCHING_EYE_HEIGHT - STANDING_EYE_HEIGHT) * self.crouching
+    }
+
+    pub fn is_on_floor(&self) -> bool {
+        self.elevation <= 0.0
+    }
+}
+
+impl Component for VerticalMotionComponent {
+}
+
+impl SavedComponent for VerticalMotionComponent {
+    const NAME: &'static str = "vertical_motion";
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String> {
+        fields.push(self.elevation).push(self.vertical_velocity).push(self.crouching);
+        Ok(())
+    }
+
+    fn load(fields: &mut FieldReader, _: &Repositories) -> Result<Self, String> {
+        Ok(Self { elevation: fields.next()?, vertical_velocity: fields.next()?, crouching: fields.next()? })
+    }
+}
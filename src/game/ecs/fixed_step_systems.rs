@@ -0,0 +1,42 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+
+use crate::game::ecs::simulation_clock::SimulationClock;
+
+// Group of simulation systems updated in order once per every simulation step of the frame
+pub struct FixedStepSystems {
+    clock: Rc<RefCell<SimulationClock>>,
+    systems: Vec<Box<dyn System>>
+}
+
+impl FixedStepSystems {
+    pub fn new(clock: &Rc<RefCell<SimulationClock>>) -> Self {
+        Self {
+            clock: clock.clone(),
+            systems: vec![]
+        }
+    }
+
+    pub fn add_system<T: 'static + System>(&mut self, system: T) -> &mut Self {
+        self.systems.push(Box::new(system));
+        self
+    }
+}
+
+impl System for FixedStepSystems {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let steps = self.clock.borrow().steps();
+
+        for _ in 0..steps {
+            for system in self.systems.iter_mut() {
+                system.update(manager, accessor);
+
+                // Same as the world does between systems, so entities ids caches see changed components
+                manager.increment_frame();
+            }
+        }
+    }
+}
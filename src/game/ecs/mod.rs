@@ -1,2 +1,6 @@
 pub mod system;
 pub mod component;
+pub mod raycast_worlds;
+pub mod use_action;
+pub mod simulation_clock;
+pub mod fixed_step_systems;
@@ -0,0 +1,93 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+
+use crate::game::ecs::component::door_component::DoorComponent;
+use crate::game::ecs::component::linemap_component::LinemapComponent;
+use crate::game::ecs::component::tilemap_component::TilemapComponent;
+use crate::game::game_state::Repositories;
+use crate::game::model::composite_world::CompositeWorld;
+use crate::game::model::doors_world::DoorsWorld;
+use crate::game::model::linemap::Linemap;
+use crate::game::model::raycast_world::RaycastWorld;
+use crate::game::model::repository::Repository;
+use crate::game::model::tilemap::Tilemap;
+
+// Resolves map components of the entities to the maps. New map types should be registered only here
+#[derive(Clone)]
+pub struct RaycastWorlds {
+    tilemap_repository: Rc<RefCell<Repository<Tilemap>>>,
+    linemap_repository: Rc<RefCell<Repository<Linemap>>>
+}
+
+impl RaycastWorlds {
+    pub fn new(repositories: &Repositories) -> Self {
+        Self {
+            tilemap_repository: repositories.tilemap_repository().clone(),
+            linemap_repository: repositories.linemap_repository().clone()
+        }
+    }
+
+    pub fn collect(&self, manager: &EntityManager, accessor: &mut EntityIdAccessor) -> Vec<Arc<dyn RaycastWorld>> {
+        let mut worlds: Vec<Arc<dyn RaycastWorld>> = vec![];
+
+        let tilemap_repository = self.tilemap_repository.borrow();
+
+        for tilemap_entity_id in accessor.borrow_ids::<TilemapComponent>(manager).into_iter().flatten() {
+            let tilemap = manager
+                .borrow_component::<TilemapComponent>(*tilemap_entity_id)
+                .and_then(|tilemap_component| tilemap_repository.get_resource(tilemap_component.tilemap()));
+
+            match tilemap {
+                None => log::warn!("Tilemap of entity {} is not found", tilemap_entity_id),
+                Some(tilemap) => worlds.push(tilemap.clone())
+            }
+        }
+
+        let linemap_repository = self.linemap_repository.borrow();
+
+        for linemap_entity_id in accessor.borrow_ids::<LinemapComponent>(manager).into_iter().flatten() {
+            let linemap = manager
+                .borrow_component::<LinemapComponent>(*linemap_entity_id)
+                .and_then(|linemap_component| linemap_repository.get_resource(&linemap_component.linemap()));
+
+            match linemap {
+                None => log::warn!("Linemap of entity {} is not found", linemap_entity_id),
+                Some(linemap) => worlds.push(linemap.clone())
+            }
+        }
+
+        let doors = Self::collect_doors(manager, accessor);
+
+        if !doors.is_empty() {
+            worlds.push(Arc::new(doors));
+        }
+
+        worlds
+    }
+
+    fn collect_doors(manager: &EntityManager, accessor: &mut EntityIdAccessor) -> DoorsWorld {
+        let mut doors = DoorsWorld::new();
+
+        for door_entity_id in accessor.borrow_ids::<DoorComponent>(manager).into_iter().flatten() {
+            if let Some(door) = manager.borrow_component::<DoorComponent>(*door_entity_id) {
+                doors.add_door(*door.color(), door.visible_segment(), !door.is_passable());
+            }
+        }
+
+        doors
+    }
+
+    // All maps of the level combined into a single world, so maps on the same level do not overdraw each other
+    pub fn compose(&self, manager: &EntityManager, accessor: &mut EntityIdAccessor) -> Option<Arc<dyn RaycastWorld>> {
+        let mut worlds = self.collect(manager, accessor);
+
+        match worlds.len() {
+            0 => None,
+            1 => worlds.pop(),
+            _ => Some(Arc::new(CompositeWorld::new(worlds)))
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+// Simulation runs at fixed rate independent of the frame rate
+pub const SIMULATION_STEP: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+// Slow frames don't cause spiral of death, simulation slows down instead
+const MAX_STEPS_PER_FRAME: u32 = 5;
+
+// Shared time resource: steps to simulate in the current frame and their fixed delta time
+pub struct SimulationClock {
+    step: Duration,
+    accumulator: Duration,
+    steps: u32,
+    interpolation: f32
+}
+
+impl SimulationClock {
+    pub fn new(step: Duration) -> Self {
+        Self { step, accumulator: Duration::ZERO, steps: 0, interpolation: 0.0 }
+    }
+
+    // Accumulates frame time, whole steps are simulated in this frame and the rest is left for the next ones
+    pub fn advance(&mut self, frame_time: Duration) {
+        self.accumulator = (self.accumulator + frame_time).min(self.step * MAX_STEPS_PER_FRAME);
+
+        self.steps = (self.accumulator.as_nanos() / self.step.as_nanos()) as u32;
+        self.accumulator -= self.step * self.steps;
+
+        self.interpolation = self.accumulator.as_secs_f32() / self.step.as_secs_f32();
+    }
+
+    pub fn delta_time(&self) -> Duration {
+        self.step
+    }
+
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    // Part of the next step which is already passed, used to interpolate rendered state between steps
+    pub fn interpolation(&self) -> f32 {
+        self.interpolation
+    }
+}
@@ -0,0 +1,159 @@
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+use glm::{vec2, Vec2};
+use crate::game::ecs::component::direction_component::DirectionComponent;
+
+use crate::game::ecs::component::player_flag_component::PlayerFlagComponent;
+use crate::game::ecs::component::position_component::PositionComponent;
+use crate::game::ecs::component::previous_pose_component::PreviousPoseComponent;
+use crate::game::ecs::component::velocity_component::VelocityComponent;
+use crate::game::ecs::raycast_worlds::RaycastWorlds;
+use crate::game::model::raycast_world::RaycastWorld;
+
+pub const PLAYER_COLLISION_RADIUS: f32 = 0.1f32; // Tiles
+
+// Halvings of the step displacement while searching for the contact point
+const CONTACT_SEARCH_ITERATIONS: u32 = 8;
+
+pub struct CollisionResolvingSystem {
+    worlds: RaycastWorlds
+}
+
+impl CollisionResolvingSystem {
+
+    pub fn new(worlds: &RaycastWorlds) -> Self {
+        Self { worlds: worlds.clone() }
+    }
+}
+
+// Player moves along the step displacement up to the wall and slides along it with the rest of the displacement.
+// Velocity components stopped by the wall are cleared, so the player doesn't keep pushing into it
+fn resolve_collision(world: &dyn RaycastWorld, player_position: &mut Vec2, previous_position: Vec2, player_velocity: &mut Vec2) {
+    // Player already stuck in the geometry has no free position to return to, it is allowed to walk out
+    if !world.is_colliding(*player_position, PLAYER_COLLISION_RADIUS) || world.is_colliding(previous_position, PLAYER_COLLISION_RADIUS) {
+        return;
+    }
+
+    let displacement = *player_position - previous_position;
+    let contact = contact_fraction(world, previous_position, displacement);
+
+    let mut resolved_position = previous_position + displacement * contact;
+    let remaining_displacement = displacement * (1.0 - contact);
+
+    let slide_position = resolved_position + vec2(remaining_displacement.x, 0.0);
+
+    if !world.is_colliding(slide_position, PLAYER_COLLISION_RADIUS) {
+        resolved_position = slide_position;
+    } else if player_velocity.x * remaining_displacement.x > 0.0 {
+        player_velocity.x = 0.0;
+    }
+
+    let slide_position = resolved_position + vec2(0.0, remaining_displacement.y);
+
+    if !world.is_colliding(slide_position, PLAYER_COLLISION_RADIUS) {
+        resolved_position = slide_position;
+    } else if player_velocity.y * remaining_displacement.y > 0.0 {
+        player_velocity.y = 0.0;
+    }
+
+    *player_position = resolved_position;
+}
+
+// Positions of the entities colliding with the geometry. Only moving ones have velocity, items and keys don't block anything
+pub fn colliding_positions(manager: &EntityManager, accessor: &mut EntityIdAccessor) -> Vec<Vec2> {
+    accessor.borrow_ids_for_pair::<PositionComponent, VelocityComponent>(manager)
+        .into_iter()
+        .flatten()
+        .filter_map(|entity_id| manager.borrow_component::<PositionComponent>(*entity_id))
+        .map(|position_component| position_component.position)
+        .collect()
+}
+
+// Largest free fraction of the displacement from the free start position
+fn contact_fraction(world: &dyn RaycastWorld, start_position: Vec2, displacement: Vec2) -> f32 {
+    let (mut free, mut colliding) = (0.0f32, 1.0f32);
+
+    for _ in 0..CONTACT_SEARCH_ITERATIONS {
+        let middle = (free + colliding) / 2.0;
+
+        if world.is_colliding(start_position + displacement * middle, PLAYER_COLLISION_RADIUS) {
+            colliding = middle;
+        } else {
+            free = middle;
+        }
+    }
+
+    free
+}
+
+impl System for CollisionResolvingSystem {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let world = match self.worlds.compose(manager, accessor) {
+            None => return,
+            Some(world) => world
+        };
+
+        let player = accessor
+            .borrow_ids_for_triple::<PositionComponent, DirectionComponent, PlayerFlagComponent>(manager)
+            .and_then(|player_entities_ids| player_entities_ids.first())
+            .and_then(|player_id| manager.borrow_component_triple_mut::<PositionComponent, PreviousPoseComponent, VelocityComponent>(*player_id));
+
+        match player {
+            None => {}
+            Some((player_position, previous_pose, player_velocity)) =>
+                resolve_collision(world.as_ref(), &mut player_position.position, previous_pose.position, &mut player_velocity.velocity)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use glm::{vec2, Vec2};
+
+    use crate::game::model::object_color::ObjectColor;
+    use crate::game::model::tile::Tile;
+    use crate::game::model::tilemap::Tilemap;
+
+    use super::{resolve_collision, PLAYER_COLLISION_RADIUS};
+
+    // Open room with the wall column at x = 2
+    fn create_tilemap() -> Tilemap {
+        let empty = Arc::new(Tile::new(0, ObjectColor::WHITE, false));
+        let wall = Arc::new(Tile::new(1, ObjectColor::RED, true));
+
+        let raw_tilemap = (0..4)
+            .map(|_| (0..4).map(|x| if x == 2 { &wall } else { &empty }).collect())
+            .collect();
+
+        Tilemap::from_raw_tilemap(0, raw_tilemap)
+    }
+
+    fn resolve(previous_position: Vec2, position: Vec2, velocity: Vec2) -> (Vec2, Vec2) {
+        let (mut position, mut velocity) = (position, velocity);
+
+        resolve_collision(&create_tilemap(), &mut position, previous_position, &mut velocity);
+        (position, velocity)
+    }
+
+    #[test]
+    fn diagonal_move_into_wall_slides_along_it() {
+        let (position, velocity) = resolve(vec2(1.85, 1.5), vec2(1.95, 1.6), vec2(6.0, 6.0));
+
+        assert!(position.x <= 2.0 - PLAYER_COLLISION_RADIUS);
+        assert!(position.x > 1.85);
+        assert!((position.y - 1.6).abs() < 1e-5);
+        assert_eq!(velocity, vec2(0.0, 6.0));
+    }
+
+    #[test]
+    fn moving_away_from_facing_direction_is_resolved_along_the_movement() {
+        // Backpedalling or strafing doesn't depend on the facing direction, the player stays at the wall side it came from
+        let (position, velocity) = resolve(vec2(3.15, 1.5), vec2(3.05, 1.5), vec2(-6.0, 0.0));
+
+        assert!(position.x >= 3.0 + PLAYER_COLLISION_RADIUS);
+        assert!(position.x < 3.15);
+        assert_eq!(velocity, vec2(0.0, 0.0));
+    }
+}
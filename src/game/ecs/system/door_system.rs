@@ -0,0 +1,130 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+use glm::Vec2;
+
+use crate::game::ecs::component::door_component::{DoorComponent, DoorState};
+use crate::game::ecs::component::keys_component::KeysComponent;
+use crate::game::ecs::simulation_clock::SimulationClock;
+use crate::game::ecs::system::collision_resolving_system::{colliding_positions, PLAYER_COLLISION_RADIUS};
+use crate::game::ecs::use_action::{UseAction, UseTarget};
+use crate::game::model::linemap::distance_to_segment;
+
+// Opens doors used by the player, animates doors and closes them after a timeout
+pub struct DoorSystem {
+    use_action: Rc<RefCell<UseAction>>,
+    clock: Rc<RefCell<SimulationClock>>
+}
+
+impl DoorSystem {
+
+    pub fn new(use_action: &Rc<RefCell<UseAction>>, clock: &Rc<RefCell<SimulationClock>>) -> Self {
+        Self {
+            use_action: use_action.clone(),
+            clock: clock.clone()
+        }
+    }
+
+    fn use_door(&self, player_id: usize, door_id: usize, manager: &mut EntityManager) {
+        let has_required_key = {
+            let keys = manager.borrow_component::<KeysComponent>(player_id);
+            let required_key = manager.borrow_component::<DoorComponent>(door_id).and_then(|door| door.required_key());
+
+            match (required_key, keys) {
+                (Some(required_key), Some(keys)) => keys.has_key(required_key),
+                _ => false
+            }
+        };
+
+        let Some(door) = manager.borrow_component_mut::<DoorComponent>(door_id) else {
+            return;
+        };
+
+        if door.is_locked() {
+            if !has_required_key {
+                log::info!("Door {} is locked", door_id);
+                return;
+            }
+
+            log::info!("Door {} has been unlocked", door_id);
+            door.set_locked(false);
+        }
+
+        match door.state() {
+            DoorState::Closed | DoorState::Closing => door.set_state(DoorState::Opening),
+            DoorState::Open { .. } => door.set_state(DoorState::Closing),
+            DoorState::Opening => {}
+        }
+    }
+}
+
+impl System for DoorSystem {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let time_delta = self.clock.borrow().delta_time();
+
+        let door_ids = match accessor.borrow_ids::<DoorComponent>(manager) {
+            None => return,
+            Some(door_ids) => door_ids.clone()
+        };
+
+        if let Some((player_id, UseTarget::Door(door_id))) = self.use_action.borrow().used_target() {
+            self.use_door(player_id, door_id, manager);
+        }
+
+        let positions = colliding_positions(manager, accessor);
+
+        for door_id in door_ids {
+            if let Some(door) = manager.borrow_component_mut::<DoorComponent>(door_id) {
+                update_door(door, time_delta, &positions);
+            }
+        }
+    }
+}
+
+fn update_door(door: &mut DoorComponent, time_delta: Duration, positions: &[Vec2]) {
+    let open_fraction_delta = door.speed() * time_delta.as_secs_f32();
+
+    match door.state() {
+        DoorState::Closed => {}
+
+        DoorState::Opening => {
+            door.set_open_fraction(door.open_fraction() + open_fraction_delta);
+
+            if door.open_fraction() >= 1.0 {
+                door.set_state(DoorState::Open { elapsed: Duration::ZERO });
+            }
+        }
+
+        DoorState::Open { elapsed } => {
+            let elapsed = elapsed + time_delta;
+
+            if elapsed >= door.auto_close_delay() && !is_door_blocked(door, positions) {
+                door.set_state(DoorState::Closing);
+            } else {
+                door.set_state(DoorState::Open { elapsed });
+            }
+        }
+
+        DoorState::Closing => {
+            // Door never closes on someone, it opens again instead
+            if is_door_blocked(door, positions) {
+                door.set_state(DoorState::Opening);
+                return;
+            }
+
+            door.set_open_fraction(door.open_fraction() - open_fraction_delta);
+
+            if door.open_fraction() <= 0.0 {
+                door.set_state(DoorState::Closed);
+            }
+        }
+    }
+}
+
+fn is_door_blocked(door: &DoorComponent, positions: &[Vec2]) -> bool {
+    let (from, to) = door.closed_segment();
+    positions.iter().any(|position| distance_to_segment(from, to, *position) <= PLAYER_COLLISION_RADIUS)
+}
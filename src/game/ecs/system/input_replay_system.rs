@@ -0,0 +1,43 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+
+use crate::game::ecs::component::direction_component::DirectionComponent;
+use crate::game::ecs::component::player_flag_component::PlayerFlagComponent;
+use crate::game::ecs::component::position_component::PositionComponent;
+use crate::game::input::input_state::InputState;
+use crate::game::input::replay::{InputReplay, ReplayPose};
+
+// Records or plays the gameplay input of every simulation step, must run before the input reading systems
+pub struct InputReplaySystem {
+    replay: Rc<RefCell<InputReplay>>,
+    input: Rc<RefCell<InputState>>
+}
+
+impl InputReplaySystem {
+    pub fn new(replay: &Rc<RefCell<InputReplay>>, input: &Rc<RefCell<InputState>>) -> Self {
+        Self {
+            replay: replay.clone(),
+            input: input.clone()
+        }
+    }
+}
+
+impl System for InputReplaySystem {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let player_id = accessor
+            .borrow_ids_for_triple::<PositionComponent, DirectionComponent, PlayerFlagComponent>(manager)
+            .and_then(|player_ids| player_ids.first().copied());
+
+        let pose = player_id.and_then(|player_id| {
+            let position = manager.borrow_component::<PositionComponent>(player_id)?.position;
+            let direction = manager.borrow_component::<DirectionComponent>(player_id)?.direction;
+
+            Some(ReplayPose { position, direction })
+        });
+
+        self.replay.borrow_mut().step(&mut self.input.borrow_mut(), pose);
+    }
+}
@@ -1,2 +1,12 @@
 pub mod moving_system;
-pub mod tilemap_collision_resolving_system;
+pub mod collision_resolving_system;
+pub mod door_system;
+pub mod moving_geometry_system;
+pub mod trigger_system;
+pub mod script_timer_system;
+pub mod pose_history_system;
+pub mod vertical_moving_system;
+pub mod rumble_system;
+pub mod input_replay_system;
+pub mod save_system;
+pub mod use_system;
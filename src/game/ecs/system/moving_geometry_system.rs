@@ -0,0 +1,321 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+use glm::{uvec2, vec2, UVec2, Vec2};
+
+use crate::game::ecs::component::moving_segment_component::{MovingSegmentComponent, MovingSegmentState};
+use crate::game::ecs::component::push_wall_component::{PushWallComponent, PushWallState};
+use crate::game::ecs::simulation_clock::SimulationClock;
+use crate::game::ecs::system::collision_resolving_system::{colliding_positions, PLAYER_COLLISION_RADIUS};
+use crate::game::ecs::use_action::{UseAction, UseTarget};
+use crate::game::game_state::Repositories;
+use crate::game::model::linemap::{distance_to_segment, Linemap};
+use crate::game::model::repository::Repository;
+use crate::game::model::tile::{Tile, TileEdge};
+use crate::game::model::tilemap::Tilemap;
+
+// Moves push-walls and linemap segments through the maps data, so ray casting and collision see every intermediate position
+pub struct MovingGeometrySystem {
+    use_action: Rc<RefCell<UseAction>>,
+    tiles_repository: Rc<RefCell<Repository<Tile>>>,
+    tilemap_repository: Rc<RefCell<Repository<Tilemap>>>,
+    linemap_repository: Rc<RefCell<Repository<Linemap>>>,
+    clock: Rc<RefCell<SimulationClock>>
+}
+
+impl MovingGeometrySystem {
+
+    pub fn new(use_action: &Rc<RefCell<UseAction>>, repositories: &Repositories, clock: &Rc<RefCell<SimulationClock>>) -> Self {
+        Self {
+            use_action: use_action.clone(),
+            tiles_repository: repositories.tiles_repository().clone(),
+            tilemap_repository: repositories.tilemap_repository().clone(),
+            linemap_repository: repositories.linemap_repository().clone(),
+            clock: clock.clone()
+        }
+    }
+
+    fn update_push_wall(&self, push_wall_id: usize, push_wall: &mut PushWallComponent, time_delta: Duration, positions: &[Vec2]) {
+        let state = match push_wall.state() {
+            PushWallState::Idle | PushWallState::Finished => return,
+            PushWallState::Triggered => self.start_push_wall(push_wall_id, push_wall),
+            PushWallState::Moving { tile, distance, travelled } => {
+                let travelled = (travelled + push_wall.speed() * time_delta.as_secs_f32()).min(*distance as f32);
+                let next_position = cell_origin(push_wall.cell()) + push_wall.direction().direction() * travelled;
+
+                // Wall never moves onto someone, it waits until the way is free
+                if positions.iter().any(|position| distance_to_cell_at(next_position, *position) <= PLAYER_COLLISION_RADIUS) {
+                    return;
+                }
+
+                self.move_push_wall(push_wall_id, push_wall, tile.clone(), *distance, travelled)
+            }
+        };
+
+        push_wall.set_state(state);
+    }
+
+    // Replaces wall tile of the cell with the moving tile
+    fn start_push_wall(&self, push_wall_id: usize, push_wall: &PushWallComponent) -> PushWallState {
+        let empty_tile = match self.tiles_repository.borrow().get_resource(&push_wall.empty_tile()) {
+            None => {
+                log::warn!("Empty tile {} of push-wall {} is not found", push_wall.empty_tile(), push_wall_id);
+                return PushWallState::Finished;
+            }
+            Some(empty_tile) => empty_tile.clone()
+        };
+
+        let mut tilemap_repository = self.tilemap_repository.borrow_mut();
+
+        let tilemap = match tilemap_repository.get_resource_mut(&push_wall.tilemap()) {
+            None => {
+                log::warn!("Tilemap {} of push-wall {} is not found", push_wall.tilemap(), push_wall_id);
+                return PushWallState::Finished;
+            }
+            Some(tilemap) => tilemap
+        };
+
+        let tile = match tilemap.get_tile(push_wall.cell()) {
+            None => return PushWallState::Finished,
+            Some(placed_tile) => placed_tile.tile().clone()
+        };
+
+        // Wall stops before the first solid cell
+        let distance = (1..=push_wall.distance())
+            .take_while(|step| neighbour_cell(push_wall.cell(), push_wall.direction(), *step)
+                .and_then(|cell| tilemap.get_tile(cell))
+                .is_some_and(|placed_tile| !placed_tile.tile().is_collision_enabled()))
+            .count() as u32;
+
+        if distance == 0 {
+            log::info!("Push-wall {} is blocked", push_wall_id);
+            return PushWallState::Idle;
+        }
+
+        tilemap.set_tile(push_wall.cell(), &empty_tile);
+        tilemap.set_moving_tile(push_wall_id, &tile, cell_origin(push_wall.cell()));
+
+        log::info!("Push-wall {} starts moving on {} tiles", push_wall_id, distance);
+
+        PushWallState::Moving { tile, distance, travelled: 0.0 }
+    }
+
+    fn move_push_wall(&self, push_wall_id: usize, push_wall: &PushWallComponent, tile: Arc<Tile>, distance: u32, travelled: f32) -> PushWallState {
+        let mut tilemap_repository = self.tilemap_repository.borrow_mut();
+
+        let tilemap = match tilemap_repository.get_resource_mut(&push_wall.tilemap()) {
+            None => return PushWallState::Finished,
+            Some(tilemap) => tilemap
+        };
+
+        if travelled < distance as f32 {
+            let position = cell_origin(push_wall.cell()) + push_wall.direction().direction() * travelled;
+            tilemap.set_moving_tile(push_wall_id, &tile, position);
+
+            return PushWallState::Moving { tile, distance, travelled };
+        }
+
+        tilemap.remove_moving_tile(push_wall_id);
+
+        if let Some(destination) = neighbour_cell(push_wall.cell(), push_wall.direction(), distance) {
+            tilemap.set_tile(destination, &tile);
+        }
+
+        PushWallState::Finished
+    }
+
+    fn update_moving_segment(&self, segment: &mut MovingSegmentComponent, time_delta: Duration, positions: &[Vec2]) {
+        let mut linemap_repository = self.linemap_repository.borrow_mut();
+
+        let linemap = match linemap_repository.get_resource_mut(&segment.linemap()) {
+            None => return,
+            Some(linemap) => linemap
+        };
+
+        let state = match segment.state() {
+            MovingSegmentState::Idle | MovingSegmentState::Finished => return,
+
+            MovingSegmentState::Triggered => match linemap.lines().get(segment.line_index()) {
+                None => MovingSegmentState::Finished,
+                Some(line) => MovingSegmentState::Moving { from: *line.from(), to: *line.to(), travelled: 0.0 }
+            },
+
+            MovingSegmentState::Moving { from, to, travelled } => {
+                let offset_length = glm::length(segment.offset());
+                let travelled = (travelled + segment.speed() * time_delta.as_secs_f32()).min(offset_length);
+                let offset = segment.offset() * (travelled / offset_length.max(f32::EPSILON));
+                let (next_from, next_to) = (*from + offset, *to + offset);
+
+                // Segment never moves onto someone, it waits until the way is free
+                if positions.iter().any(|position| distance_to_segment(next_from, next_to, *position) <= PLAYER_COLLISION_RADIUS) {
+                    return;
+                }
+
+                linemap.set_line(segment.line_index(), next_from, next_to);
+
+                if travelled < offset_length {
+                    MovingSegmentState::Moving { from: *from, to: *to, travelled }
+                } else {
+                    MovingSegmentState::Finished
+                }
+            }
+        };
+
+        segment.set_state(state);
+    }
+}
+
+impl System for MovingGeometrySystem {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let time_delta = self.clock.borrow().delta_time();
+
+        let push_wall_ids = accessor.borrow_ids::<PushWallComponent>(manager).cloned().unwrap_or_default();
+        let segment_ids = accessor.borrow_ids::<MovingSegmentComponent>(manager).cloned().unwrap_or_default();
+
+        match self.use_action.borrow().used_target() {
+            Some((_, UseTarget::PushWall(push_wall_id))) => {
+                if let Some(push_wall) = manager.borrow_component_mut::<PushWallComponent>(push_wall_id) {
+                    push_wall.trigger();
+                }
+            }
+
+            Some((_, UseTarget::MovingSegment(segment_id))) => {
+                if let Some(segment) = manager.borrow_component_mut::<MovingSegmentComponent>(segment_id) {
+                    segment.trigger();
+                }
+            }
+
+            _ => {}
+        }
+
+        let positions = colliding_positions(manager, accessor);
+
+        for push_wall_id in push_wall_ids {
+            if let Some(push_wall) = manager.borrow_component_mut::<PushWallComponent>(push_wall_id) {
+                self.update_push_wall(push_wall_id, push_wall, time_delta, &positions);
+            }
+        }
+
+        for segment_id in segment_ids {
+            if let Some(segment) = manager.borrow_component_mut::<MovingSegmentComponent>(segment_id) {
+                self.update_moving_segment(segment, time_delta, &positions);
+            }
+        }
+    }
+}
+
+fn cell_origin(cell: UVec2) -> Vec2 {
+    vec2(cell.x as f32, cell.y as f32)
+}
+
+// Distance from the point to the cell sized square at the given origin
+fn distance_to_cell_at(origin: Vec2, point: Vec2) -> f32 {
+    let nearest_point = vec2(point.x.clamp(origin.x, origin.x + 1.0), point.y.clamp(origin.y, origin.y + 1.0));
+    glm::distance(nearest_point, point)
+}
+
+fn neighbour_cell(cell: UVec2, direction: TileEdge, step: u32) -> Option<UVec2> {
+    let position = cell_origin(cell) + direction.direction() * step as f32;
+
+    if position.x < 0.0 || position.y < 0.0 {
+        return None;
+    }
+
+    Some(uvec2(position.x as u32, position.y as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::iter;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use glm::{uvec2, vec2};
+
+    use crate::game::ecs::component::moving_segment_component::{MovingSegmentComponent, MovingSegmentState};
+    use crate::game::ecs::component::push_wall_component::{PushWallComponent, PushWallState};
+    use crate::game::ecs::simulation_clock::{SimulationClock, SIMULATION_STEP};
+    use crate::game::ecs::system::collision_resolving_system::PLAYER_COLLISION_RADIUS;
+    use crate::game::ecs::use_action::UseAction;
+    use crate::game::game_state::Repositories;
+    use crate::game::input::bindings::Bindings;
+    use crate::game::input::input_state::InputState;
+    use crate::game::launch_options::LaunchOptions;
+    use crate::game::model::linemap::{distance_to_segment, Linemap};
+    use crate::game::model::object_color::ObjectColor;
+    use crate::game::model::tile::{Tile, TileEdge};
+    use crate::game::model::tilemap::Tilemap;
+
+    use super::MovingGeometrySystem;
+
+    const STEPS_COUNT: u32 = 120;
+
+    fn create_system(repositories: &Repositories) -> MovingGeometrySystem {
+        let options = LaunchOptions::parse(iter::empty()).unwrap();
+        let input = Rc::new(RefCell::new(InputState::new(Bindings::default(), options.mouse_settings, options.gamepad_settings)));
+        let clock = Rc::new(RefCell::new(SimulationClock::new(SIMULATION_STEP)));
+
+        MovingGeometrySystem::new(&Rc::new(RefCell::new(UseAction::new(&input))), repositories, &clock)
+    }
+
+    #[test]
+    fn push_wall_waits_while_it_would_move_onto_someone() {
+        let repositories = Repositories::new();
+
+        let empty = Arc::new(Tile::new(0, ObjectColor::WHITE, false));
+        let wall = Arc::new(Tile::new(1, ObjectColor::RED, true));
+        repositories.tiles_repository().borrow_mut().register_resource(empty.clone()).register_resource(wall.clone());
+
+        let tilemap = Tilemap::from_raw_tilemap(0, vec![vec![&wall, &empty, &empty]]);
+        repositories.tilemap_repository().borrow_mut().register_resource(Arc::new(tilemap));
+
+        let system = create_system(&repositories);
+        let positions = [vec2(1.5, 0.5)];
+
+        let mut push_wall = PushWallComponent::new(0, uvec2(0, 0), TileEdge::East, 2, 0);
+        push_wall.trigger();
+
+        for _ in 0..STEPS_COUNT {
+            system.update_push_wall(0, &mut push_wall, SIMULATION_STEP, &positions);
+        }
+
+        assert!(matches!(push_wall.state(), PushWallState::Moving { .. }));
+
+        let tilemap_repository = repositories.tilemap_repository().borrow();
+        let moving_tile = tilemap_repository.get_resource(&0).unwrap().moving_tiles().next().unwrap();
+
+        assert!(moving_tile.distance_to(positions[0]) > PLAYER_COLLISION_RADIUS);
+    }
+
+    #[test]
+    fn moving_segment_waits_while_it_would_move_onto_someone() {
+        let repositories = Repositories::new();
+
+        let mut linemap = Linemap::new(0);
+        linemap.add_line(ObjectColor::RED, vec2(1.0, 0.0), vec2(1.0, 2.0));
+        repositories.linemap_repository().borrow_mut().register_resource(Arc::new(linemap));
+
+        let system = create_system(&repositories);
+        let positions = [vec2(2.0, 1.0)];
+
+        let mut segment = MovingSegmentComponent::new(0, 0, vec2(2.0, 0.0));
+        segment.trigger();
+
+        for _ in 0..STEPS_COUNT {
+            system.update_moving_segment(&mut segment, SIMULATION_STEP, &positions);
+        }
+
+        assert!(matches!(segment.state(), MovingSegmentState::Moving { .. }));
+
+        let linemap_repository = repositories.linemap_repository().borrow();
+        let line = &linemap_repository.get_resource(&0).unwrap().lines()[0];
+
+        assert!(line.from().x > 1.0);
+        assert!(distance_to_segment(*line.from(), *line.to(), positions[0]) > PLAYER_COLLISION_RADIUS);
+    }
+}
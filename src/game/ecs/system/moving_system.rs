@@ -2,65 +2,112 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
 use ecs_rust::system::System;
+use glm::{vec2, Vec2};
 use num_traits::Zero;
-use sdl2::EventPump;
-use sdl2::keyboard::Scancode;
 use crate::game::ecs::component::direction_component::DirectionComponent;
 use crate::game::ecs::component::player_flag_component::PlayerFlagComponent;
 use crate::game::ecs::component::position_component::PositionComponent;
+use crate::game::ecs::component::velocity_component::VelocityComponent;
+use crate::game::ecs::component::vertical_motion_component::VerticalMotionComponent;
+use crate::game::ecs::simulation_clock::SimulationClock;
+use crate::game::input::input_action::InputAction;
+use crate::game::input::input_state::InputState;
 
-const PLAYER_MOVING_SPEED: f32 = 0.05f32; // Tiles
-const PLAYER_ROTATION_SPEED: f32 = 0.087f32; // Radians (~5 deg)
+const PLAYER_MOVING_SPEED: f32 = 1.5f32; // Tiles per second
+const PLAYER_ACCELERATION: f32 = 10.0f32; // Tiles per second squared
+const PLAYER_FRICTION: f32 = 8.0f32; // Tiles per second squared, slows the player down without moving input
+const PLAYER_ROTATION_SPEED: f32 = 2.6f32; // Radians per second (~150 deg)
+const PLAYER_PITCH_SPEED: f32 = 1.2f32; // Radians per second
+
+// Y-shearing distorts the view at larger angles
+const MAX_PITCH: f32 = 0.45f32; // Radians (~25 deg)
+
+// Crouching player moves slower
+const CROUCHING_SPEED_FACTOR: f32 = 0.5f32;
 
 pub struct MovingSystem {
-    event_pump: Rc<RefCell<EventPump>>
+    input: Rc<RefCell<InputState>>,
+    clock: Rc<RefCell<SimulationClock>>
 }
 
 // Can be decomposed
 
 impl MovingSystem {
 
-    pub fn new(event_pump: &Rc<RefCell<EventPump>>) -> Self {
+    pub fn new(input: &Rc<RefCell<InputState>>, clock: &Rc<RefCell<SimulationClock>>) -> Self {
         Self {
-            event_pump: event_pump.clone()
+            input: input.clone(),
+            clock: clock.clone()
         }
     }
 
-    pub fn move_player(&self, entity_id: &usize, manager: &mut EntityManager) {
-        match manager.borrow_component_pair_mut::<PositionComponent, DirectionComponent>(*entity_id) {
-            None => {}
-            Some((position_component, direction_component)) => {
-                self.handle_player_moving(position_component, direction_component);
+    pub fn move_player(&self, entity_id: usize, manager: &mut EntityManager) {
+        let delta_time = self.clock.borrow().delta_time().as_secs_f32();
+        let mut input = self.input.borrow_mut();
+
+        // Turning keys and mouse look
+        let look_delta = input.take_look_delta();
+
+        let rotation_difference = input.action_axis(InputAction::TurnLeft, InputAction::TurnRight) * PLAYER_ROTATION_SPEED * delta_time + look_delta.x;
+        let pitch_difference = input.action_axis(InputAction::LookDown, InputAction::LookUp) * PLAYER_PITCH_SPEED * delta_time + look_delta.y;
+
+        let direction = match manager.borrow_component_mut::<DirectionComponent>(entity_id) {
+            None => return,
+            Some(direction_component) => {
+                if !rotation_difference.is_zero() {
+                    direction_component.direction = direction_component.direction + rotation_difference;
+                }
+
+                direction_component.pitch = (direction_component.pitch + pitch_difference).clamp(-MAX_PITCH, MAX_PITCH);
+                direction_component.direction
             }
-        }
-    }
-    fn handle_player_moving(&self, position_component: &mut PositionComponent, direction_component: &mut DirectionComponent) {
-        let mut moving_difference = 0.0f32;
-        let mut rotation_difference = 0.0f32;
-
-        for scancode in self.event_pump.borrow_mut().keyboard_state().pressed_scancodes() {
-            match scancode {
-                Scancode::A => { rotation_difference -= PLAYER_ROTATION_SPEED }
-                Scancode::D => { rotation_difference += PLAYER_ROTATION_SPEED }
-                Scancode::W => { moving_difference += PLAYER_MOVING_SPEED }
-                Scancode::S => { moving_difference -= PLAYER_MOVING_SPEED }
-
-                _ => {}
+        };
+
+        // Right side is clockwise from the direction, as the screen y axis goes down
+        let forward = vec2(direction.cos(), direction.sin());
+        let right = vec2(-direction.sin(), direction.cos());
+
+        let moving_input = forward * input.action_axis(InputAction::MoveBackward, InputAction::MoveForward)
+            + right * input.action_axis(InputAction::StrafeLeft, InputAction::StrafeRight);
+
+        // Diagonal moving is not faster, weaker analog input gives lower speed
+        let moving_input_length = glm::length(moving_input);
+        let moving_input = if moving_input_length > 1.0 { moving_input / moving_input_length } else { moving_input };
+
+        let crouching = manager.borrow_component::<VerticalMotionComponent>(entity_id)
+            .map(|vertical_motion| vertical_motion.crouching)
+            .unwrap_or(0.0);
+
+        let moving_input = moving_input * (1.0 - (1.0 - CROUCHING_SPEED_FACTOR) * crouching);
+
+        let velocity = match manager.borrow_component_mut::<VelocityComponent>(entity_id) {
+            None => return,
+            Some(velocity_component) => {
+                velocity_component.velocity = accelerate(velocity_component.velocity, moving_input, delta_time);
+                velocity_component.velocity
             }
-        }
+        };
 
-        if !rotation_difference.is_zero() {
-            direction_component.direction = direction_component.direction + rotation_difference;
+        if let Some(position_component) = manager.borrow_component_mut::<PositionComponent>(entity_id) {
+            position_component.position = position_component.position + velocity * delta_time;
         }
+    }
+}
 
-        if !moving_difference.is_zero() {
-            position_component.position.x = position_component.position.x + direction_component.direction.cos() * moving_difference;
-            position_component.position.y = position_component.position.y + direction_component.direction.sin() * moving_difference;
-        }
+// Velocity approaches the target velocity with the acceleration, or stops with the friction without moving input
+fn accelerate(velocity: Vec2, moving_input: Vec2, delta_time: f32) -> Vec2 {
+    let is_moving = glm::length(moving_input) > 0.0;
+
+    let target_velocity = moving_input * PLAYER_MOVING_SPEED;
+    let maximal_change = if is_moving { PLAYER_ACCELERATION } else { PLAYER_FRICTION } * delta_time;
+
+    let velocity_change = target_velocity - velocity;
+    let velocity_change_length = glm::length(velocity_change);
 
-        // info!("Dif: rotation {}; moving {}", rotation_difference, moving_difference);
-        // info!("Direction {} {}", direction_component.direction.x, direction_component.direction.y);
-        // info!("Position {} {}", position_component.position.x, position_component.position.y);
+    if velocity_change_length <= maximal_change {
+        target_velocity
+    } else {
+        velocity + velocity_change * (maximal_change / velocity_change_length)
     }
 }
 
@@ -68,8 +115,7 @@ impl System for MovingSystem {
     fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
         let player_id = accessor
             .borrow_ids_for_triple::<PositionComponent, DirectionComponent, PlayerFlagComponent>(manager)
-            .map(|player_entities_ids| player_entities_ids.get(0))
-            .flatten();
+            .and_then(|player_entities_ids| player_entities_ids.first().copied());
 
         match player_id {
             None => {}
@@ -0,0 +1,43 @@
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+
+use crate::game::ecs::component::direction_component::DirectionComponent;
+use crate::game::ecs::component::position_component::PositionComponent;
+use crate::game::ecs::component::previous_pose_component::PreviousPoseComponent;
+use crate::game::ecs::component::vertical_motion_component::{VerticalMotionComponent, STANDING_EYE_HEIGHT};
+
+// Remembers poses before the simulation step, must be the first simulation system
+pub struct PoseHistorySystem {
+}
+
+impl PoseHistorySystem {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl System for PoseHistorySystem {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let entity_ids = match accessor.borrow_ids_for_triple::<PositionComponent, DirectionComponent, PreviousPoseComponent>(manager) {
+            None => return,
+            Some(entity_ids) => entity_ids.clone()
+        };
+
+        for entity_id in entity_ids {
+            let position = manager.borrow_component::<PositionComponent>(entity_id).unwrap().position;
+            let direction_component = manager.borrow_component::<DirectionComponent>(entity_id).unwrap();
+            let (direction, pitch) = (direction_component.direction, direction_component.pitch);
+
+            let eye_height = manager.borrow_component::<VerticalMotionComponent>(entity_id)
+                .map(|vertical_motion| vertical_motion.eye_height())
+                .unwrap_or(STANDING_EYE_HEIGHT);
+
+            if let Some(previous_pose) = manager.borrow_component_mut::<PreviousPoseComponent>(entity_id) {
+                previous_pose.position = position;
+                previous_pose.direction = direction;
+                previous_pose.pitch = pitch;
+                previous_pose.eye_height = eye_height;
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+
+use crate::game::ecs::component::health_component::HealthComponent;
+use crate::game::ecs::component::player_flag_component::PlayerFlagComponent;
+use crate::game::input::gamepads::Gamepads;
+
+const RUMBLE_DURATION: Duration = Duration::from_millis(250);
+
+// Weakest rumble is still noticeable for a small damage
+const MIN_RUMBLE_STRENGTH: f32 = 0.3;
+
+// Rumbles game controllers when the player is damaged, stronger for a bigger part of the health
+pub struct RumbleSystem {
+    gamepads: Rc<RefCell<Gamepads>>
+}
+
+impl RumbleSystem {
+    pub fn new(gamepads: &Rc<RefCell<Gamepads>>) -> Self {
+        Self { gamepads: gamepads.clone() }
+    }
+}
+
+impl System for RumbleSystem {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let Some(player_id) = accessor
+            .borrow_ids_for_pair::<HealthComponent, PlayerFlagComponent>(manager)
+            .and_then(|player_ids| player_ids.first().copied()) else {
+            return;
+        };
+
+        let Some(health) = manager.borrow_component_mut::<HealthComponent>(player_id) else {
+            return;
+        };
+
+        let damage = health.take_recent_damage();
+
+        if damage > 0 {
+            let damage_part = damage as f32 / health.max_health().max(1) as f32;
+            self.gamepads.borrow_mut().rumble(damage_part.max(MIN_RUMBLE_STRENGTH), RUMBLE_DURATION);
+        }
+    }
+}
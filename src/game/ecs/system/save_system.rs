@@ -0,0 +1,88 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+
+use crate::game::game_state::Repositories;
+use crate::game::save::save_game::{write_save, SaveGame, SaveHeader};
+use crate::game::save::save_service::{SaveRequest, SaveService};
+use crate::game::save::saved_components::SavedComponents;
+use crate::game::script::level_script::LevelScript;
+
+// Saves and loads the game between the simulation steps, so the saved state is never half updated
+pub struct SaveSystem {
+    save_service: Rc<RefCell<SaveService>>,
+    components: SavedComponents,
+    header: SaveHeader,
+    repositories: Repositories,
+    level_script: Option<Rc<RefCell<LevelScript>>>
+}
+
+impl SaveSystem {
+    pub fn new(save_service: &Rc<RefCell<SaveService>>, components: SavedComponents, header: SaveHeader, repositories: &Repositories, level_script: Option<&Rc<RefCell<LevelScript>>>) -> Self {
+        Self {
+            save_service: save_service.clone(),
+            components,
+            header,
+            repositories: repositories.clone(),
+            level_script: level_script.cloned()
+        }
+    }
+
+    fn save(&self, slot: &str, manager: &EntityManager, accessor: &mut EntityIdAccessor) -> String {
+        let path = self.save_service.borrow().slot_path(slot);
+
+        match write_save(&path, &self.header, manager, accessor, &self.components, &self.repositories, self.level_script.as_ref()) {
+            Ok(()) => {
+                log::info!("Game has been saved to {}", path.display());
+                format!("Game saved to slot {}", slot)
+            }
+
+            Err(error) => {
+                log::error!("Unable to save game {}: {}", path.display(), error);
+                format!("Unable to save slot {}", slot)
+            }
+        }
+    }
+
+    fn load(&self, slot: &str, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) -> String {
+        let path = self.save_service.borrow().slot_path(slot);
+
+        if !path.exists() {
+            return format!("Slot {} is empty", slot);
+        }
+
+        let current_entity_ids_bound = self.components.entity_ids(manager, accessor).last().map_or(0, |entity_id| entity_id + 1);
+
+        match SaveGame::read(&path, &self.header, &self.components, &self.repositories, self.level_script.as_ref(), current_entity_ids_bound) {
+            Ok(save_game) => {
+                save_game.apply(manager, accessor, &self.components, &self.repositories, self.level_script.as_ref());
+
+                log::info!("Game has been loaded from {}", path.display());
+                format!("Game loaded from slot {}", slot)
+            }
+
+            // Game is not changed by the broken save
+            Err(error) => {
+                log::error!("Unable to load game {}: {}", path.display(), error);
+                format!("Unable to load slot {}: {}", slot, error)
+            }
+        }
+    }
+}
+
+impl System for SaveSystem {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let Some(request) = self.save_service.borrow_mut().take_request() else {
+            return;
+        };
+
+        let message = match request {
+            SaveRequest::Save(slot) => self.save(&slot, manager, accessor),
+            SaveRequest::Load(slot) => self.load(&slot, manager, accessor)
+        };
+
+        self.save_service.borrow_mut().push_message(message);
+    }
+}
@@ -0,0 +1,54 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+
+use crate::game::ecs::component::player_flag_component::PlayerFlagComponent;
+use crate::game::ecs::simulation_clock::SimulationClock;
+use crate::game::event::game_event::{EventDispatcher, GameEvent};
+use crate::game::script::level_script::LevelScript;
+
+const LEVEL_STARTED_EVENT: &str = "level_started";
+
+// Fires "level_started" event once and events of the level script timers
+pub struct ScriptTimerSystem {
+    script: Rc<RefCell<LevelScript>>,
+    dispatcher: Rc<RefCell<EventDispatcher>>,
+    is_level_started: bool,
+    clock: Rc<RefCell<SimulationClock>>
+}
+
+impl ScriptTimerSystem {
+
+    pub fn new(script: &Rc<RefCell<LevelScript>>, dispatcher: &Rc<RefCell<EventDispatcher>>, clock: &Rc<RefCell<SimulationClock>>) -> Self {
+        Self {
+            script: script.clone(),
+            dispatcher: dispatcher.clone(),
+            is_level_started: false,
+            clock: clock.clone()
+        }
+    }
+}
+
+impl System for ScriptTimerSystem {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let time_delta = self.clock.borrow().delta_time();
+
+        let player_id = match accessor.borrow_ids::<PlayerFlagComponent>(manager).and_then(|ids| ids.first()) {
+            None => return,
+            Some(player_id) => *player_id
+        };
+
+        let mut dispatcher = self.dispatcher.borrow_mut();
+
+        if !self.is_level_started {
+            self.is_level_started = true;
+            dispatcher.push_event(GameEvent::new(LEVEL_STARTED_EVENT, player_id));
+        }
+
+        for event_name in self.script.borrow_mut().advance_timers(time_delta) {
+            dispatcher.push_event(GameEvent::new(&event_name, player_id));
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+
+use crate::game::ecs::component::trigger_component::TriggerComponent;
+use crate::game::ecs::use_action::{find_player_use_line, UseAction, UseTarget};
+use crate::game::event::game_event::{EventDispatcher, GameEvent};
+use crate::game::game_state::Repositories;
+
+// Fires trigger events and delivers all queued game events to their handlers
+pub struct TriggerSystem {
+    use_action: Rc<RefCell<UseAction>>,
+    dispatcher: Rc<RefCell<EventDispatcher>>,
+    repositories: Repositories
+}
+
+impl TriggerSystem {
+
+    pub fn new(use_action: &Rc<RefCell<UseAction>>, dispatcher: &Rc<RefCell<EventDispatcher>>, repositories: &Repositories) -> Self {
+        Self {
+            use_action: use_action.clone(),
+            dispatcher: dispatcher.clone(),
+            repositories: repositories.clone()
+        }
+    }
+}
+
+impl System for TriggerSystem {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let used_target = self.use_action.borrow().used_target();
+        let trigger_ids = accessor.borrow_ids::<TriggerComponent>(manager).cloned().unwrap_or_default();
+
+        if let Some((player_id, position, _)) = find_player_use_line(manager, accessor) {
+            let mut dispatcher = self.dispatcher.borrow_mut();
+
+            for trigger_id in trigger_ids {
+                let trigger = match manager.borrow_component_mut::<TriggerComponent>(trigger_id) {
+                    None => continue,
+                    Some(trigger) => trigger
+                };
+
+                let is_player_inside = trigger.region().contains(position);
+
+                let event_name = match (trigger.is_player_inside(), is_player_inside) {
+                    (false, true) => trigger.enter_event(),
+                    (true, false) => trigger.exit_event(),
+                    _ => None
+                };
+
+                if let Some(event_name) = event_name {
+                    dispatcher.push_event(GameEvent::new(event_name, player_id));
+                }
+
+                let is_used = used_target == Some((player_id, UseTarget::Trigger(trigger_id)));
+
+                if let (true, Some(event_name)) = (is_used, trigger.use_event()) {
+                    dispatcher.push_event(GameEvent::new(event_name, player_id));
+                }
+
+                trigger.set_player_inside(is_player_inside);
+            }
+        }
+
+        self.dispatcher.borrow_mut().dispatch(manager, &self.repositories);
+    }
+}
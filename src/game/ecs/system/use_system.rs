@@ -0,0 +1,122 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+use glm::{uvec2, Vec2};
+
+use crate::game::ecs::component::door_component::DoorComponent;
+use crate::game::ecs::component::moving_segment_component::MovingSegmentComponent;
+use crate::game::ecs::component::push_wall_component::PushWallComponent;
+use crate::game::ecs::component::trigger_component::TriggerComponent;
+use crate::game::ecs::use_action::{find_player_use_line, UseAction, UseTarget};
+use crate::game::game_state::Repositories;
+use crate::game::model::linemap::{Line, Linemap};
+use crate::game::model::repository::Repository;
+
+const USE_LINE_STEP: f32 = 0.05f32; // Tiles
+
+// Resolves the "use" press to the nearest usable entity in front of the player, must run before the systems activating them
+pub struct UseSystem {
+    use_action: Rc<RefCell<UseAction>>,
+    linemap_repository: Rc<RefCell<Repository<Linemap>>>
+}
+
+impl UseSystem {
+    pub fn new(use_action: &Rc<RefCell<UseAction>>, repositories: &Repositories) -> Self {
+        Self {
+            use_action: use_action.clone(),
+            linemap_repository: repositories.linemap_repository().clone()
+        }
+    }
+
+    // Targets crossed by the use line with distances from the player
+    fn find_targets(&self, use_line: (Vec2, Vec2), manager: &EntityManager, accessor: &mut EntityIdAccessor) -> Vec<(UseTarget, f32)> {
+        let (position, use_target) = use_line;
+        let mut targets = vec![];
+
+        for door_id in accessor.borrow_ids::<DoorComponent>(manager).cloned().unwrap_or_default() {
+            let Some(door) = manager.borrow_component::<DoorComponent>(door_id) else {
+                continue;
+            };
+
+            let (from, to) = door.closed_segment();
+
+            if let Some(intersection) = Line::find_intersection(&Line::new(*door.color(), position, use_target), &Line::new(*door.color(), from, to)) {
+                targets.push((UseTarget::Door(door_id), glm::distance(position, intersection)));
+            }
+        }
+
+        let linemap_repository = self.linemap_repository.borrow();
+
+        for segment_id in accessor.borrow_ids::<MovingSegmentComponent>(manager).cloned().unwrap_or_default() {
+            let line = manager.borrow_component::<MovingSegmentComponent>(segment_id)
+                .and_then(|segment| linemap_repository.get_resource(&segment.linemap())?.lines().get(segment.line_index()));
+
+            let Some(line) = line else {
+                continue;
+            };
+
+            if let Some(intersection) = Line::find_intersection(line, &Line::new(*line.color(), position, use_target)) {
+                targets.push((UseTarget::MovingSegment(segment_id), glm::distance(position, intersection)));
+            }
+        }
+
+        // Push-walls and trigger regions are found by stepping along the use line
+        let push_wall_ids = accessor.borrow_ids::<PushWallComponent>(manager).cloned().unwrap_or_default();
+        let trigger_ids = accessor.borrow_ids::<TriggerComponent>(manager).cloned().unwrap_or_default();
+        let use_distance = glm::distance(position, use_target);
+
+        let line_points = (0..)
+            .map(|step| step as f32 * USE_LINE_STEP)
+            .take_while(|distance| *distance <= use_distance)
+            .map(|distance| (position + (use_target - position) * (distance / use_distance.max(f32::EPSILON)), distance));
+
+        let mut push_wall_target = None;
+        let mut trigger_target = None;
+
+        for (point, distance) in line_points {
+            if push_wall_target.is_none() && point.x >= 0.0 && point.y >= 0.0 {
+                let cell = uvec2(point.x as u32, point.y as u32);
+
+                push_wall_target = push_wall_ids.iter()
+                    .find(|push_wall_id| manager
+                        .borrow_component::<PushWallComponent>(**push_wall_id)
+                        .is_some_and(|push_wall| push_wall.cell() == cell))
+                    .map(|push_wall_id| (UseTarget::PushWall(*push_wall_id), distance));
+            }
+
+            // Only triggers with the use event can be used
+            if trigger_target.is_none() {
+                trigger_target = trigger_ids.iter()
+                    .find(|trigger_id| manager
+                        .borrow_component::<TriggerComponent>(**trigger_id)
+                        .is_some_and(|trigger| trigger.use_event().is_some() && trigger.region().contains(point)))
+                    .map(|trigger_id| (UseTarget::Trigger(*trigger_id), distance));
+            }
+        }
+
+        targets.extend(push_wall_target);
+        targets.extend(trigger_target);
+
+        targets
+    }
+}
+
+impl System for UseSystem {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let is_use_pressed = self.use_action.borrow_mut().take_press();
+
+        let used_target = match is_use_pressed {
+            false => None,
+            true => find_player_use_line(manager, accessor).and_then(|(player_id, position, use_target)| {
+                self.find_targets((position, use_target), manager, accessor)
+                    .into_iter()
+                    .min_by(|(_, first), (_, second)| first.total_cmp(second))
+                    .map(|(target, _)| (player_id, target))
+            })
+        };
+
+        self.use_action.borrow_mut().set_used_target(used_target);
+    }
+}
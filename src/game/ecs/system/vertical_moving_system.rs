@@ -0,0 +1,69 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+
+use crate::game::ecs::component::player_flag_component::PlayerFlagComponent;
+use crate::game::ecs::component::vertical_motion_component::VerticalMotionComponent;
+use crate::game::ecs::simulation_clock::SimulationClock;
+use crate::game::input::input_action::InputAction;
+use crate::game::input::input_state::InputState;
+
+const JUMP_SPEED: f32 = 2.2f32; // Wall heights per second, jump is ~0.27 high
+const GRAVITY: f32 = 9.0f32; // Wall heights per second squared
+const CROUCHING_SPEED: f32 = 5.0f32; // Full crouch takes 0.2 second
+
+// Jumps with gravity and crouches the player, the eye height is used by the camera
+pub struct VerticalMovingSystem {
+    input: Rc<RefCell<InputState>>,
+    clock: Rc<RefCell<SimulationClock>>
+}
+
+impl VerticalMovingSystem {
+    pub fn new(input: &Rc<RefCell<InputState>>, clock: &Rc<RefCell<SimulationClock>>) -> Self {
+        Self {
+            input: input.clone(),
+            clock: clock.clone()
+        }
+    }
+}
+
+impl System for VerticalMovingSystem {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let Some(player_id) = accessor
+            .borrow_ids_for_pair::<VerticalMotionComponent, PlayerFlagComponent>(manager)
+            .and_then(|player_ids| player_ids.first().copied()) else {
+            return;
+        };
+
+        let Some(vertical_motion) = manager.borrow_component_mut::<VerticalMotionComponent>(player_id) else {
+            return;
+        };
+
+        let delta_time = self.clock.borrow().delta_time().as_secs_f32();
+        let input = self.input.borrow();
+
+        // Player can't jump while crouching
+        let is_crouch_pressed = input.is_active(InputAction::Crouch);
+
+        if vertical_motion.is_on_floor() && input.is_active(InputAction::Jump) && !is_crouch_pressed {
+            vertical_motion.vertical_velocity = JUMP_SPEED;
+        }
+
+        if !vertical_motion.is_on_floor() || vertical_motion.vertical_velocity > 0.0 {
+            vertical_motion.vertical_velocity -= GRAVITY * delta_time;
+            vertical_motion.elevation += vertical_motion.vertical_velocity * delta_time;
+
+            if vertical_motion.elevation <= 0.0 {
+                vertical_motion.elevation = 0.0;
+                vertical_motion.vertical_velocity = 0.0;
+            }
+        }
+
+        let target_crouching = if is_crouch_pressed { 1.0 } else { 0.0 };
+        let crouching_change = (target_crouching - vertical_motion.crouching).clamp(-CROUCHING_SPEED * delta_time, CROUCHING_SPEED * delta_time);
+
+        vertical_motion.crouching += crouching_change;
+    }
+}
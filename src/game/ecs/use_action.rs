@@ -0,0 +1,65 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use glm::{vec2, Vec2};
+
+use crate::game::ecs::component::direction_component::DirectionComponent;
+use crate::game::ecs::component::player_flag_component::PlayerFlagComponent;
+use crate::game::ecs::component::position_component::PositionComponent;
+use crate::game::input::input_action::InputAction;
+use crate::game::input::input_state::InputState;
+
+const USE_DISTANCE: f32 = 1.0f32; // Tiles
+
+// Entity which can be used by the player
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum UseTarget {
+    Door(usize),
+    PushWall(usize),
+    MovingSegment(usize),
+    Trigger(usize)
+}
+
+// "Use" action shared by the systems. The use system resolves the press to the nearest target once,
+// the system owning the target activates it, so a single press never uses several objects
+pub struct UseAction {
+    input: Rc<RefCell<InputState>>,
+    was_pressed: bool,
+    // Player and the target used on the current simulation step
+    used_target: Option<(usize, UseTarget)>
+}
+
+impl UseAction {
+    pub fn new(input: &Rc<RefCell<InputState>>) -> Self {
+        Self { input: input.clone(), was_pressed: false, used_target: None }
+    }
+
+    // True only on the simulation step when the key became pressed
+    pub fn take_press(&mut self) -> bool {
+        let is_pressed = self.input.borrow().is_active(InputAction::Use);
+        let is_just_pressed = is_pressed && !self.was_pressed;
+
+        self.was_pressed = is_pressed;
+        is_just_pressed
+    }
+
+    pub fn used_target(&self) -> Option<(usize, UseTarget)> {
+        self.used_target
+    }
+    pub fn set_used_target(&mut self, used_target: Option<(usize, UseTarget)>) {
+        self.used_target = used_target;
+    }
+}
+
+// Player id and segment from the player to the farthest point which can be used
+pub fn find_player_use_line(manager: &EntityManager, accessor: &mut EntityIdAccessor) -> Option<(usize, Vec2, Vec2)> {
+    let player_id = *accessor
+        .borrow_ids_for_triple::<PositionComponent, DirectionComponent, PlayerFlagComponent>(manager)?
+        .first()?;
+
+    let position = manager.borrow_component::<PositionComponent>(player_id)?.position;
+    let direction = manager.borrow_component::<DirectionComponent>(player_id)?.direction;
+
+    Some((player_id, position, position + vec2(direction.cos(), direction.sin()) * USE_DISTANCE))
+}
@@ -0,0 +1,91 @@
+use glm::{UVec2, Vec2};
+
+use crate::game::ecs::component::direction_component::DirectionComponent;
+use crate::game::ecs::component::door_component::{DoorComponent, DoorState};
+use crate::game::ecs::component::health_component::HealthComponent;
+use crate::game::ecs::component::position_component::PositionComponent;
+use crate::game::event::game_event::{EventContext, GameEvent, GameEventHandler};
+use crate::game::model::ResourceId;
+
+// Built-in event handlers
+pub enum EventAction {
+    // Unlocks and opens the door entity
+    OpenDoor {
+        door: usize
+    },
+
+    SetTile {
+        tilemap: ResourceId,
+        position: UVec2,
+        tile: ResourceId
+    },
+
+    // Moves the event activator
+    Teleport {
+        position: Vec2,
+        direction: Option<f32>
+    },
+
+    ShowMessage {
+        text: String
+    },
+
+    // Damages the event activator
+    Damage {
+        amount: u32
+    },
+
+    EndLevel
+}
+
+impl GameEventHandler for EventAction {
+    fn handle(&mut self, event: &GameEvent, context: &mut EventContext) {
+        match self {
+            EventAction::OpenDoor { door } => match context.manager.borrow_component_mut::<DoorComponent>(*door) {
+                None => log::warn!("Door {} of event {} is not found", door, event.name()),
+                Some(door) => {
+                    door.set_locked(false);
+
+                    if let DoorState::Closed | DoorState::Closing = door.state() {
+                        door.set_state(DoorState::Opening);
+                    }
+                }
+            },
+
+            EventAction::SetTile { tilemap, position, tile } => {
+                let tile = match context.repositories.tiles_repository().borrow().get_resource(tile) {
+                    None => {
+                        log::warn!("Tile {} of event {} is not found", tile, event.name());
+                        return;
+                    }
+                    Some(tile) => tile.clone()
+                };
+
+                match context.repositories.tilemap_repository().borrow_mut().get_resource_mut(tilemap) {
+                    None => log::warn!("Tilemap {} of event {} is not found", tilemap, event.name()),
+                    Some(tilemap) => tilemap.set_tile(*position, &tile)
+                }
+            }
+
+            EventAction::Teleport { position, direction } => {
+                if let Some(position_component) = context.manager.borrow_component_mut::<PositionComponent>(event.activator()) {
+                    position_component.position = *position;
+                }
+
+                if let (Some(direction), Some(direction_component)) = (direction, context.manager.borrow_component_mut::<DirectionComponent>(event.activator())) {
+                    direction_component.direction = *direction;
+                }
+            }
+
+            EventAction::ShowMessage { text } => context.messages.push(text.clone()),
+
+            EventAction::Damage { amount } => {
+                if let Some(health) = context.manager.borrow_component_mut::<HealthComponent>(event.activator()) {
+                    health.damage(*amount);
+                }
+            }
+
+            EventAction::EndLevel => context.is_level_finished = true
+        }
+    }
+}
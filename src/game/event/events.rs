@@ -1,19 +1,92 @@
 use std::cell::RefCell;
 use std::rc::Rc;
-use sdl2::EventPump;
+use sdl2::event::Event;
+use sdl2::mouse::MouseUtil;
+use sdl2::{EventPump, Sdl};
+
+use crate::game::event::game_event::EventDispatcher;
+use crate::game::input::bindings::Bindings;
+use crate::game::input::gamepads::Gamepads;
+use crate::game::input::input_state::InputState;
+use crate::game::input::replay::InputReplay;
+use crate::game::input::synthetic_input::SyntheticInput;
+use crate::game::launch_options::LaunchOptions;
 
 pub struct Events {
-    event_pump: Rc<RefCell<EventPump>>
+    event_pump: Rc<RefCell<EventPump>>,
+    mouse: MouseUtil,
+    dispatcher: Rc<RefCell<EventDispatcher>>,
+    input: Rc<RefCell<InputState>>,
+    gamepads: Rc<RefCell<Gamepads>>,
+    synthetic_input: Option<SyntheticInput>,
+    replay: Option<Rc<RefCell<InputReplay>>>
 }
 
 impl Events {
-    pub fn new(event_pump: EventPump) -> Self {
-        Self {
-            event_pump: Rc::new(RefCell::new(event_pump))
+    pub fn initialize_events(sdl_context: &Sdl, bindings: Bindings, replay: Option<InputReplay>, options: &LaunchOptions) -> Result<Events, String> {
+        let game_controller_subsystem = match sdl_context.game_controller() {
+            Ok(subsystem) => Some(subsystem),
+            Err(error) => {
+                log::error!("Game controllers are not available: {}", error);
+                None
+            }
+        };
+
+        let synthetic_input = match &options.input_script_path {
+            None => None,
+            Some(input_script_path) => {
+                let synthetic_input = SyntheticInput::load(input_script_path)
+                    .map_err(|error| format!("unable to load input script {}: {}", input_script_path.display(), error))?;
+
+                log::info!("Input is scripted by {}", input_script_path.display());
+                Some(synthetic_input)
+            }
+        };
+
+        let mut input = InputState::new(bindings, options.mouse_settings, options.gamepad_settings);
+
+        if let Some(InputReplay::Playing(_)) = replay {
+            input.start_replaying();
+        }
+
+        Ok(Events {
+            event_pump: Rc::new(RefCell::new(sdl_context.event_pump()?)),
+            mouse: sdl_context.mouse(),
+            dispatcher: Rc::new(RefCell::new(EventDispatcher::new())),
+            input: Rc::new(RefCell::new(input)),
+            gamepads: Rc::new(RefCell::new(Gamepads::new(game_controller_subsystem))),
+            synthetic_input,
+            replay: replay.map(|replay| Rc::new(RefCell::new(replay)))
+        })
+    }
+
+    pub fn dispatcher(&self) -> &Rc<RefCell<EventDispatcher>> {
+        &self.dispatcher
+    }
+    pub fn input(&self) -> &Rc<RefCell<InputState>> {
+        &self.input
+    }
+    pub fn gamepads(&self) -> &Rc<RefCell<Gamepads>> {
+        &self.gamepads
+    }
+    pub fn replay(&self) -> Option<&Rc<RefCell<InputReplay>>> {
+        self.replay.as_ref()
+    }
+
+    // SDL events followed by the scripted input events of the frame
+    pub fn poll_events(&mut self, frame: u64) -> Vec<Event> {
+        let mut events: Vec<Event> = self.event_pump.borrow_mut().poll_iter().collect();
+
+        if let Some(synthetic_input) = self.synthetic_input.as_mut() {
+            events.extend(synthetic_input.take_events(frame));
         }
+
+        events
     }
 
-    pub fn event_pump(&self) -> &Rc<RefCell<EventPump>> {
-        &self.event_pump
+    // Relative mode hides the cursor and keeps reporting motion at the window borders
+    pub fn set_mouse_look_enabled(&self, is_mouse_look_enabled: bool) {
+        self.mouse.set_relative_mouse_mode(is_mouse_look_enabled);
+        self.input.borrow_mut().set_mouse_look_enabled(is_mouse_look_enabled);
     }
 }
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use ecs_rust::entity_manager::EntityManager;
+
+use crate::game::game_state::Repositories;
+
+// Named event of the level, e.g. fired by a trigger
+pub struct GameEvent {
+    name: String,
+    // Entity which has caused the event, e.g. player
+    activator: usize
+}
+
+impl GameEvent {
+    pub fn new(name: &str, activator: usize) -> Self {
+        Self { name: name.to_string(), activator }
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    pub fn activator(&self) -> usize {
+        self.activator
+    }
+}
+
+// Game state available to the event handlers
+pub struct EventContext<'a> {
+    pub manager: &'a mut EntityManager,
+    pub repositories: &'a Repositories,
+    pub messages: Vec<String>,
+    pub is_level_finished: bool
+}
+
+pub trait GameEventHandler {
+    fn handle(&mut self, event: &GameEvent, context: &mut EventContext);
+}
+
+// Game level event bus. Events are queued during the frame and delivered to the handlers registered for the event name
+pub struct EventDispatcher {
+    queued_events: Vec<GameEvent>,
+    handlers: HashMap<String, Vec<Box<dyn GameEventHandler>>>,
+    // Handlers receiving events with any name, e.g. level script
+    global_handlers: Vec<Box<dyn GameEventHandler>>,
+    messages: Vec<String>,
+    is_level_finished: bool
+}
+
+impl EventDispatcher {
+    pub fn new() -> Self {
+        Self {
+            queued_events: vec![],
+            handlers: HashMap::new(),
+            global_handlers: vec![],
+            messages: vec![],
+            is_level_finished: false
+        }
+    }
+
+    pub fn register_handler(&mut self, event_name: &str, handler: Box<dyn GameEventHandler>) -> &mut Self {
+        self.handlers.entry(event_name.to_string()).or_default().push(handler);
+        self
+    }
+
+    pub fn register_global_handler(&mut self, handler: Box<dyn GameEventHandler>) -> &mut Self {
+        self.global_handlers.push(handler);
+        self
+    }
+
+    pub fn push_event(&mut self, event: GameEvent) {
+        self.queued_events.push(event);
+    }
+
+    pub fn dispatch(&mut self, manager: &mut EntityManager, repositories: &Repositories) {
+        let events: Vec<GameEvent> = self.queued_events.drain(..).collect();
+
+        let mut context = EventContext {
+            manager,
+            repositories,
+            messages: vec![],
+            is_level_finished: false
+        };
+
+        for event in events {
+            let handlers = self.handlers.get_mut(event.name()).into_iter().flatten();
+
+            for handler in handlers.chain(self.global_handlers.iter_mut()) {
+                handler.handle(&event, &mut context);
+            }
+        }
+
+        self.messages.append(&mut context.messages);
+        self.is_level_finished |= context.is_level_finished;
+    }
+
+    // Messages shown by the handlers since the last call
+    pub fn take_messages(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.messages)
+    }
+
+    pub fn is_level_finished(&self) -> bool {
+        self.is_level_finished
+    }
+}
@@ -1 +1,3 @@
 pub mod events;
+pub mod game_event;
+pub mod event_action;
@@ -1,15 +1,19 @@
 use std::cell::RefCell;
+use std::path::PathBuf;
 use std::rc::Rc;
 use crate::game::model::linemap::Linemap;
 use crate::game::model::repository::Repository;
 use crate::game::model::tile::Tile;
 use crate::game::model::tilemap::Tilemap;
+use crate::game::save::save_service::SaveService;
 
 pub struct GameState {
     is_game_running: bool,
-    repositories: Repositories
+    repositories: Repositories,
+    save_service: Rc<RefCell<SaveService>>
 }
 
+#[derive(Clone)]
 pub struct Repositories {
     tiles_repository: Rc<RefCell<Repository<Tile>>>,
     tilemap_repository: Rc<RefCell<Repository<Tilemap>>>,
@@ -37,10 +41,11 @@ impl Repositories {
 }
 
 impl GameState {
-    pub fn new() -> Self {
+    pub fn new(save_directory: PathBuf) -> Self {
         Self {
             is_game_running: true,
-            repositories: Repositories::new()
+            repositories: Repositories::new(),
+            save_service: Rc::new(RefCell::new(SaveService::new(save_directory)))
         }
     }
 
@@ -53,4 +58,7 @@ impl GameState {
     pub fn repositories(&self) -> &Repositories {
         &self.repositories
     }
+    pub fn save_service(&self) -> &Rc<RefCell<SaveService>> {
+        &self.save_service
+    }
 }
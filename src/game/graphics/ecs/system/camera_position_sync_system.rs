@@ -1,20 +1,29 @@
 use std::cell::RefCell;
+use std::f32::consts::{PI, TAU};
 use std::rc::Rc;
 use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
 use ecs_rust::system::System;
 use crate::game::ecs::component::direction_component::DirectionComponent;
 use crate::game::ecs::component::player_flag_component::PlayerFlagComponent;
 use crate::game::ecs::component::position_component::PositionComponent;
+use crate::game::ecs::component::previous_pose_component::PreviousPoseComponent;
+use crate::game::ecs::component::vertical_motion_component::{VerticalMotionComponent, STANDING_EYE_HEIGHT};
+use crate::game::ecs::simulation_clock::SimulationClock;
 use crate::game::graphics::RenderingState;
 
+// Longer moves during one step are teleports, they are not interpolated
+const MAX_INTERPOLATED_DISTANCE: f32 = 1.0;
+
 pub struct CameraPositionSyncSystem {
     rendering_state: Rc<RefCell<RenderingState>>,
+    clock: Rc<RefCell<SimulationClock>>
 }
 
 impl CameraPositionSyncSystem {
-    pub fn new(rendering_state: &Rc<RefCell<RenderingState>>) -> Self {
+    pub fn new(rendering_state: &Rc<RefCell<RenderingState>>, clock: &Rc<RefCell<SimulationClock>>) -> Self {
         Self {
-            rendering_state: rendering_state.clone()
+            rendering_state: rendering_state.clone(),
+            clock: clock.clone()
         }
     }
 }
@@ -32,11 +41,36 @@ impl System for CameraPositionSyncSystem {
         let direction_component = manager
             .borrow_component::<DirectionComponent>(*player_id).unwrap();
 
+        let mut position = position_component.position;
+        let mut direction = direction_component.direction;
+        let mut pitch = direction_component.pitch;
+
+        let mut eye_height = manager.borrow_component::<VerticalMotionComponent>(*player_id)
+            .map(|vertical_motion| vertical_motion.eye_height())
+            .unwrap_or(STANDING_EYE_HEIGHT);
+
+        // Camera is between the last two simulation steps, so movement is smooth at any frame rate
+        if let Some(previous_pose) = manager.borrow_component::<PreviousPoseComponent>(*player_id) {
+            let interpolation = self.clock.borrow().interpolation();
+
+            if glm::distance(previous_pose.position, position) <= MAX_INTERPOLATED_DISTANCE {
+                position = previous_pose.position + (position - previous_pose.position) * interpolation;
+            }
+
+            let direction_difference = (direction - previous_pose.direction + PI).rem_euclid(TAU) - PI;
+            direction = previous_pose.direction + direction_difference * interpolation;
+
+            pitch = previous_pose.pitch + (pitch - previous_pose.pitch) * interpolation;
+            eye_height = previous_pose.eye_height + (eye_height - previous_pose.eye_height) * interpolation;
+        }
+
         let mut rendering_state = self.rendering_state.borrow_mut();
 
         let camera = rendering_state.camera_mut();
 
-        camera.set_position(position_component.position.clone());
-        camera.set_direction(direction_component.direction);
+        camera.set_position(position);
+        camera.set_direction(direction);
+        camera.set_pitch(pitch);
+        camera.set_eye_height(eye_height);
     }
 }
@@ -0,0 +1,105 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+use sdl2::rect::Rect;
+
+use crate::game::ecs::component::ammo_component::AmmoComponent;
+use crate::game::ecs::component::health_component::HealthComponent;
+use crate::game::ecs::component::player_flag_component::PlayerFlagComponent;
+use crate::game::ecs::component::position_component::PositionComponent;
+use crate::game::graphics::hud::{hud_scale, render_crosshair, render_text_block, Hud, HudAnchor};
+use crate::game::graphics::profiler_overlay::render_profiler_overlay;
+use crate::game::graphics::renderer::Renderer;
+use crate::game::graphics::RenderingState;
+use crate::game::profiler::FrameProfiler;
+
+// Weight of the last frame in the smoothed frame rate
+const FRAME_RATE_SMOOTHING: f32 = 0.1;
+
+pub struct HudRenderingSystem {
+    renderer: Rc<RefCell<Renderer>>,
+    rendering_state: Rc<RefCell<RenderingState>>,
+    hud: Rc<RefCell<Hud>>,
+    profiler: Rc<RefCell<FrameProfiler>>,
+    last_frame: Instant,
+    frame_rate: f32
+}
+
+impl HudRenderingSystem {
+    pub fn new(renderer: &Rc<RefCell<Renderer>>, rendering_state: &Rc<RefCell<RenderingState>>, hud: &Rc<RefCell<Hud>>, profiler: &Rc<RefCell<FrameProfiler>>) -> Self {
+        Self {
+            renderer: renderer.clone(),
+            rendering_state: rendering_state.clone(),
+            hud: hud.clone(),
+            profiler: profiler.clone(),
+            last_frame: Instant::now(),
+            frame_rate: 0.0
+        }
+    }
+
+    fn update_frame_rate(&mut self) {
+        let frame_time = self.last_frame.elapsed().as_secs_f32();
+        self.last_frame = Instant::now();
+
+        if frame_time > 0.0 {
+            self.frame_rate += (1.0 / frame_time - self.frame_rate) * FRAME_RATE_SMOOTHING;
+        }
+    }
+}
+
+impl System for HudRenderingSystem {
+
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        self.update_frame_rate();
+
+        let renderer = self.renderer.borrow();
+        let rendering_state = self.rendering_state.borrow();
+        let hud = self.hud.borrow();
+
+        let resolution = rendering_state.internal_resolution();
+        let screen = Rect::new(0, 0, resolution.x, resolution.y);
+        let scale = hud_scale(resolution);
+
+        // Crosshair and player stats belong to the 3d view
+        if let Some(scene_viewport) = rendering_state.view_layout().scene {
+            render_crosshair(scene_viewport, scale, &renderer);
+
+            let player_id = accessor.borrow_ids_for_pair::<PositionComponent, PlayerFlagComponent>(manager)
+                .and_then(|player_ids| player_ids.first().copied());
+
+            if let Some(player_id) = player_id {
+                if let Some(health) = manager.borrow_component::<HealthComponent>(player_id) {
+                    let health_text = format!("HEALTH {}/{}", health.health(), health.max_health());
+                    render_text_block(&[health_text], HudAnchor::BottomLeft, scene_viewport, scale, &renderer);
+                }
+
+                if let Some(ammo) = manager.borrow_component::<AmmoComponent>(player_id) {
+                    render_text_block(&[format!("AMMO {}", ammo.ammo())], HudAnchor::BottomRight, scene_viewport, scale, &renderer);
+                }
+            }
+        }
+
+        render_text_block(&hud.visible_messages(), HudAnchor::Top, screen, scale, &renderer);
+
+        if hud.is_debug_shown() {
+            let camera = rendering_state.camera();
+
+            let debug_lines = [
+                format!("FPS {:.0}", self.frame_rate),
+                format!("POS {:.2} {:.2}", camera.position().x, camera.position().y),
+                format!("DIR {:.0}", camera.direction().to_degrees().rem_euclid(360.0)),
+                format!("VIEW {:?}", rendering_state.view_mode()),
+                format!("SCENE {}x{}", rendering_state.scene_resolution().x, rendering_state.scene_resolution().y)
+            ];
+
+            render_text_block(&debug_lines, HudAnchor::TopLeft, screen, scale, &renderer);
+        }
+
+        if hud.is_profiler_shown() {
+            render_profiler_overlay(&self.profiler.borrow(), screen, scale, &renderer);
+        }
+    }
+}
@@ -1,7 +1,6 @@
 pub mod camera_position_sync_system;
-pub mod tilemap_2d_rendering_system;
 pub mod rendering_swapbuffers_system;
-pub mod tilemap_3d_rendering_system;
 pub mod rendering_clear_system;
-pub mod linemap_2d_rendering_system;
-pub mod linemap_3d_rendering_system;
+pub mod world_2d_rendering_system;
+pub mod world_3d_rendering_system;
+pub mod hud_rendering_system;
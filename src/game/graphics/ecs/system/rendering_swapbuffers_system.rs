@@ -2,16 +2,22 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
 use ecs_rust::system::System;
+use crate::game::graphics::frame_recorder::FrameRecorder;
 use crate::game::graphics::renderer::Renderer;
+use crate::game::graphics::screenshot::ScreenshotService;
 
 pub struct RenderingSwapBuffersSystem {
     renderer: Rc<RefCell<Renderer>>,
+    screenshot_service: Rc<RefCell<ScreenshotService>>,
+    frame_recorder: Rc<RefCell<FrameRecorder>>
 }
 
 impl RenderingSwapBuffersSystem {
-    pub fn new(renderer: &Rc<RefCell<Renderer>>) -> Self {
+    pub fn new(renderer: &Rc<RefCell<Renderer>>, screenshot_service: &Rc<RefCell<ScreenshotService>>, frame_recorder: &Rc<RefCell<FrameRecorder>>) -> Self {
         Self {
-            renderer: renderer.clone()
+            renderer: renderer.clone(),
+            screenshot_service: screenshot_service.clone(),
+            frame_recorder: frame_recorder.clone()
         }
     }
 }
@@ -19,6 +25,25 @@ impl RenderingSwapBuffersSystem {
 impl System for RenderingSwapBuffersSystem {
 
     fn update(&mut self, _manager: &mut EntityManager, _accessor: &mut EntityIdAccessor) {
-        self.renderer.borrow_mut().show();
+        let renderer = self.renderer.borrow();
+        let mut screenshot_service = self.screenshot_service.borrow_mut();
+
+        if screenshot_service.take_request() {
+            match renderer.capture_frame() {
+                Ok(capture) => screenshot_service.save_in_background(capture),
+                Err(error) => log::error!("Unable to capture frame for screenshot: {}", error)
+            }
+        }
+
+        let mut frame_recorder = self.frame_recorder.borrow_mut();
+
+        if frame_recorder.is_recording() {
+            match renderer.capture_frame() {
+                Ok(capture) => frame_recorder.push_frame(capture),
+                Err(error) => log::error!("Unable to capture frame for recording: {}", error)
+            }
+        }
+
+        renderer.show();
     }
 }
@@ -0,0 +1,50 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+
+use crate::game::ecs::raycast_worlds::RaycastWorlds;
+use crate::game::graphics::renderer::Renderer;
+use crate::game::graphics::RenderingState;
+use crate::game::graphics::view_mode::MapKind;
+use crate::game::graphics::world_2d_render::{render_automap, render_minimap, render_world_2d};
+use crate::game::model::exploration::Exploration;
+
+pub struct World2DRenderingSystem {
+    renderer: Rc<RefCell<Renderer>>,
+    rendering_state: Rc<RefCell<RenderingState>>,
+    worlds: RaycastWorlds,
+    exploration: Rc<RefCell<Exploration>>
+}
+
+impl World2DRenderingSystem {
+    pub fn new(renderer: &Rc<RefCell<Renderer>>, rendering_state: &Rc<RefCell<RenderingState>>, worlds: &RaycastWorlds, exploration: &Rc<RefCell<Exploration>>) -> Self {
+        Self {
+            renderer: renderer.clone(),
+            rendering_state: rendering_state.clone(),
+            worlds: worlds.clone(),
+            exploration: exploration.clone()
+        }
+    }
+}
+
+impl System for World2DRenderingSystem {
+
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let renderer = self.renderer.borrow();
+        let rendering_state = self.rendering_state.borrow();
+
+        let Some((viewport, map_kind)) = rendering_state.view_layout().map else {
+            return;
+        };
+
+        if let Some(world) = self.worlds.compose(manager, accessor) {
+            match map_kind {
+                MapKind::Debug => render_world_2d(world.as_ref(), viewport, &rendering_state, &renderer),
+                MapKind::Minimap => render_minimap(world.as_ref(), viewport, &self.exploration, &rendering_state, &renderer),
+                MapKind::Automap => render_automap(world.as_ref(), viewport, &self.exploration, &rendering_state, &renderer)
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+
+use crate::game::ecs::raycast_worlds::RaycastWorlds;
+use crate::game::graphics::renderer::Renderer;
+use crate::game::graphics::RenderingState;
+use crate::game::graphics::world_3d_render::render_world_3d;
+use crate::game::model::exploration::Exploration;
+use crate::game::profiler::FrameProfiler;
+
+pub struct World3DRenderingSystem {
+    renderer: Rc<RefCell<Renderer>>,
+    rendering_state: Rc<RefCell<RenderingState>>,
+    worlds: RaycastWorlds,
+    exploration: Rc<RefCell<Exploration>>,
+    profiler: Rc<RefCell<FrameProfiler>>
+}
+
+impl World3DRenderingSystem {
+    pub fn new(renderer: &Rc<RefCell<Renderer>>, rendering_state: &Rc<RefCell<RenderingState>>, worlds: &RaycastWorlds, exploration: &Rc<RefCell<Exploration>>, profiler: &Rc<RefCell<FrameProfiler>>) -> Self {
+        Self {
+            renderer: renderer.clone(),
+            rendering_state: rendering_state.clone(),
+            worlds: worlds.clone(),
+            exploration: exploration.clone(),
+            profiler: profiler.clone()
+        }
+    }
+}
+
+impl System for World3DRenderingSystem {
+
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let renderer = self.renderer.borrow();
+        let rendering_state = self.rendering_state.borrow();
+
+        let Some(viewport) = rendering_state.view_layout().scene else {
+            return;
+        };
+
+        if let Some(world) = self.worlds.compose(manager, accessor) {
+            let hits = render_world_3d(&world, viewport, &rendering_state, &renderer, &mut self.profiler.borrow_mut());
+
+            // Walls seen by the camera are revealed on the minimap
+            self.exploration.borrow_mut().record_hits(hits.iter().map(|hit_details| (hit_details.ray(), hit_details.hit())));
+        }
+    }
+}
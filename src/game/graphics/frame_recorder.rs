@@ -0,0 +1,310 @@
+use std::fs;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{sync_channel, SyncSender, TrySendError};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::game::graphics::screenshot::{format_timestamp, FrameCapture};
+
+// Frames waiting for the writer thread, each one is a full RGB frame
+const FRAMES_QUEUE_LENGTH: usize = 8;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RecordingFormat {
+    // Numbered PNG files, one per frame
+    PngSequence,
+    // Single uncompressed YUV4MPEG2 (4:4:4) stream
+    Y4m
+}
+
+impl RecordingFormat {
+    pub fn from_name(name: &str) -> Option<RecordingFormat> {
+        match name {
+            "png" => Some(RecordingFormat::PngSequence),
+            "y4m" => Some(RecordingFormat::Y4m),
+            _ => None
+        }
+    }
+}
+
+struct RecordedFrame {
+    number: u64,
+    timestamp: Duration,
+    capture: FrameCapture
+}
+
+struct ActiveRecording {
+    started_at: Instant,
+    frames_count: u64,
+    dropped_frames_count: u64,
+    // Real-time recording drops frames when the writer falls behind, otherwise the game waits for the writer
+    is_realtime: bool,
+    frames_sender: SyncSender<RecordedFrame>,
+    writer_thread: JoinHandle<()>
+}
+
+pub struct FrameRecorder {
+    directory: PathBuf,
+    format: RecordingFormat,
+    frame_rate: u32,
+    active_recording: Option<ActiveRecording>,
+    // Stopped recordings still writing their queued frames, joined when the recorder is dropped
+    finishing_writers: Vec<JoinHandle<()>>
+}
+
+impl FrameRecorder {
+    pub fn new(directory: PathBuf, format: RecordingFormat, frame_rate: u32) -> Self {
+        Self { directory, format, frame_rate, active_recording: None, finishing_writers: vec![] }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.active_recording.is_some()
+    }
+
+    pub fn toggle(&mut self) {
+        if self.is_recording() {
+            self.stop()
+        } else {
+            self.start()
+        }
+    }
+
+    pub fn start(&mut self) {
+        let output_path = self.directory.join(format!("recording_{}", format_timestamp(SystemTime::now())));
+        self.start_at(output_path, true)
+    }
+
+    // Records frames directly to given directory
+    pub fn start_at(&mut self, output_path: PathBuf, is_realtime: bool) {
+        if self.is_recording() {
+            return;
+        }
+
+        let (format, frame_rate) = (self.format, self.frame_rate);
+        let (frames_sender, frames_receiver) = sync_channel::<RecordedFrame>(FRAMES_QUEUE_LENGTH);
+
+        log::info!("Recording {:?} frames to {}", format, output_path.display());
+
+        // Frames are encoded in order on a separate thread to avoid frame hitches
+        let writer_thread = thread::spawn(move || {
+            let mut writer = match FrameSequenceWriter::create(&output_path, format, frame_rate) {
+                Ok(writer) => writer,
+                Err(error) => {
+                    log::error!("Unable to start recording: {}", error);
+                    return;
+                }
+            };
+
+            for frame in frames_receiver {
+                if let Err(error) = writer.write_frame(frame) {
+                    log::error!("Unable to write recorded frame: {}", error);
+                }
+            }
+
+            if let Err(error) = writer.finish() {
+                log::error!("Unable to finish recording: {}", error);
+            }
+        });
+
+        self.active_recording = Some(ActiveRecording {
+            started_at: Instant::now(),
+            frames_count: 0,
+            dropped_frames_count: 0,
+            is_realtime,
+            frames_sender,
+            writer_thread
+        });
+    }
+
+    pub fn stop(&mut self) {
+        match self.active_recording.take() {
+            None => {}
+            Some(recording) => {
+                // Closing channel lets the writer thread flush remaining frames and exit, the game doesn't wait for it
+                drop(recording.frames_sender);
+
+                self.finishing_writers.retain(|writer_thread| !writer_thread.is_finished());
+                self.finishing_writers.push(recording.writer_thread);
+
+                match recording.dropped_frames_count {
+                    0 => log::info!("Recording stopped after {} frames", recording.frames_count),
+                    dropped_frames_count => log::warn!("Recording stopped after {} frames, {} frames were dropped as the writer fell behind",
+                        recording.frames_count, dropped_frames_count)
+                }
+            }
+        }
+    }
+
+    pub fn push_frame(&mut self, capture: FrameCapture) {
+        match self.active_recording.as_mut() {
+            None => {}
+            Some(recording) => {
+                let frame = RecordedFrame {
+                    number: recording.frames_count,
+                    timestamp: recording.started_at.elapsed(),
+                    capture
+                };
+
+                recording.frames_count += 1;
+
+                let send_result = match recording.is_realtime {
+                    true => recording.frames_sender.try_send(frame),
+                    false => recording.frames_sender.send(frame).map_err(|error| TrySendError::Disconnected(error.0))
+                };
+
+                match send_result {
+                    Ok(()) => {}
+                    Err(TrySendError::Full(_)) => recording.dropped_frames_count += 1,
+                    Err(TrySendError::Disconnected(_)) => {
+                        log::error!("Recording writer thread is not available, stopping recording");
+                        self.stop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for FrameRecorder {
+    fn drop(&mut self) {
+        self.stop();
+
+        // Queued frames are written before the game exits
+        for writer_thread in self.finishing_writers.drain(..) {
+            if writer_thread.join().is_err() {
+                log::error!("Recording writer thread has panicked");
+            }
+        }
+    }
+}
+
+enum FrameSink {
+    PngSequence {
+        directory: PathBuf
+    },
+
+    // Stream has fixed frame sizes, so a new segment file is started when the window size changes
+    Y4m {
+        directory: PathBuf,
+        stream: BufWriter<File>,
+        segment: u32,
+        frame_sizes: Option<(u32, u32)>,
+        frame_rate: u32
+    }
+}
+
+// Writes frames and "frames.csv" index with frame numbers and timestamps
+struct FrameSequenceWriter {
+    sink: FrameSink,
+    index: BufWriter<File>
+}
+
+impl FrameSequenceWriter {
+    fn create(output_path: &Path, format: RecordingFormat, frame_rate: u32) -> Result<Self, String> {
+        fs::create_dir_all(output_path).map_err(|error| error.to_string())?;
+
+        let sink = match format {
+            RecordingFormat::PngSequence => FrameSink::PngSequence {
+                directory: output_path.to_path_buf()
+            },
+
+            RecordingFormat::Y4m => FrameSink::Y4m {
+                directory: output_path.to_path_buf(),
+                stream: create_file(&y4m_segment_path(output_path, 0))?,
+                segment: 0,
+                frame_sizes: None,
+                frame_rate
+            }
+        };
+
+        let mut index = create_file(&output_path.join("frames.csv"))?;
+        writeln!(index, "frame,timestamp_ms").map_err(|error| error.to_string())?;
+
+        Ok(Self { sink, index })
+    }
+
+    fn write_frame(&mut self, frame: RecordedFrame) -> Result<(), String> {
+        let RecordedFrame { number, timestamp, mut capture } = frame;
+
+        match &mut self.sink {
+            FrameSink::PngSequence { directory } => {
+                capture.save_png(&directory.join(format!("frame_{:06}.png", number)))?;
+            }
+
+            FrameSink::Y4m { directory, stream, segment, frame_sizes, frame_rate } => {
+                let capture_sizes = (capture.width(), capture.height());
+
+                if frame_sizes.is_some_and(|sizes| sizes != capture_sizes) {
+                    stream.flush().map_err(|error| error.to_string())?;
+
+                    *segment += 1;
+                    *stream = create_file(&y4m_segment_path(directory, *segment))?;
+                    *frame_sizes = None;
+
+                    log::info!("Frame sizes have changed to {}x{}, recording continues in segment {}", capture_sizes.0, capture_sizes.1, segment);
+                }
+
+                if frame_sizes.is_none() {
+                    writeln!(stream, "YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444", capture_sizes.0, capture_sizes.1, frame_rate)
+                        .map_err(|error| error.to_string())?;
+                    *frame_sizes = Some(capture_sizes);
+                }
+
+                write_y4m_frame(stream, number, timestamp, &capture).map_err(|error| error.to_string())?;
+            }
+        }
+
+        writeln!(self.index, "{},{}", number, timestamp.as_millis()).map_err(|error| error.to_string())
+    }
+
+    fn finish(mut self) -> Result<(), String> {
+        if let FrameSink::Y4m { stream, .. } = &mut self.sink {
+            stream.flush().map_err(|error| error.to_string())?;
+        }
+
+        self.index.flush().map_err(|error| error.to_string())
+    }
+}
+
+// First segment is "recording.y4m", the following ones are numbered
+fn y4m_segment_path(directory: &Path, segment: u32) -> PathBuf {
+    match segment {
+        0 => directory.join("recording.y4m"),
+        segment => directory.join(format!("recording_{:03}.y4m", segment))
+    }
+}
+
+fn create_file(path: &Path) -> Result<BufWriter<File>, String> {
+    File::create(path)
+        .map(BufWriter::new)
+        .map_err(|error| format!("{}: {}", path.display(), error))
+}
+
+// Converts RGB frame to full resolution planar Y'CbCr (BT.601, full range).
+// Frame number and timestamp are stored as X-parameters of the frame header
+fn write_y4m_frame(stream: &mut BufWriter<File>, number: u64, timestamp: Duration, capture: &FrameCapture) -> std::io::Result<()> {
+    let pixels_count = (capture.width() * capture.height()) as usize;
+    let pitch = capture.pitch() as usize;
+    let row_length = capture.width() as usize;
+
+    let mut planes = vec![0u8; pixels_count * 3];
+    let (y_plane, chroma_planes) = planes.split_at_mut(pixels_count);
+    let (cb_plane, cr_plane) = chroma_planes.split_at_mut(pixels_count);
+
+    for (row_index, row) in capture.pixels().chunks(pitch).enumerate() {
+        for (column_index, rgb) in row[..row_length * 3].chunks_exact(3).enumerate() {
+            let (r, g, b) = (rgb[0] as f32, rgb[1] as f32, rgb[2] as f32);
+            let plane_index = row_index * row_length + column_index;
+
+            y_plane[plane_index] = (0.299 * r + 0.587 * g + 0.114 * b).round().clamp(0.0, 255.0) as u8;
+            cb_plane[plane_index] = (128.0 - 0.168736 * r - 0.331264 * g + 0.5 * b).round().clamp(0.0, 255.0) as u8;
+            cr_plane[plane_index] = (128.0 + 0.5 * r - 0.418688 * g - 0.081312 * b).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    writeln!(stream, "FRAME Xframe={} Xtimestamp_ms={}", number, timestamp.as_millis())?;
+    stream.write_all(&planes)
+}
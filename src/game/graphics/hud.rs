@@ -0,0 +1,147 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use glm::{uvec2, UVec2};
+use sdl2::pixels::Color;
+use sdl2::rect::{Point, Rect};
+
+use crate::game::graphics::font::{text_sizes, GLYPH_CELL};
+use crate::game::graphics::renderer::Renderer;
+
+const MESSAGE_DURATION: Duration = Duration::from_secs(4);
+const MAX_VISIBLE_MESSAGES: usize = 4;
+
+// HUD is designed for 300 pixels high resolution and scaled by whole pixels
+const HUD_BASE_HEIGHT: u32 = 300;
+pub const HUD_MARGIN: u32 = 4;
+
+const TEXT_COLOR: Color = Color::WHITE;
+const SHADOW_COLOR: Color = Color::BLACK;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HudAnchor {
+    TopLeft,
+    Top,
+    Center,
+    BottomLeft,
+    Bottom,
+    BottomRight
+}
+
+impl HudAnchor {
+    // Top left corner of the block with given sizes, attached to the anchor point of the area
+    pub fn place(&self, sizes: UVec2, area: Rect, margin: u32) -> Point {
+        let left = area.x() + margin as i32;
+        let center_x = area.x() + (area.width() as i32 - sizes.x as i32) / 2;
+        let right = area.x() + area.width() as i32 - (sizes.x + margin) as i32;
+
+        let top = area.y() + margin as i32;
+        let center_y = area.y() + (area.height() as i32 - sizes.y as i32) / 2;
+        let bottom = area.y() + area.height() as i32 - (sizes.y + margin) as i32;
+
+        match self {
+            HudAnchor::TopLeft => Point::new(left, top),
+            HudAnchor::Top => Point::new(center_x, top),
+            HudAnchor::Center => Point::new(center_x, center_y),
+            HudAnchor::BottomLeft => Point::new(left, bottom),
+            HudAnchor::Bottom => Point::new(center_x, bottom),
+            HudAnchor::BottomRight => Point::new(right, bottom)
+        }
+    }
+}
+
+// State of the overlay drawn over 3d and 2d views
+pub struct Hud {
+    // Messages with their shown time, counted in simulation time so headless runs and replays show the same frames
+    messages: VecDeque<(String, Duration)>,
+    is_debug_shown: bool,
+    is_profiler_shown: bool
+}
+
+impl Hud {
+    pub fn new() -> Self {
+        Self {
+            messages: VecDeque::new(),
+            is_debug_shown: false,
+            is_profiler_shown: false
+        }
+    }
+
+    pub fn push_message(&mut self, message: String) {
+        self.messages.push_back((message, Duration::ZERO));
+
+        while self.messages.len() > MAX_VISIBLE_MESSAGES {
+            self.messages.pop_front();
+        }
+    }
+
+    // Expired messages are removed
+    pub fn advance(&mut self, time_delta: Duration) {
+        for (_, shown_time) in self.messages.iter_mut() {
+            *shown_time += time_delta;
+        }
+
+        self.messages.retain(|(_, shown_time)| *shown_time < MESSAGE_DURATION);
+    }
+
+    pub fn visible_messages(&self) -> Vec<String> {
+        self.messages.iter().map(|(message, _)| message.clone()).collect()
+    }
+
+    pub fn is_debug_shown(&self) -> bool {
+        self.is_debug_shown
+    }
+    pub fn set_debug_shown(&mut self, is_debug_shown: bool) {
+        self.is_debug_shown = is_debug_shown;
+    }
+
+    pub fn is_profiler_shown(&self) -> bool {
+        self.is_profiler_shown
+    }
+    pub fn set_profiler_shown(&mut self, is_profiler_shown: bool) {
+        self.is_profiler_shown = is_profiler_shown;
+    }
+}
+
+pub fn hud_scale(resolution: UVec2) -> u32 {
+    (resolution.y / HUD_BASE_HEIGHT).max(1)
+}
+
+// Lines are aligned by the left border of the block
+pub fn render_text_block(lines: &[String], anchor: HudAnchor, area: Rect, scale: u32, renderer: &Renderer) {
+    if lines.is_empty() {
+        return;
+    }
+
+    let block_position = anchor.place(text_block_sizes(lines, scale), area, HUD_MARGIN * scale);
+    let colored_lines: Vec<(&str, Color)> = lines.iter().map(|line| (line.as_str(), TEXT_COLOR)).collect();
+
+    render_text_lines(&colored_lines, block_position, scale, renderer);
+}
+
+pub fn text_block_sizes(lines: &[String], scale: u32) -> UVec2 {
+    let block_width = lines.iter().map(|line| text_sizes(line, scale).x).max().unwrap_or(0);
+    let block_height = (lines.len() as u32 * GLYPH_CELL.y).saturating_sub(1) * scale;
+
+    uvec2(block_width, block_height)
+}
+
+pub fn render_text_lines(lines: &[(&str, Color)], position: Point, scale: u32, renderer: &Renderer) {
+    for (index, (line, color)) in lines.iter().enumerate() {
+        let line_position = position.offset(0, (index as u32 * GLYPH_CELL.y * scale) as i32);
+
+        // Shadow keeps text readable over bright walls
+        renderer.render_text(line, line_position.offset(scale as i32, scale as i32), scale, SHADOW_COLOR);
+        renderer.render_text(line, line_position, scale, *color);
+    }
+}
+
+pub fn render_crosshair(area: Rect, scale: u32, renderer: &Renderer) {
+    const CROSSHAIR_SIZE: u32 = 7;
+
+    let (size, thickness) = (CROSSHAIR_SIZE * scale, scale);
+    let crosshair_position = HudAnchor::Center.place(uvec2(size, size), area, 0);
+
+    renderer.render_screen_rect(Rect::new(crosshair_position.x, crosshair_position.y + ((size - thickness) / 2) as i32, size, thickness), TEXT_COLOR);
+    renderer.render_screen_rect(Rect::new(crosshair_position.x + ((size - thickness) / 2) as i32, crosshair_position.y, thickness, size), TEXT_COLOR);
+}
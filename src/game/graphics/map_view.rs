@@ -0,0 +1,138 @@
+use glm::{vec2, Vec2};
+use sdl2::rect::Rect;
+
+const FIT_PADDING: f32 = 4.0;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MapShape {
+    Rect,
+    Circle
+}
+
+// Transform of 2d map coordinates into the viewport pixels. Map is rotated around the center point
+#[derive(Copy, Clone)]
+pub struct MapView {
+    viewport: Rect,
+    shape: MapShape,
+    center: Vec2,
+    scale: f32,
+    rotation: f32
+}
+
+impl MapView {
+    // Whole map bounds fit into the viewport keeping aspect ratio
+    pub fn fit(viewport: Rect, bounds: Vec2) -> Self {
+        let bounds = vec2(bounds.x.max(1.0), bounds.y.max(1.0));
+        let available_width = (viewport.width() as f32 - 2.0 * FIT_PADDING).max(1.0);
+        let available_height = (viewport.height() as f32 - 2.0 * FIT_PADDING).max(1.0);
+
+        Self {
+            viewport,
+            shape: MapShape::Rect,
+            center: bounds / 2.0,
+            scale: (available_width / bounds.x).min(available_height / bounds.y),
+            rotation: 0.0
+        }
+    }
+
+    // Map part around the center point, scale is given in pixels per map unit
+    pub fn follow(viewport: Rect, shape: MapShape, center: Vec2, scale: f32, rotation: f32) -> Self {
+        Self { viewport, shape, center, scale, rotation }
+    }
+
+    pub fn viewport(&self) -> Rect {
+        self.viewport
+    }
+    pub fn shape(&self) -> MapShape {
+        self.shape
+    }
+    pub fn is_rotated(&self) -> bool {
+        self.rotation != 0.0
+    }
+
+    pub fn to_screen(self, position: &Vec2) -> Vec2 {
+        let offset = *position - self.center;
+        let (sin, cos) = self.rotation.sin_cos();
+
+        self.viewport_center() + vec2(offset.x * cos - offset.y * sin, offset.x * sin + offset.y * cos) * self.scale
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        match self.shape {
+            MapShape::Rect => self.viewport.contains_point((point.x as i32, point.y as i32)),
+            MapShape::Circle => glm::distance(point, self.viewport_center()) <= self.radius()
+        }
+    }
+
+    // Part of the horizontal pixels row inside the circle, rect shape is clipped by the canvas
+    pub fn clip_span(&self, y: f32, x_from: f32, x_to: f32) -> Option<(f32, f32)> {
+        match self.shape {
+            MapShape::Rect => Some((x_from, x_to)),
+
+            MapShape::Circle => {
+                let center = self.viewport_center();
+                let radius = self.radius();
+                let distance_y = y - center.y;
+
+                if distance_y.abs() > radius {
+                    return None;
+                }
+
+                let half_width = (radius * radius - distance_y * distance_y).sqrt();
+                let (x_from, x_to) = (x_from.max(center.x - half_width), x_to.min(center.x + half_width));
+
+                if x_from > x_to { None } else { Some((x_from, x_to)) }
+            }
+        }
+    }
+
+    // Part of the screen segment inside the circle, rect shape is clipped by the canvas
+    pub fn clip_segment(&self, from: Vec2, to: Vec2) -> Option<(Vec2, Vec2)> {
+        match self.shape {
+            MapShape::Rect => Some((from, to)),
+
+            MapShape::Circle => {
+                let center = self.viewport_center();
+                let radius = self.radius();
+                let segment = to - from;
+                let offset = from - center;
+
+                // Solving |from + t * segment - center| = radius
+                let a = glm::dot(segment, segment);
+                let b = 2.0 * glm::dot(offset, segment);
+                let c = glm::dot(offset, offset) - radius * radius;
+
+                if a == 0.0 {
+                    return if c <= 0.0 { Some((from, to)) } else { None };
+                }
+
+                let discriminant = b * b - 4.0 * a * c;
+
+                if discriminant < 0.0 {
+                    return None;
+                }
+
+                let discriminant_root = discriminant.sqrt();
+                let t_from = ((-b - discriminant_root) / (2.0 * a)).max(0.0);
+                let t_to = ((-b + discriminant_root) / (2.0 * a)).min(1.0);
+
+                if t_from > t_to {
+                    return None;
+                }
+
+                Some((from + segment * t_from, from + segment * t_to))
+            }
+        }
+    }
+
+    pub fn viewport_center(&self) -> Vec2 {
+        vec2(
+            self.viewport.x() as f32 + self.viewport.width() as f32 / 2.0,
+            self.viewport.y() as f32 + self.viewport.height() as f32 / 2.0
+        )
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.viewport.width().min(self.viewport.height()) as f32 / 2.0
+    }
+}
@@ -1,52 +1,107 @@
 use std::cell::RefCell;
+use std::env;
+use std::path::PathBuf;
 use std::rc::Rc;
 
-use glm::vec2;
+use glm::{uvec2, vec2, UVec2};
 use sdl2::image::{InitFlag, LoadTexture};
 use sdl2::Sdl;
 
-use crate::game::game_state::Repositories;
+use crate::game::launch_options::LaunchOptions;
 use crate::game::graphics::model::camera::Camera;
+use crate::game::graphics::frame_recorder::{FrameRecorder, RecordingFormat};
+use crate::game::graphics::hud::Hud;
+use crate::game::graphics::ray_casting_pool::RayCastingPool;
 use crate::game::graphics::renderer::Renderer;
+use crate::game::graphics::screenshot::ScreenshotService;
+use crate::game::graphics::map_view::MapShape;
+use crate::game::graphics::view_mode::{automap_layout, MinimapSettings, ViewLayout, ViewMode};
+use crate::game::model::exploration::Exploration;
 
 pub mod model;
 pub mod ecs;
-mod renderer;
-mod ray_caster;
-mod tilemap_2d_render;
-mod tilemap_3d_render;
-mod linemap_2d_render;
-mod linemap_3d_render;
+pub mod renderer;
+mod scene_buffer;
+pub mod screenshot;
+pub mod frame_recorder;
+pub mod ray_caster;
+mod ray_casting_pool;
+mod ray_fan;
+mod world_2d_render;
+mod world_3d_render;
+pub mod view_mode;
+pub mod map_view;
+pub mod font;
+pub mod hud;
+mod profiler_overlay;
 
 #[must_use]
 pub struct Graphics {
     renderer: Rc<RefCell<Renderer>>,
-    rendering_state: Rc<RefCell<RenderingState>>
+    rendering_state: Rc<RefCell<RenderingState>>,
+    screenshot_service: Rc<RefCell<ScreenshotService>>,
+    frame_recorder: Rc<RefCell<FrameRecorder>>,
+    exploration: Rc<RefCell<Exploration>>,
+    hud: Rc<RefCell<Hud>>
 }
 
 impl Graphics {
 
-    pub fn initialize_graphics(sdl_context: &Sdl, repositories: &Repositories) -> Graphics {
+    pub fn initialize_graphics(sdl_context: &Sdl, options: &LaunchOptions) -> Graphics {
         log::info!("Initializing graphics module");
 
+        // Headless frames are rendered without a display, e.g. on build servers. Video driver given by the user is kept
+        if options.headless.is_some() && env::var_os("SDL_VIDEODRIVER").is_none() {
+            env::set_var("SDL_VIDEODRIVER", "offscreen");
+        }
+
         let video_subsystem = sdl_context.video().unwrap();
 
         sdl2::image::init(InitFlag::PNG | InitFlag::JPG).unwrap();
 
-        let window = video_subsystem.window("Raymarcher", 800, 600)
-            .position_centered()
-            .build()
-            .unwrap();
+        let rendering_state = RenderingState::new(options.window_size, options.rendering_distance, options.columns, options.view_mode, options.ray_casting_threads);
+        let internal_resolution = rendering_state.borrow().internal_resolution();
+
+        let mut window_builder = video_subsystem.window("Raymarcher", internal_resolution.x, internal_resolution.y);
+        window_builder.position_centered().resizable();
+
+        // Headless frames are rendered by the software renderer into the hidden offscreen window
+        if options.headless.is_some() {
+            window_builder.hidden();
+        }
+
+        let window = window_builder.build().unwrap();
 
-        let canvas = window.into_canvas().build().unwrap();
+        let canvas = match options.headless {
+            None => window.into_canvas().build().unwrap(),
+            Some(_) => window.into_canvas().software().build().unwrap()
+        };
 
         let canvas_ref = Rc::new(RefCell::new(canvas));
 
+        let renderer = Renderer::new(&canvas_ref);
+        renderer.set_internal_resolution(&internal_resolution).unwrap();
+
+        if options.is_fullscreen && options.headless.is_none() {
+            if let Err(error) = renderer.set_fullscreen(true) {
+                log::error!("Unable to enter fullscreen mode: {}", error);
+            }
+        }
+
+        let recording_format = options.recording.unwrap_or(RecordingFormat::PngSequence);
+
+        let screenshots_directory = env::var("SCREENSHOTS_DIR").unwrap_or("screenshots".to_string());
+        let recordings_directory = env::var("RECORDINGS_DIR").unwrap_or("recordings".to_string());
+
         log::info!("Initializing graphics module has been initialized");
 
         return Graphics {
-            rendering_state: RenderingState::new(),
-            renderer: Rc::new(RefCell::new(Renderer::new(&canvas_ref)))
+            rendering_state,
+            renderer: Rc::new(RefCell::new(renderer)),
+            screenshot_service: Rc::new(RefCell::new(ScreenshotService::new(PathBuf::from(screenshots_directory)))),
+            frame_recorder: Rc::new(RefCell::new(FrameRecorder::new(PathBuf::from(recordings_directory), recording_format, options.target_fps))),
+            exploration: Rc::new(RefCell::new(Exploration::default())),
+            hud: Rc::new(RefCell::new(Hud::new()))
         }
     }
 
@@ -58,8 +113,91 @@ impl Graphics {
     pub fn renderer(&self) -> &Rc<RefCell<Renderer>> {
         &self.renderer
     }
+
+    pub fn screenshot_service(&self) -> &Rc<RefCell<ScreenshotService>> {
+        &self.screenshot_service
+    }
+
+    pub fn frame_recorder(&self) -> &Rc<RefCell<FrameRecorder>> {
+        &self.frame_recorder
+    }
+
+    pub fn exploration(&self) -> &Rc<RefCell<Exploration>> {
+        &self.exploration
+    }
+
+    pub fn hud(&self) -> &Rc<RefCell<Hud>> {
+        &self.hud
+    }
+
+    pub fn change_resolution_scale(&self, difference: f32) {
+        let mut rendering_state = self.rendering_state.borrow_mut();
+        let resolution_scale = rendering_state.resolution_scale() + difference;
+        rendering_state.set_resolution_scale(resolution_scale);
+
+        log::info!("Resolution scale is set to {:.0}%", rendering_state.resolution_scale() * 100.0);
+    }
+
+    pub fn set_view_mode(&self, view_mode: ViewMode) {
+        self.rendering_state.borrow_mut().set_view_mode(view_mode);
+        log::info!("View mode is set to {:?}", view_mode);
+    }
+
+    pub fn switch_view_mode(&self) {
+        let view_mode = self.rendering_state.borrow().view_mode().next();
+        self.set_view_mode(view_mode);
+    }
+
+    pub fn toggle_automap(&self) {
+        let mut rendering_state = self.rendering_state.borrow_mut();
+        let is_automap_shown = !rendering_state.is_automap_shown();
+        rendering_state.set_automap_shown(is_automap_shown);
+    }
+
+    pub fn toggle_minimap_rotation(&self) {
+        let mut rendering_state = self.rendering_state.borrow_mut();
+        let minimap = rendering_state.minimap_mut();
+        minimap.is_rotating = !minimap.is_rotating;
+
+        log::info!("Minimap rotation is {}", if minimap.is_rotating { "enabled" } else { "disabled" });
+    }
+
+    pub fn switch_minimap_shape(&self) {
+        let mut rendering_state = self.rendering_state.borrow_mut();
+        let minimap = rendering_state.minimap_mut();
+
+        minimap.shape = match minimap.shape {
+            MapShape::Rect => MapShape::Circle,
+            MapShape::Circle => MapShape::Rect
+        };
+
+        log::info!("Minimap shape is set to {:?}", minimap.shape);
+    }
+
+    pub fn toggle_debug_info(&self) {
+        let mut hud = self.hud.borrow_mut();
+        let is_debug_shown = !hud.is_debug_shown();
+        hud.set_debug_shown(is_debug_shown);
+    }
+
+    pub fn toggle_profiler_overlay(&self) {
+        let mut hud = self.hud.borrow_mut();
+        let is_profiler_shown = !hud.is_profiler_shown();
+        hud.set_profiler_shown(is_profiler_shown);
+    }
+
+    pub fn toggle_fullscreen(&self) {
+        let renderer = self.renderer.borrow();
+
+        if let Err(error) = renderer.set_fullscreen(!renderer.is_fullscreen()) {
+            log::error!("Unable to toggle fullscreen mode: {}", error);
+        }
+    }
 }
 
+pub const MIN_RESOLUTION_SCALE: f32 = 0.25;
+pub const MAX_RESOLUTION_SCALE: f32 = 1.0;
+
 pub struct RenderingState {
 
     // Maximal rendering distance
@@ -67,16 +205,34 @@ pub struct RenderingState {
 
     camera: Camera,
 
-    // Total columns in 3d graphics
-    total_columns: u32,
+    // Resolution of the rendered image before scaling to the window
+    internal_resolution: UVec2,
+
+    // Part of the internal resolution used for 3d graphics, trades sharpness for speed
+    resolution_scale: f32,
+
+    ray_casting_pool: RayCastingPool,
+
+    // Fixed rays count per frame instead of one ray per scene column
+    columns: Option<u32>,
+
+    view_mode: ViewMode,
+    minimap: MinimapSettings,
+    is_automap_shown: bool
 }
 
 impl RenderingState {
-    pub fn new() -> Rc<RefCell<Self>> {
+    pub fn new(internal_resolution: UVec2, rendering_distance: f32, columns: Option<u32>, view_mode: ViewMode, ray_casting_threads: usize) -> Rc<RefCell<Self>> {
         let rendering_state = Self {
-            rendering_distance: 7.0,
-            total_columns: 120,
-            camera: Camera::new(vec2(3.0, 3.0), 0.0f32, 90f32.to_radians())
+            rendering_distance,
+            internal_resolution,
+            resolution_scale: 1.0,
+            ray_casting_pool: RayCastingPool::new(ray_casting_threads),
+            camera: Camera::new(vec2(3.0, 3.0), 0.0f32, 90f32.to_radians()),
+            columns,
+            view_mode,
+            minimap: MinimapSettings::default(),
+            is_automap_shown: false
         };
 
         Rc::new(RefCell::new(rendering_state))
@@ -91,7 +247,65 @@ impl RenderingState {
     pub fn camera_mut(&mut self) -> &mut Camera {
         &mut self.camera
     }
+    pub fn internal_resolution(&self) -> UVec2 {
+        self.internal_resolution
+    }
+
+    pub fn resolution_scale(&self) -> f32 {
+        self.resolution_scale
+    }
+    pub fn set_resolution_scale(&mut self, resolution_scale: f32) {
+        self.resolution_scale = resolution_scale.clamp(MIN_RESOLUTION_SCALE, MAX_RESOLUTION_SCALE);
+    }
+
+    pub fn ray_casting_pool(&self) -> &RayCastingPool {
+        &self.ray_casting_pool
+    }
+
+    pub fn view_mode(&self) -> ViewMode {
+        self.view_mode
+    }
+    pub fn set_view_mode(&mut self, view_mode: ViewMode) {
+        self.view_mode = view_mode;
+    }
+
+    pub fn minimap(&self) -> &MinimapSettings {
+        &self.minimap
+    }
+    pub fn minimap_mut(&mut self) -> &mut MinimapSettings {
+        &mut self.minimap
+    }
+
+    pub fn is_automap_shown(&self) -> bool {
+        self.is_automap_shown
+    }
+    pub fn set_automap_shown(&mut self, is_automap_shown: bool) {
+        self.is_automap_shown = is_automap_shown;
+    }
+
+    pub fn view_layout(&self) -> ViewLayout {
+        if self.is_automap_shown {
+            return automap_layout(self.internal_resolution);
+        }
+
+        self.view_mode.layout(self.internal_resolution)
+    }
+
+    // Resolution of the 3d graphics before scaling to the scene viewport
+    pub fn scene_resolution(&self) -> UVec2 {
+        let scene_sizes = match self.view_layout().scene {
+            Some(scene_viewport) => uvec2(scene_viewport.width(), scene_viewport.height()),
+            None => self.internal_resolution
+        };
+
+        uvec2(
+            ((scene_sizes.x as f32 * self.resolution_scale) as u32).max(1),
+            ((scene_sizes.y as f32 * self.resolution_scale) as u32).max(1)
+        )
+    }
+
+    // Total columns in 3d graphics, one ray per scene pixel column by default
     pub fn total_columns(&self) -> u32 {
-        self.total_columns
+        self.columns.unwrap_or(self.scene_resolution().x)
     }
 }
@@ -1,15 +1,24 @@
 use glm::Vec2;
 
+// Eye in the middle of the wall height
+const DEFAULT_EYE_HEIGHT: f32 = 0.5;
+
 pub struct Camera {
     position: Vec2,
     direction: f32,
-    fov: f32
+    fov: f32,
+
+    // Vertical look angle, rendered by shearing the horizon
+    pitch: f32,
+
+    // Height above the floor in wall heights
+    eye_height: f32
 }
 
 impl Camera {
 
     pub fn new(position: Vec2, direction: f32, fov: f32) -> Self {
-        Self { position, direction, fov }
+        Self { position, direction, fov, pitch: 0.0, eye_height: DEFAULT_EYE_HEIGHT }
     }
 
     pub fn position(&self) -> Vec2 {
@@ -24,6 +33,14 @@ impl Camera {
         self.fov
     }
 
+    pub fn pitch(&self) -> f32 {
+        self.pitch
+    }
+
+    pub fn eye_height(&self) -> f32 {
+        self.eye_height
+    }
+
     pub fn set_position(&mut self, position: Vec2) {
         self.position = position;
     }
@@ -33,4 +50,10 @@ impl Camera {
     pub fn set_fov(&mut self, fov: f32) {
         self.fov = fov;
     }
+    pub fn set_pitch(&mut self, pitch: f32) {
+        self.pitch = pitch;
+    }
+    pub fn set_eye_height(&mut self, eye_height: f32) {
+        self.eye_height = eye_height;
+    }
 }
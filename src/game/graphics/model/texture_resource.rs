@@ -2,18 +2,18 @@ use sdl2::render::Texture;
 use crate::game::model::repository::Resource;
 use crate::game::model::ResourceId;
 
-pub struct TextureResource<'r> {
+pub struct TextureResource {
     id: ResourceId,
-    sdl_texture: Texture<'r>
+    sdl_texture: Texture
 }
 
-impl<'r> TextureResource<'r> {
-    pub fn new(id: ResourceId, sdl_texture: Texture<'r>) -> Self {
+impl TextureResource {
+    pub fn new(id: ResourceId, sdl_texture: Texture) -> Self {
         Self { id, sdl_texture }
     }
 }
 
-impl Resource for TextureResource<'_> {
+impl Resource for TextureResource {
     fn id(&self) -> ResourceId {
         self.id
     }
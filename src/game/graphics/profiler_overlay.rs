@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use glm::uvec2;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+
+use crate::game::graphics::hud::{render_text_lines, text_block_sizes, HudAnchor, HUD_MARGIN};
+use crate::game::graphics::renderer::Renderer;
+use crate::game::profiler::{FrameProfiler, PROFILE_HISTORY_LEN};
+
+// Graph sizes in HUD pixels, graph top is twice the target frame time
+const GRAPH_HEIGHT: u32 = 50;
+const GRAPH_BAR_WIDTH: u32 = 1;
+const GRAPH_GAP: u32 = 3;
+
+const GRAPH_BACKGROUND_COLOR: Color = Color::RGB(24, 24, 24);
+const TARGET_LINE_COLOR: Color = Color::WHITE;
+const FRAME_TEXT_COLOR: Color = Color::WHITE;
+
+const SECTION_COLORS: [Color; 8] = [
+    Color::RGB(230, 90, 90),
+    Color::RGB(240, 170, 60),
+    Color::RGB(230, 230, 80),
+    Color::RGB(110, 210, 100),
+    Color::RGB(80, 200, 210),
+    Color::RGB(90, 130, 240),
+    Color::RGB(180, 110, 230),
+    Color::RGB(230, 120, 190)
+];
+
+// Averaged timings of the recent frames above the stacked graph of every recent frame
+pub fn render_profiler_overlay(profiler: &FrameProfiler, area: Rect, scale: u32, renderer: &Renderer) {
+    let Some(average_frame) = profiler.average_frame() else {
+        return;
+    };
+
+    let mut lines = vec![format!("frame {} ms", format_milliseconds(average_frame.frame_time()))];
+
+    for (name, time) in average_frame.sections() {
+        lines.push(format!("{} {} ms", name, format_milliseconds(*time)));
+    }
+
+    lines.push(format!(
+        "rays {} ms, {} tests",
+        format_milliseconds(average_frame.ray_casting_time()),
+        average_frame.intersection_tests()
+    ));
+
+    let text_sizes = text_block_sizes(&lines, scale);
+    let graph_sizes = uvec2(PROFILE_HISTORY_LEN as u32 * GRAPH_BAR_WIDTH * scale, GRAPH_HEIGHT * scale);
+    let overlay_sizes = uvec2(text_sizes.x.max(graph_sizes.x), text_sizes.y + GRAPH_GAP * scale + graph_sizes.y);
+
+    let overlay_position = HudAnchor::Bottom.place(overlay_sizes, area, HUD_MARGIN * scale);
+
+    let colored_lines: Vec<(&str, Color)> = lines.iter()
+        .enumerate()
+        .map(|(index, line)| match index {
+            0 => (line.as_str(), FRAME_TEXT_COLOR),
+            index if index <= average_frame.sections().len() => (line.as_str(), section_color(index - 1)),
+            _ => (line.as_str(), FRAME_TEXT_COLOR)
+        })
+        .collect();
+
+    render_text_lines(&colored_lines, overlay_position, scale, renderer);
+
+    let graph = Rect::new(
+        overlay_position.x,
+        overlay_position.y + (text_sizes.y + GRAPH_GAP * scale) as i32,
+        graph_sizes.x,
+        graph_sizes.y
+    );
+
+    render_frames_graph(profiler, graph, scale, renderer);
+}
+
+fn render_frames_graph(profiler: &FrameProfiler, graph: Rect, scale: u32, renderer: &Renderer) {
+    renderer.render_screen_rect(graph, GRAPH_BACKGROUND_COLOR);
+
+    let graph_time = profiler.target_frame_time() * 2;
+    let time_to_height = |time: Duration| (time.as_secs_f32() / graph_time.as_secs_f32() * graph.height() as f32) as u32;
+
+    let bar_width = GRAPH_BAR_WIDTH * scale;
+
+    for (frame_index, frame) in profiler.history().iter().enumerate() {
+        let bar_x = graph.x() + (frame_index as u32 * bar_width) as i32;
+        let mut bar_height = 0;
+
+        // Sections are stacked from the graph bottom in the update order
+        for (section_index, (_, time)) in frame.sections().iter().enumerate() {
+            let section_height = time_to_height(*time).min(graph.height() - bar_height);
+
+            if section_height > 0 {
+                let section_y = graph.bottom() - (bar_height + section_height) as i32;
+                renderer.render_screen_rect(Rect::new(bar_x, section_y, bar_width, section_height), section_color(section_index));
+            }
+
+            bar_height += section_height;
+        }
+    }
+
+    let target_line_y = graph.bottom() - time_to_height(profiler.target_frame_time()) as i32;
+    renderer.render_screen_rect(Rect::new(graph.x(), target_line_y, graph.width(), scale), TARGET_LINE_COLOR);
+}
+
+fn section_color(section_index: usize) -> Color {
+    SECTION_COLORS[section_index % SECTION_COLORS.len()]
+}
+
+fn format_milliseconds(time: Duration) -> String {
+    format!("{:.2}", time.as_secs_f64() * 1000.0)
+}
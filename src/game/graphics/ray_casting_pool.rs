@@ -0,0 +1,241 @@
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::thread::JoinHandle;
+
+use crate::game::graphics::ray_caster::HitDetails;
+use crate::game::graphics::ray_fan::{RayFan, RayFanSettings};
+use crate::game::model::raycast_world::RaycastWorld;
+
+// Amount of column chunks per worker. Several chunks per worker smooth out uneven chunks costs
+const CHUNKS_PER_WORKER: u32 = 4;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+// Worker threads for casting fan columns in parallel. Pool with a single thread casts columns serially
+// on the calling thread, so both paths compute each column with the same function and inputs
+pub struct RayCastingPool {
+    threads_count: usize,
+    job_sender: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>
+}
+
+impl RayCastingPool {
+    pub fn new(threads_count: usize) -> Self {
+        let threads_count = threads_count.max(1);
+
+        if threads_count == 1 {
+            return Self { threads_count, job_sender: None, workers: vec![] };
+        }
+
+        let (job_sender, job_receiver) = channel::<Job>();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        let workers = (0..threads_count)
+            .map(|worker_index| {
+                let job_receiver = job_receiver.clone();
+
+                thread::Builder::new()
+                    .name(format!("ray-caster-{}", worker_index))
+                    .spawn(move || run_worker(&job_receiver))
+                    .unwrap()
+            })
+            .collect();
+
+        log::info!("Ray casting pool has been started with {} threads", threads_count);
+
+        Self { threads_count, job_sender: Some(job_sender), workers }
+    }
+
+    // Casts the whole fan and returns hits ordered by column
+    pub fn cast_fan<M>(&self, map: &Arc<M>, settings: RayFanSettings) -> Vec<HitDetails>
+        where M: RaycastWorld + ?Sized + 'static
+    {
+        let fan = RayFan::new(map.as_ref(), settings);
+
+        let job_sender = match &self.job_sender {
+            Some(job_sender) if fan.len() > 1 => job_sender,
+            _ => return fan.collect()
+        };
+
+        let chunks_columns: Vec<Range<u32>> = fan
+            .split(self.threads_count as u32 * CHUNKS_PER_WORKER)
+            .map(|chunk| chunk.columns())
+            .collect();
+
+        let (chunk_sender, chunk_receiver) = channel::<(usize, Vec<HitDetails>)>();
+
+        for (chunk_index, columns) in chunks_columns.iter().cloned().enumerate() {
+            let map = map.clone();
+            let chunk_sender = chunk_sender.clone();
+
+            let job: Job = Box::new(move || {
+                let hits = RayFan::new(map.as_ref(), settings).with_columns(columns).collect();
+                // Receiver is alive until all chunks are received
+                let _ = chunk_sender.send((chunk_index, hits));
+            });
+
+            // Workers are gone, the rest of chunks are cast below
+            if job_sender.send(job).is_err() {
+                break;
+            }
+        }
+
+        drop(chunk_sender);
+
+        let mut chunks: Vec<Option<Vec<HitDetails>>> = chunks_columns.iter().map(|_| None).collect();
+
+        for (chunk_index, hits) in chunk_receiver {
+            chunks[chunk_index] = Some(hits);
+        }
+
+        // Chunks of the failed workers are cast serially, so the frame is still complete
+        let missing_chunks_count = chunks.iter().filter(|chunk| chunk.is_none()).count();
+
+        if missing_chunks_count > 0 {
+            log::error!("Ray casting workers have failed, {} of {} chunks are cast on the calling thread", missing_chunks_count, chunks.len());
+        }
+
+        chunks.into_iter()
+            .zip(chunks_columns)
+            .flat_map(|(hits, columns)| hits.unwrap_or_else(|| RayFan::new(map.as_ref(), settings).with_columns(columns).collect()))
+            .collect()
+    }
+}
+
+impl Drop for RayCastingPool {
+    fn drop(&mut self) {
+        // Closing jobs channel stops workers
+        drop(self.job_sender.take());
+
+        for worker in self.workers.drain(..) {
+            if worker.join().is_err() {
+                log::error!("Ray casting worker thread has panicked");
+            }
+        }
+    }
+}
+
+fn run_worker(job_receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = match job_receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return
+        };
+
+        match job {
+            Ok(job) => job(),
+            Err(_) => return
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use glm::vec2;
+
+    use crate::game::graphics::ray_caster::HitDetails;
+    use crate::game::graphics::ray_casting_pool::RayCastingPool;
+    use crate::game::graphics::ray_fan::RayFanSettings;
+    use crate::game::model::linemap::Linemap;
+    use crate::game::model::object_color::ObjectColor;
+    use crate::game::model::ray::Hit;
+    use crate::game::model::raycast_world::RaycastWorld;
+    use crate::game::model::tile::Tile;
+    use crate::game::model::tilemap::Tilemap;
+
+    const TOTAL_COLUMNS: u32 = 317;
+
+    fn test_tilemap() -> Tilemap {
+        let empty = Arc::new(Tile::new(0, ObjectColor::WHITE, false));
+        let red = Arc::new(Tile::new(1, ObjectColor::RED, true));
+        let blue = Arc::new(Tile::new(2, ObjectColor::BLUE, true));
+
+        let rows = [
+            "########",
+            "#......#",
+            "#..b...#",
+            "#......#",
+            "#...#..#",
+            "#.b....#",
+            "#......#",
+            "########"
+        ];
+
+        let raw_tilemap = rows.iter()
+            .map(|row| row.chars()
+                .map(|cell| match cell {
+                    '#' => &red,
+                    'b' => &blue,
+                    _ => &empty
+                })
+                .collect())
+            .collect();
+
+        Tilemap::from_raw_tilemap(0, raw_tilemap)
+    }
+
+    fn test_linemap() -> Linemap {
+        let mut linemap = Linemap::new(0);
+
+        linemap
+            .add_line(ObjectColor::RED, vec2(0.0, 0.0), vec2(8.0, 0.0))
+            .add_line(ObjectColor::RED, vec2(8.0, 0.0), vec2(8.0, 8.0))
+            .add_line(ObjectColor::GREEN, vec2(8.0, 8.0), vec2(0.0, 8.0))
+            .add_line(ObjectColor::GREEN, vec2(0.0, 8.0), vec2(0.0, 0.0))
+            .add_line(ObjectColor::BLUE, vec2(2.0, 2.5), vec2(3.5, 4.0))
+            .add_line(ObjectColor::YELLOW, vec2(5.0, 1.0), vec2(5.5, 6.0));
+
+        linemap
+    }
+
+    fn assert_same_hits(serial_hits: &[HitDetails], parallel_hits: &[HitDetails]) {
+        assert_eq!(serial_hits.len(), parallel_hits.len());
+
+        for (serial, parallel) in serial_hits.iter().zip(parallel_hits) {
+            assert_eq!(serial.column(), parallel.column());
+            assert_eq!(serial.total_columns(), parallel.total_columns());
+            assert_eq!(serial.ray().end_position(), parallel.ray().end_position(), "column {}", serial.column());
+            assert_eq!(serial.ray().distance().to_bits(), parallel.ray().distance().to_bits(), "column {}", serial.column());
+
+            match (serial.hit(), parallel.hit()) {
+                (Hit::None, Hit::None) => {}
+                (Hit::Wall { color: serial_color, is_collision_enabled: serial_collision },
+                    Hit::Wall { color: parallel_color, is_collision_enabled: parallel_collision }) => {
+                    assert_eq!(serial_color.name(), parallel_color.name(), "column {}", serial.column());
+                    assert_eq!(serial_collision, parallel_collision, "column {}", serial.column());
+                }
+                _ => panic!("column {} hits differ", serial.column())
+            }
+        }
+    }
+
+    fn assert_parallel_fan_is_serial<M: RaycastWorld + 'static>(map: M) {
+        let map = Arc::new(map);
+        let serial_pool = RayCastingPool::new(1);
+        let parallel_pool = RayCastingPool::new(4);
+
+        for direction in [0.0f32, 0.7, 2.1, 3.9, 5.5] {
+            let settings = RayFanSettings::new(vec2(3.5, 3.5), direction, 7.0, TOTAL_COLUMNS);
+
+            let serial_hits = serial_pool.cast_fan(&map, settings);
+            let parallel_hits = parallel_pool.cast_fan(&map, settings);
+
+            assert_eq!(serial_hits.len(), TOTAL_COLUMNS as usize);
+            assert_same_hits(&serial_hits, &parallel_hits);
+        }
+    }
+
+    #[test]
+    fn parallel_tilemap_fan_is_identical_to_serial() {
+        assert_parallel_fan_is_serial(test_tilemap());
+    }
+
+    #[test]
+    fn parallel_linemap_fan_is_identical_to_serial() {
+        assert_parallel_fan_is_serial(test_linemap());
+    }
+}
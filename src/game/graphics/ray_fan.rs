@@ -0,0 +1,115 @@
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+use glm::Vec2;
+
+use crate::game::graphics::ray_caster::{relative_ray_angle, HitDetails};
+use crate::game::graphics::RenderingState;
+use crate::game::model::raycast_world::RaycastWorld;
+
+// Camera parameters shared by all rays of a fan
+#[derive(Copy, Clone)]
+pub struct RayFanSettings {
+    camera_position: Vec2,
+    camera_direction: f32,
+    rendering_distance: f32,
+    total_columns: u32
+}
+
+impl RayFanSettings {
+    pub fn new(camera_position: Vec2, camera_direction: f32, rendering_distance: f32, total_columns: u32) -> Self {
+        Self { camera_position, camera_direction, rendering_distance, total_columns }
+    }
+
+    pub fn from_rendering_state(rendering_state: &RenderingState) -> Self {
+        Self::new(
+            rendering_state.camera().position(),
+            rendering_state.camera().direction(),
+            rendering_state.rendering_distance(),
+            rendering_state.total_columns()
+        )
+    }
+}
+
+// Lazy iterator over camera rays. Each ray is cast only when it is requested, skipped rays are not cast at all
+pub struct RayFan<'m, M: RaycastWorld + ?Sized> {
+    map: &'m M,
+    settings: RayFanSettings,
+    columns: Range<u32>
+}
+
+impl<'m, M: RaycastWorld + ?Sized> RayFan<'m, M> {
+    pub fn new(map: &'m M, settings: RayFanSettings) -> Self {
+        Self { map, settings, columns: 0..settings.total_columns }
+    }
+
+    // Columns which are not yielded yet
+    pub fn columns(&self) -> Range<u32> {
+        self.columns.clone()
+    }
+
+    // Restricts fan to given columns, keeping rays angles of the whole fan
+    pub fn with_columns(self, columns: Range<u32>) -> Self {
+        let start = columns.start.clamp(self.columns.start, self.columns.end);
+        let end = columns.end.clamp(start, self.columns.end);
+
+        Self { columns: start..end, ..self }
+    }
+
+    // Splits fan into given amount of parts with almost equal columns count
+    pub fn split(self, parts_count: u32) -> impl Iterator<Item = RayFan<'m, M>> {
+        let parts_count = parts_count.clamp(1, self.columns.len().max(1) as u32);
+        let Range { start, end } = self.columns;
+        let length = end - start;
+
+        (0..parts_count).map(move |part| {
+            let part_start = start + length * part / parts_count;
+            let part_end = start + length * (part + 1) / parts_count;
+
+            Self { columns: part_start..part_end, ..self }
+        })
+    }
+
+    fn cast_column(&self, column: u32) -> HitDetails {
+        let settings = &self.settings;
+        let ray_angle = settings.camera_direction + relative_ray_angle(column, settings.total_columns);
+        let (ray, hit) = self.map.cast_ray(settings.camera_position, ray_angle, settings.rendering_distance);
+
+        HitDetails::new(column, settings.total_columns, ray, hit)
+    }
+}
+
+impl<M: RaycastWorld + ?Sized> Clone for RayFan<'_, M> {
+    fn clone(&self) -> Self {
+        Self { map: self.map, settings: self.settings, columns: self.columns.clone() }
+    }
+}
+
+impl<M: RaycastWorld + ?Sized> Iterator for RayFan<'_, M> {
+    type Item = HitDetails;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.columns.next().map(|column| self.cast_column(column))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.columns.size_hint()
+    }
+
+    // Skipped columns are not cast, so step_by() and skip() are cheap
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.columns.nth(n).map(|column| self.cast_column(column))
+    }
+}
+
+impl<M: RaycastWorld + ?Sized> DoubleEndedIterator for RayFan<'_, M> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.columns.next_back().map(|column| self.cast_column(column))
+    }
+}
+
+impl<M: RaycastWorld + ?Sized> ExactSizeIterator for RayFan<'_, M> {
+}
+
+impl<M: RaycastWorld + ?Sized> FusedIterator for RayFan<'_, M> {
+}
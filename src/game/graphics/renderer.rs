@@ -1,73 +1,222 @@
-use std::cell::{RefCell, RefMut};
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
-use glm::{UVec2, Vec2};
+use glm::{vec2, UVec2, Vec2};
 use sdl2::pixels::Color;
 use sdl2::rect::{Point, Rect};
-use sdl2::render::WindowCanvas;
-
-use crate::game::graphics::ray_caster::{Hit, HitDetails, Ray};
+use sdl2::render::{Texture, TextureCreator, WindowCanvas};
+use sdl2::video::{FullscreenType, WindowContext};
+
+use crate::game::graphics::font::{glyph, GLYPH_CELL, GLYPH_WIDTH};
+use crate::game::graphics::map_view::{MapShape, MapView};
+use crate::game::graphics::model::camera::Camera;
+use crate::game::graphics::ray_caster::HitDetails;
+use crate::game::graphics::scene_buffer::{SCENE_PIXEL_FORMAT, SceneBuffer};
+use crate::game::graphics::screenshot::{FRAME_CAPTURE_FORMAT, FrameCapture};
 use crate::game::graphics::RenderingState;
+use crate::game::model::exploration::Exploration;
 use crate::game::model::object_color::ObjectColor;
-use crate::game::model::tilemap::PlacedTile;
+use crate::game::model::ray::{Hit, Ray};
+
+// Lines hidden by the fog of war are revealed by pieces of this length
+const FOG_LINE_PIECE_LENGTH: f32 = 0.25;
+const CIRCLE_BORDER_SEGMENTS: u32 = 48;
 
-const TILE_SIZE: Vec2 = Vec2 { x: 32f32, y: 32f32 };
+const SKY_COLOR: ObjectColor = ObjectColor::COLOR { color: &Color::RGB(135, 206, 235) };
+const FLOOR_COLOR: ObjectColor = ObjectColor::GRAY;
+
+// Walls are one unit high, eye height is given in the same units
+const WALL_HEIGHT: f32 = 1.0;
 
 pub struct Renderer {
-    canvas: Rc<RefCell<WindowCanvas>>
+    canvas: Rc<RefCell<WindowCanvas>>,
+
+    // Textures are not bound to the creator lifetime, so the scene texture is destroyed by the renderer
+    texture_creator: TextureCreator<WindowContext>,
+    scene_texture: RefCell<Option<Texture>>,
+    scene_buffer: RefCell<SceneBuffer>,
+
+    // Transform of 2d map coordinates into the map viewport
+    map_view: Cell<MapView>,
+
+    // Only explored walls are drawn in the map view when set
+    map_fog: RefCell<Option<Rc<RefCell<Exploration>>>>
 }
 
 impl Renderer {
     pub fn new(canvas: &Rc<RefCell<WindowCanvas>>) -> Self {
-        Self { canvas: canvas.clone() }
+        let texture_creator = canvas.borrow().texture_creator();
+
+        Self {
+            canvas: canvas.clone(),
+            texture_creator,
+            scene_texture: RefCell::new(None),
+            scene_buffer: RefCell::new(SceneBuffer::new()),
+            map_view: Cell::new(MapView::fit(Rect::new(0, 0, 1, 1), vec2(1.0, 1.0))),
+            map_fog: RefCell::new(None)
+        }
     }
 
     pub fn clear(&self) {
         let mut canvas = self.canvas.borrow_mut();
-        canvas.set_draw_color(Color::WHITE);
+
+        // Whole window is cleared to draw letterboxing bars, viewport only is filled with background
+        canvas.set_draw_color(Color::BLACK);
         canvas.clear();
+        canvas.set_draw_color(Color::WHITE);
+        canvas.fill_rect(None).unwrap();
     }
 
     pub fn show(&self) {
         self.canvas.borrow_mut().present();
     }
 
-    pub fn render_2d_placed_tile(&self, tile_position: &UVec2, placed_tile: &PlacedTile) {
-        let mut canvas = self.canvas.borrow_mut();
+    // Prepares scene buffer for 3d columns rendering in given resolution
+    pub fn begin_scene(&self, resolution: UVec2) {
+        self.scene_buffer.borrow_mut().resize(resolution);
+    }
 
-        let color = placed_tile.tile().color();
+    // Uploads 3d columns to the streaming texture and stretches it over the scene viewport
+    pub fn present_scene(&self, viewport: Rect) {
+        let scene_buffer = self.scene_buffer.borrow();
+        let sizes = scene_buffer.sizes();
+        let mut scene_texture = self.scene_texture.borrow_mut();
+
+        let is_texture_outdated = scene_texture.as_ref()
+            .is_none_or(|texture| {
+                let query = texture.query();
+                query.width != sizes.x || query.height != sizes.y
+            });
+
+        if is_texture_outdated {
+            let texture = self.texture_creator
+                .create_texture_streaming(SCENE_PIXEL_FORMAT, sizes.x, sizes.y)
+                .unwrap();
+
+            if let Some(outdated_texture) = scene_texture.replace(texture) {
+                // SAFETY: canvas is kept alive by the renderer, so its SDL renderer still owns the texture
+                unsafe { outdated_texture.destroy() };
+            }
+        }
 
-        let tile_rect = Rect::new(
-            (tile_position.x as f32 * TILE_SIZE.x) as i32,
-            (tile_position.y as f32 * TILE_SIZE.y) as i32,
-            TILE_SIZE.x as u32,
-            TILE_SIZE.y as u32
-        );
+        let texture = scene_texture.as_mut().unwrap();
+        texture.update(None, scene_buffer.pixels(), scene_buffer.pitch()).unwrap();
+
+        self.canvas.borrow_mut().copy(texture, None, viewport).unwrap();
+    }
+
+    // Reads back current frame without letterboxing bars. Must be called before show()
+    pub fn capture_frame(&self) -> Result<FrameCapture, String> {
+        let canvas = self.canvas.borrow();
+        let viewport = letterboxed_viewport(&canvas)?;
+        let pixels = canvas.read_pixels(viewport, FRAME_CAPTURE_FORMAT)?;
+
+        Ok(FrameCapture::new(viewport.width(), viewport.height(), pixels))
+    }
+
+    // Internal resolution is scaled to the window size keeping aspect ratio
+    pub fn set_internal_resolution(&self, resolution: &UVec2) -> Result<(), String> {
+        self.canvas.borrow_mut()
+            .set_logical_size(resolution.x, resolution.y)
+            .map_err(|error| error.to_string())
+    }
 
-        draw_colored_rect(&mut canvas, &tile_rect, color)
+    pub fn is_fullscreen(&self) -> bool {
+        self.canvas.borrow().window().fullscreen_state() != FullscreenType::Off
     }
 
-    pub fn render_2d_rect(&self, color: &ObjectColor, position: &Vec2, size: &Vec2) {
+    pub fn set_fullscreen(&self, is_fullscreen: bool) -> Result<(), String> {
+        let fullscreen_type = if is_fullscreen { FullscreenType::Desktop } else { FullscreenType::Off };
+        self.canvas.borrow_mut().window_mut().set_fullscreen(fullscreen_type)
+    }
+
+    // 2d drawing is transformed and clipped by the map view until end_map_view() is called
+    pub fn begin_map_view(&self, map_view: MapView, fog: Option<&Rc<RefCell<Exploration>>>) {
+        self.map_view.set(map_view);
+        *self.map_fog.borrow_mut() = fog.cloned();
+
+        let viewport = map_view.viewport();
         let mut canvas = self.canvas.borrow_mut();
+        canvas.set_clip_rect(viewport);
+
+        match map_view.shape() {
+            MapShape::Rect => {
+                canvas.set_draw_color(Color::WHITE);
+                canvas.fill_rect(viewport).unwrap();
+                canvas.set_draw_color(Color::BLACK);
+                canvas.draw_rect(viewport).unwrap();
+            }
+
+            MapShape::Circle => {
+                let viewport_corners = [
+                    vec2(viewport.left() as f32, viewport.top() as f32),
+                    vec2(viewport.right() as f32, viewport.top() as f32),
+                    vec2(viewport.right() as f32, viewport.bottom() as f32),
+                    vec2(viewport.left() as f32, viewport.bottom() as f32)
+                ];
+
+                canvas.set_draw_color(Color::WHITE);
+                fill_convex_polygon(&mut canvas, &map_view, &viewport_corners);
+
+                let center = map_view.viewport_center();
+                let radius = map_view.radius() - 1.0;
+                let border_points: Vec<Point> = (0..=CIRCLE_BORDER_SEGMENTS)
+                    .map(|segment| segment as f32 / CIRCLE_BORDER_SEGMENTS as f32 * std::f32::consts::TAU)
+                    .map(|angle| Point::new((center.x + radius * angle.cos()) as i32, (center.y + radius * angle.sin()) as i32))
+                    .collect();
+
+                canvas.set_draw_color(Color::BLACK);
+                canvas.draw_lines(border_points.as_slice()).unwrap();
+            }
+        }
+    }
 
-        canvas.set_draw_color(*resolve_object_color(color));
+    pub fn end_map_view(&self) {
+        self.clear_map_fog();
+        self.canvas.borrow_mut().set_clip_rect(None);
+    }
 
-        let tile_rect = Rect::new(
-            position.x as i32,
-            position.y as i32,
-            size.x as u32,
-            size.y as u32
-        );
+    pub fn clear_map_fog(&self) {
+        *self.map_fog.borrow_mut() = None;
+    }
+
+    // Tile sized rect with top left corner at given map position, for tiles between cells
+    pub fn render_2d_tile_at(&self, position: &Vec2, color: &ObjectColor) {
+        if !self.is_revealed(*position + vec2(0.5, 0.5)) {
+            return;
+        }
+
+        let mut canvas = self.canvas.borrow_mut();
+        let map_view = self.map_view.get();
+
+        if !map_view.is_rotated() && map_view.shape() == MapShape::Rect {
+            let tile_rect = map_rect(&map_view, position, &vec2(1.0, 1.0));
+            return draw_colored_rect(&mut canvas, &tile_rect, color);
+        }
 
-        canvas.fill_rect(tile_rect).unwrap();
+        if let ObjectColor::COLOR { color } = color {
+            let tile_corners = [vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(1.0, 1.0), vec2(0.0, 1.0)]
+                .map(|corner| map_view.to_screen(&(*position + corner)));
+
+            canvas.set_draw_color(**color);
+            fill_convex_polygon(&mut canvas, &map_view, &tile_corners);
+        }
     }
 
+    // Point size is given in pixels, so the point is visible in any map scale
     pub fn render_2d_point(&self, point_center: &Vec2, point_size: u32) {
         let mut canvas = self.canvas.borrow_mut();
+        let map_view = self.map_view.get();
+
+        let point_center = map_view.to_screen(point_center);
+
+        if !map_view.contains(point_center) {
+            return;
+        }
 
         let camera_point_rect = Rect::new(
-            (point_center.x * TILE_SIZE.x) as i32 - (point_size / 2) as i32,
-            (point_center.y * TILE_SIZE.y) as i32 - (point_size / 2) as i32,
+            point_center.x as i32 - (point_size / 2) as i32,
+            point_center.y as i32 - (point_size / 2) as i32,
             point_size,
             point_size
         );
@@ -78,67 +227,175 @@ impl Renderer {
 
     pub fn render_2d_line(&self, from: &Vec2, to: &Vec2, color: &ObjectColor) {
         let mut canvas = self.canvas.borrow_mut();
+        let map_view = self.map_view.get();
 
         canvas.set_draw_color(*resolve_object_color(color));
 
-        canvas.draw_line(
-            Point::new(
-                (from.x * TILE_SIZE.x) as i32,
-                (from.y * TILE_SIZE.y) as i32
-            ),
-
-            Point::new(
-                (to.x * TILE_SIZE.x) as i32,
-                (to.y * TILE_SIZE.y) as i32
-            )
-        ).unwrap()
+        if self.map_fog.borrow().is_none() {
+            return draw_map_segment(&mut canvas, &map_view, from, to);
+        }
+
+        let pieces_count = ((glm::distance(*from, *to) / FOG_LINE_PIECE_LENGTH).ceil() as u32).max(1);
+
+        for piece in 0..pieces_count {
+            let piece_from = *from + (*to - *from) * (piece as f32 / pieces_count as f32);
+            let piece_to = *from + (*to - *from) * ((piece + 1) as f32 / pieces_count as f32);
+
+            if self.is_revealed((piece_from + piece_to) / 2.0) {
+                draw_map_segment(&mut canvas, &map_view, &piece_from, &piece_to);
+            }
+        }
     }
 
-    fn render_column(&self, ray: &Ray, column: u32, total_column: u32, color: &ObjectColor) {
+    // Text is drawn in internal resolution pixels, every glyph pixel is a square of the scale size
+    pub fn render_text(&self, text: &str, position: Point, scale: u32, color: Color) {
+        let mut glyph_pixels: Vec<Rect> = vec![];
+
+        for (index, character) in text.chars().enumerate() {
+            let glyph_x = position.x + (index as u32 * GLYPH_CELL.x * scale) as i32;
+
+            for (row, row_bits) in glyph(character).iter().enumerate() {
+                for column in 0..GLYPH_WIDTH {
+                    if row_bits & (1 << (GLYPH_WIDTH - 1 - column)) != 0 {
+                        glyph_pixels.push(Rect::new(
+                            glyph_x + (column * scale) as i32,
+                            position.y + (row as u32 * scale) as i32,
+                            scale,
+                            scale
+                        ));
+                    }
+                }
+            }
+        }
+
         let mut canvas = self.canvas.borrow_mut();
+        canvas.set_draw_color(color);
+        canvas.fill_rects(&glyph_pixels).unwrap();
+    }
 
-        let (width, height) = canvas.window().size();
+    pub fn render_screen_rect(&self, rect: Rect, color: Color) {
+        let mut canvas = self.canvas.borrow_mut();
+        canvas.set_draw_color(color);
+        canvas.fill_rect(rect).unwrap();
+    }
 
-        let column_width = (width as f32 * (1f32 / total_column as f32)) as u32 + 1;
+    fn is_revealed(&self, position: Vec2) -> bool {
+        self.map_fog.borrow().as_ref()
+            .is_none_or(|exploration| exploration.borrow().is_explored(position))
+    }
 
-        let wall_height = 1.0f32 / ray.distance();
-        let view_wall_height = ((height as f32) * wall_height.min(1.0f32)) as u32;
+    fn render_column(&self, ray: &Ray, column: u32, total_column: u32, color: &ObjectColor, camera: &Camera) {
+        let mut scene_buffer = self.scene_buffer.borrow_mut();
 
-        let wall_top = (height - view_wall_height) / 2;
-        let wall_bottom = wall_top + view_wall_height;
+        let sizes = scene_buffer.sizes();
+        let (width, height) = (sizes.x, sizes.y);
 
-        let column_x = (column_width * column) as i32;
+        // Column borders are computed separately, so columns neither overlap nor leave gaps
+        let column_x = (width as u64 * column as u64 / total_column as u64) as u32;
+        let next_column_x = (width as u64 * (column as u64 + 1) / total_column as u64) as u32;
+
+        // Y-shearing: looking up or down moves the horizon instead of rotating the projection plane
+        let horizon = height as f32 / 2.0 + camera.pitch().tan() * height as f32;
+
+        // Wall of the unit height at the unit distance fills the view height
+        let projection_scale = height as f32 / ray.distance();
+
+        let wall_top = (horizon - (WALL_HEIGHT - camera.eye_height()) * projection_scale).clamp(0.0, height as f32) as u32;
+        let wall_bottom = (horizon + camera.eye_height() * projection_scale).clamp(0.0, height as f32) as u32;
 
         // Ceiling
-        const SKY_COLOR: ObjectColor = ObjectColor::COLOR { color: &Color::RGB(135, 206, 235) };
-        let ceiling_rect = Rect::new(
-            column_x,
-            0,
-            column_width,
-            wall_top
-        );
-        draw_colored_rect(&mut canvas, &ceiling_rect, &SKY_COLOR);
+        fill_colored_scene_rect(&mut scene_buffer, column_x, next_column_x, 0, wall_top, &SKY_COLOR);
 
         // Wall
-        let column_rect = Rect::new(
-            column_x,
-            wall_top as i32,
-            column_width,
-            wall_bottom - wall_top
-        );
-        draw_colored_rect(&mut canvas, &column_rect, &color);
+        fill_colored_scene_rect(&mut scene_buffer, column_x, next_column_x, wall_top, wall_bottom, color);
 
         // Floor
-        let ceiling_rect = Rect::new(
-            column_x,
-            wall_bottom as i32,
-            column_width,
-            height - wall_bottom
-        );
-        draw_colored_rect(&mut canvas, &ceiling_rect, &ObjectColor::GRAY);
+        fill_colored_scene_rect(&mut scene_buffer, column_x, next_column_x, wall_bottom, height, &FLOOR_COLOR);
     }
 }
 
+impl Drop for Renderer {
+    fn drop(&mut self) {
+        if let Some(scene_texture) = self.scene_texture.get_mut().take() {
+            // SAFETY: fields are dropped after this call, so the canvas and its SDL renderer are still alive
+            unsafe { scene_texture.destroy() };
+        }
+    }
+}
+
+// Rect borders are transformed separately, so neighbour tiles neither overlap nor leave gaps
+fn map_rect(map_view: &MapView, position: &Vec2, size: &Vec2) -> Rect {
+    let top_left = map_view.to_screen(position);
+    let bottom_right = map_view.to_screen(&(*position + *size));
+
+    Rect::new(
+        top_left.x as i32,
+        top_left.y as i32,
+        ((bottom_right.x as i32) - (top_left.x as i32)).max(1) as u32,
+        ((bottom_right.y as i32) - (top_left.y as i32)).max(1) as u32
+    )
+}
+
+fn draw_map_segment(canvas: &mut WindowCanvas, map_view: &MapView, from: &Vec2, to: &Vec2) {
+    if let Some((from, to)) = map_view.clip_segment(map_view.to_screen(from), map_view.to_screen(to)) {
+        canvas.draw_line(
+            Point::new(from.x as i32, from.y as i32),
+            Point::new(to.x as i32, to.y as i32)
+        ).unwrap()
+    }
+}
+
+// Fills polygon given in screen coordinates by horizontal rows, so it can be clipped by the circle
+fn fill_convex_polygon(canvas: &mut WindowCanvas, map_view: &MapView, points: &[Vec2]) {
+    let min_y = points.iter().map(|point| point.y).fold(f32::MAX, f32::min).floor() as i32;
+    let max_y = points.iter().map(|point| point.y).fold(f32::MIN, f32::max).ceil() as i32;
+
+    for y in min_y..max_y {
+        let row_y = y as f32 + 0.5;
+        let mut span: Option<(f32, f32)> = None;
+
+        for (index, from) in points.iter().enumerate() {
+            let to = &points[(index + 1) % points.len()];
+
+            if (from.y <= row_y) != (to.y <= row_y) {
+                let x = from.x + (row_y - from.y) / (to.y - from.y) * (to.x - from.x);
+                span = Some(span.map_or((x, x), |(x_from, x_to)| (x_from.min(x), x_to.max(x))));
+            }
+        }
+
+        let Some((x_from, x_to)) = span.and_then(|(x_from, x_to)| map_view.clip_span(row_y, x_from, x_to)) else {
+            continue;
+        };
+
+        let (x_from, x_to) = (x_from.round() as i32, x_to.round() as i32 - 1);
+
+        if x_from <= x_to {
+            canvas.draw_line(Point::new(x_from, y), Point::new(x_to, y)).unwrap();
+        }
+    }
+}
+
+// Area of the output which is covered by the logical size, in output pixels
+fn letterboxed_viewport(canvas: &WindowCanvas) -> Result<Rect, String> {
+    let (output_width, output_height) = canvas.output_size()?;
+    let (logical_width, logical_height) = canvas.logical_size();
+
+    if logical_width == 0 || logical_height == 0 {
+        return Ok(Rect::new(0, 0, output_width, output_height));
+    }
+
+    let scale = (output_width as f32 / logical_width as f32).min(output_height as f32 / logical_height as f32);
+    let viewport_width = ((logical_width as f32 * scale) as u32).clamp(1, output_width);
+    let viewport_height = ((logical_height as f32 * scale) as u32).clamp(1, output_height);
+
+    Ok(Rect::new(
+        ((output_width - viewport_width) / 2) as i32,
+        ((output_height - viewport_height) / 2) as i32,
+        viewport_width,
+        viewport_height
+    ))
+}
+
 fn draw_colored_rect(canvas: &mut WindowCanvas, rect: &Rect, color: &ObjectColor) {
     match color {
         ObjectColor::COLOR { color } => {
@@ -149,6 +406,15 @@ fn draw_colored_rect(canvas: &mut WindowCanvas, rect: &Rect, color: &ObjectColor
     }
 }
 
+fn fill_colored_scene_rect(scene_buffer: &mut SceneBuffer, x_from: u32, x_to: u32, y_from: u32, y_to: u32, color: &ObjectColor) {
+    match color {
+        ObjectColor::COLOR { color } => {
+            scene_buffer.fill_rect(x_from, x_to, y_from, y_to, color);
+        }
+        ObjectColor::TEXTURE { .. } => {}
+    }
+}
+
 fn resolve_object_color(color: &ObjectColor) -> &'static Color {
     return match color {
         ObjectColor::COLOR { color } => {
@@ -191,15 +457,17 @@ pub fn render_hit_line(hit_details: &HitDetails, _rendering_state: &RenderingSta
     };
 }
 
-pub fn render_hit_column(hit_details: &HitDetails, _rendering_state: &RenderingState, renderer: &Renderer) {
+pub fn render_hit_column(hit_details: &HitDetails, rendering_state: &RenderingState, renderer: &Renderer) {
     let ray = hit_details.ray();
+    let camera = rendering_state.camera();
+
     match hit_details.hit() {
         Hit::None => {
-            renderer.render_column(&ray, hit_details.column(), hit_details.total_columns(), &ObjectColor::WHITE);
+            renderer.render_column(&ray, hit_details.column(), hit_details.total_columns(), &ObjectColor::WHITE, camera);
         }
 
         Hit::Wall { color, .. } => {
-            renderer.render_column(&ray, hit_details.column(), hit_details.total_columns(), &color);
+            renderer.render_column(&ray, hit_details.column(), hit_details.total_columns(), &color, camera);
         }
     };
 }
@@ -0,0 +1,55 @@
+use glm::{uvec2, UVec2};
+use num_traits::zero;
+use sdl2::pixels::{Color, PixelFormatEnum};
+
+pub const SCENE_PIXEL_FORMAT: PixelFormatEnum = PixelFormatEnum::RGB24;
+const BYTES_PER_PIXEL: usize = 3;
+
+// CPU side pixels of the 3d scene. Uploaded to the streaming texture once per frame
+pub struct SceneBuffer {
+    sizes: UVec2,
+    pixels: Vec<u8>
+}
+
+impl SceneBuffer {
+    pub fn new() -> Self {
+        Self { sizes: zero(), pixels: vec![] }
+    }
+
+    pub fn sizes(&self) -> UVec2 {
+        self.sizes
+    }
+    pub fn pixels(&self) -> &Vec<u8> {
+        &self.pixels
+    }
+
+    pub fn pitch(&self) -> usize {
+        self.sizes.x as usize * BYTES_PER_PIXEL
+    }
+
+    pub fn resize(&mut self, sizes: UVec2) {
+        if self.sizes == sizes {
+            return;
+        }
+
+        self.sizes = uvec2(sizes.x.max(1), sizes.y.max(1));
+        self.pixels = vec![0; self.pitch() * self.sizes.y as usize];
+    }
+
+    // Fills pixels in [x_from, x_to) x [y_from, y_to), clamped to the buffer sizes
+    pub fn fill_rect(&mut self, x_from: u32, x_to: u32, y_from: u32, y_to: u32, color: &Color) {
+        let x_to = x_to.min(self.sizes.x) as usize;
+        let y_to = y_to.min(self.sizes.y) as usize;
+        let x_from = (x_from as usize).min(x_to);
+        let y_from = (y_from as usize).min(y_to);
+
+        let pitch = self.pitch();
+        let rgb = [color.r, color.g, color.b];
+
+        for row in self.pixels.chunks_exact_mut(pitch).take(y_to).skip(y_from) {
+            for pixel in row[x_from * BYTES_PER_PIXEL..x_to * BYTES_PER_PIXEL].chunks_exact_mut(BYTES_PER_PIXEL) {
+                pixel.copy_from_slice(&rgb);
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sdl2::image::SaveSurface;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::surface::Surface;
+
+pub const FRAME_CAPTURE_FORMAT: PixelFormatEnum = PixelFormatEnum::RGB24;
+
+// Raw frame pixels read back from the canvas. Owns its data, so it can be moved to another thread
+pub struct FrameCapture {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>
+}
+
+impl FrameCapture {
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        Self { width, height, pixels }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+    pub fn pixels(&self) -> &Vec<u8> {
+        &self.pixels
+    }
+
+    pub fn pitch(&self) -> u32 {
+        self.width * FRAME_CAPTURE_FORMAT.byte_size_per_pixel() as u32
+    }
+
+    pub fn save_png(&mut self, path: &Path) -> Result<(), String> {
+        let (width, height, pitch) = (self.width, self.height, self.pitch());
+        let surface = Surface::from_data(&mut self.pixels, width, height, pitch, FRAME_CAPTURE_FORMAT)?;
+        surface.save(path)
+    }
+}
+
+pub struct ScreenshotService {
+    directory: PathBuf,
+    is_requested: bool
+}
+
+impl ScreenshotService {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory, is_requested: false }
+    }
+
+    // Screenshot will be taken from the next presented frame
+    pub fn request(&mut self) {
+        self.is_requested = true;
+    }
+
+    pub fn take_request(&mut self) -> bool {
+        std::mem::replace(&mut self.is_requested, false)
+    }
+
+    // Encoding and writing is done on a separate thread to avoid a frame hitch
+    pub fn save_in_background(&self, capture: FrameCapture) {
+        let directory = self.directory.clone();
+
+        thread::spawn(move || {
+            match save_screenshot(&directory, capture) {
+                Ok(path) => log::info!("Screenshot saved to {}", path.display()),
+                Err(error) => log::error!("Unable to save screenshot: {}", error)
+            }
+        });
+    }
+}
+
+pub fn save_screenshot(directory: &Path, mut capture: FrameCapture) -> Result<PathBuf, String> {
+    fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+
+    let path = directory.join(format!("screenshot_{}.png", format_timestamp(SystemTime::now())));
+    capture.save_png(&path)?;
+
+    Ok(path)
+}
+
+// UTC timestamp in "YYYYMMDD_HHMMSS_mmm" format
+pub fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    let total_seconds = since_epoch.as_secs();
+    let (days, day_seconds) = (total_seconds / 86_400, total_seconds % 86_400);
+
+    // Days to civil date conversion (http://howardhinnant.github.io/date_algorithms.html)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_shifted = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_shifted + 2) / 5 + 1;
+    let month = if month_shifted < 10 { month_shifted + 3 } else { month_shifted - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}{:02}{:02}_{:02}{:02}{:02}_{:03}",
+        year, month, day,
+        day_seconds / 3600, (day_seconds % 3600) / 60, day_seconds % 60,
+        since_epoch.subsec_millis()
+    )
+}
@@ -0,0 +1,105 @@
+use glm::UVec2;
+use sdl2::rect::Rect;
+
+use crate::game::graphics::map_view::MapShape;
+
+const MINIMAP_SIZE_FRACTION: f32 = 0.3;
+const MINIMAP_MARGIN: u32 = 8;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ViewMode {
+    World3D,
+    World2D,
+    Minimap,
+    Split
+}
+
+impl ViewMode {
+    pub fn from_name(name: &str) -> Option<ViewMode> {
+        match name {
+            "3d" => Some(ViewMode::World3D),
+            "2d" => Some(ViewMode::World2D),
+            "minimap" => Some(ViewMode::Minimap),
+            "split" => Some(ViewMode::Split),
+            _ => None
+        }
+    }
+
+    pub fn next(&self) -> ViewMode {
+        match self {
+            ViewMode::World3D => ViewMode::World2D,
+            ViewMode::World2D => ViewMode::Minimap,
+            ViewMode::Minimap => ViewMode::Split,
+            ViewMode::Split => ViewMode::World3D
+        }
+    }
+
+    // Places 3d scene and 2d map in the internal resolution
+    pub fn layout(&self, resolution: UVec2) -> ViewLayout {
+        let full_view = Rect::new(0, 0, resolution.x, resolution.y);
+
+        match self {
+            ViewMode::World3D => ViewLayout { scene: Some(full_view), map: None },
+            ViewMode::World2D => ViewLayout { scene: None, map: Some((full_view, MapKind::Debug)) },
+
+            // Square minimap in the top right corner
+            ViewMode::Minimap => {
+                let minimap_size = ((resolution.x.min(resolution.y) as f32 * MINIMAP_SIZE_FRACTION) as u32).max(1);
+                let minimap_x = resolution.x.saturating_sub(minimap_size + MINIMAP_MARGIN);
+
+                ViewLayout {
+                    scene: Some(full_view),
+                    map: Some((Rect::new(minimap_x as i32, MINIMAP_MARGIN as i32, minimap_size, minimap_size), MapKind::Minimap))
+                }
+            }
+
+            ViewMode::Split => {
+                let scene_width = (resolution.x / 2).max(1);
+                let map_width = (resolution.x - scene_width).max(1);
+
+                ViewLayout {
+                    scene: Some(Rect::new(0, 0, scene_width, resolution.y)),
+                    map: Some((Rect::new(scene_width as i32, 0, map_width, resolution.y), MapKind::Debug))
+                }
+            }
+        }
+    }
+}
+
+// Debug map shows the whole map with camera rays, minimap and automap show explored walls only
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MapKind {
+    Debug,
+    Minimap,
+    Automap
+}
+
+// Viewports in internal resolution pixels, None when the view is hidden
+#[derive(Copy, Clone, Debug)]
+pub struct ViewLayout {
+    pub scene: Option<Rect>,
+    pub map: Option<(Rect, MapKind)>
+}
+
+// Automap covers the whole view, so it is shown over any view mode
+pub fn automap_layout(resolution: UVec2) -> ViewLayout {
+    ViewLayout {
+        scene: None,
+        map: Some((Rect::new(0, 0, resolution.x, resolution.y), MapKind::Automap))
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct MinimapSettings {
+    pub shape: MapShape,
+    pub is_rotating: bool,
+
+    // Pixels per map unit
+    pub scale: f32
+}
+
+impl Default for MinimapSettings {
+    fn default() -> Self {
+        Self { shape: MapShape::Circle, is_rotating: true, scale: 12.0 }
+    }
+}
@@ -0,0 +1,119 @@
+use std::cell::RefCell;
+use std::f32::consts::FRAC_PI_2;
+use std::rc::Rc;
+
+use glm::Vec2;
+use sdl2::rect::Rect;
+
+use crate::game::graphics::map_view::MapView;
+use crate::game::graphics::ray_fan::{RayFan, RayFanSettings};
+use crate::game::graphics::renderer::{render_hit_line, Renderer};
+use crate::game::graphics::RenderingState;
+use crate::game::model::exploration::Exploration;
+use crate::game::model::object_color::ObjectColor;
+use crate::game::model::map_painter::MapPainter;
+use crate::game::model::raycast_world::RaycastWorld;
+
+const MAX_DEBUG_RAYS: usize = 120;
+
+// Whole map is scaled into the viewport
+pub fn render_world_2d(world: &dyn RaycastWorld, viewport: Rect, rendering_state: &RenderingState, renderer: &Renderer) {
+    renderer.begin_map_view(MapView::fit(viewport, world.bounds()), None);
+
+    render_geometry_2d(world, renderer);
+    render_camera_2d(world, rendering_state, renderer);
+
+    renderer.end_map_view();
+}
+
+// Explored walls around the player, heading points up when minimap is rotating
+pub fn render_minimap(world: &dyn RaycastWorld, viewport: Rect, exploration: &Rc<RefCell<Exploration>>, rendering_state: &RenderingState, renderer: &Renderer) {
+    let camera = &rendering_state.camera;
+    let minimap = rendering_state.minimap();
+
+    let rotation = if minimap.is_rotating { -FRAC_PI_2 - camera.direction() } else { 0.0 };
+    let map_view = MapView::follow(viewport, minimap.shape, camera.position(), minimap.scale, rotation);
+
+    renderer.begin_map_view(map_view, Some(exploration));
+
+    render_geometry_2d(world, renderer);
+    render_player_marker(rendering_state, renderer);
+
+    renderer.end_map_view();
+}
+
+// Explored walls of the whole map
+pub fn render_automap(world: &dyn RaycastWorld, viewport: Rect, exploration: &Rc<RefCell<Exploration>>, rendering_state: &RenderingState, renderer: &Renderer) {
+    renderer.begin_map_view(MapView::fit(viewport, world.bounds()), Some(exploration));
+
+    render_geometry_2d(world, renderer);
+    render_player_marker(rendering_state, renderer);
+
+    renderer.end_map_view();
+}
+
+// Draws map geometry of the world in the current map view
+fn render_geometry_2d(world: &dyn RaycastWorld, renderer: &Renderer) {
+    world.render_2d(&mut RendererMapPainter { renderer });
+}
+
+struct RendererMapPainter<'r> {
+    renderer: &'r Renderer
+}
+
+impl MapPainter for RendererMapPainter<'_> {
+    fn paint_tile(&mut self, position: Vec2, color: &ObjectColor) {
+        self.renderer.render_2d_tile_at(&position, color)
+    }
+
+    fn paint_line(&mut self, from: Vec2, to: Vec2, color: &ObjectColor) {
+        self.renderer.render_2d_line(&from, &to, color)
+    }
+}
+
+fn render_player_marker(rendering_state: &RenderingState, renderer: &Renderer) {
+    const HEADING_LINE_LEN: f32 = 1.0;
+
+    let camera = &rendering_state.camera;
+    let camera_position = camera.position();
+
+    let heading_point = Vec2::new(
+        camera_position.x + HEADING_LINE_LEN * camera.direction().cos(),
+        camera_position.y + HEADING_LINE_LEN * camera.direction().sin()
+    );
+
+    // Player is always visible
+    renderer.clear_map_fog();
+
+    renderer.render_2d_point(&camera_position, 6);
+    renderer.render_2d_line(&camera_position, &heading_point, &ObjectColor::RED);
+}
+
+pub fn render_camera_2d(world: &dyn RaycastWorld, rendering_state: &RenderingState, renderer: &Renderer) {
+    let camera = &rendering_state.camera;
+
+    let camera_position = camera.position();
+    renderer.render_2d_point(&camera_position, 10);
+
+    let camera_direction = camera.direction();
+
+    // Rendering camera direction red ray
+    {
+        const CAMERA_DIRECTION_RAY_LEN: f32 = 3.0;
+
+        let camera_direction_second_point = Vec2::new(
+            camera_position.x + CAMERA_DIRECTION_RAY_LEN * camera_direction.cos(),
+            camera_position.y + CAMERA_DIRECTION_RAY_LEN * camera_direction.sin(),
+        );
+
+        renderer.render_2d_line(&camera_position, &camera_direction_second_point, &ObjectColor::RED);
+    }
+
+    // Only part of the rays is cast for the debug view
+    let ray_fan = RayFan::new(world, RayFanSettings::from_rendering_state(rendering_state));
+    let rays_step = (ray_fan.len() / MAX_DEBUG_RAYS).max(1);
+
+    for hit_details in ray_fan.step_by(rays_step) {
+        render_hit_line(&hit_details, rendering_state, renderer);
+    }
+}
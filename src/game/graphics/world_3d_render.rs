@@ -0,0 +1,28 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use sdl2::rect::Rect;
+
+use crate::game::graphics::ray_caster::{cast_rays, HitDetails};
+use crate::game::graphics::renderer::{render_hit_column, Renderer};
+use crate::game::graphics::RenderingState;
+use crate::game::model::raycast_world::RaycastWorld;
+use crate::game::profiler::FrameProfiler;
+
+// Returns cast rays, so they can be reused after rendering
+pub fn render_world_3d(world: &Arc<dyn RaycastWorld>, viewport: Rect, rendering_state: &RenderingState, renderer: &Renderer, profiler: &mut FrameProfiler) -> Vec<HitDetails> {
+    renderer.begin_scene(rendering_state.scene_resolution());
+
+    let ray_casting_start = Instant::now();
+    let hits = cast_rays(world, rendering_state);
+    let intersection_tests = hits.iter().map(|hit_details| hit_details.ray().intersection_tests() as u64).sum();
+    profiler.record_ray_casting(ray_casting_start.elapsed(), intersection_tests);
+
+    for hit_details in &hits {
+        render_hit_column(hit_details, rendering_state, renderer);
+    }
+
+    renderer.present_scene(viewport);
+
+    hits
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use sdl2::controller::{Axis, Button};
+use sdl2::keyboard::Scancode;
+use sdl2::mouse::MouseButton;
+
+use crate::game::input::input_action::InputAction;
+
+// Physical input which can be bound to actions
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum InputSource {
+    Key(Scancode),
+    MouseButton(MouseButton),
+    GamepadButton(Button),
+
+    // Axis is pressed when it is deflected to the given side
+    GamepadAxis {
+        axis: Axis,
+        is_positive: bool
+    }
+}
+
+impl InputSource {
+    // Names are "key:NAME", "mouse:BUTTON", "pad:BUTTON" and "axis:AXIS+" or "axis:AXIS-".
+    // Spaces in the key names are replaced by underscores, so names can be split by whitespaces
+    pub fn from_name(name: &str) -> Option<InputSource> {
+        let (device, input) = name.split_once(':')?;
+
+        match device {
+            "key" => Scancode::from_name(&input.replace('_', " ")).map(InputSource::Key),
+            "mouse" => mouse_button_from_name(input).map(InputSource::MouseButton),
+            "pad" => Button::from_string(input).map(InputSource::GamepadButton),
+            "axis" => {
+                let (axis, is_positive) = match input.strip_suffix('+') {
+                    Some(axis) => (axis, true),
+                    None => (input.strip_suffix('-')?, false)
+                };
+
+                Axis::from_string(axis).map(|axis| InputSource::GamepadAxis { axis, is_positive })
+            }
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            InputSource::Key(scancode) => format!("key:{}", scancode.name().replace(' ', "_")),
+            InputSource::MouseButton(mouse_button) => format!("mouse:{}", mouse_button_name(*mouse_button)),
+            InputSource::GamepadButton(button) => format!("pad:{}", button.string()),
+            InputSource::GamepadAxis { axis, is_positive } => format!("axis:{}{}", axis.string(), if *is_positive { '+' } else { '-' })
+        }
+    }
+
+    pub fn is_gamepad(&self) -> bool {
+        matches!(self, InputSource::GamepadButton(_) | InputSource::GamepadAxis { .. })
+    }
+}
+
+fn mouse_button_from_name(name: &str) -> Option<MouseButton> {
+    match name {
+        "left" => Some(MouseButton::Left),
+        "middle" => Some(MouseButton::Middle),
+        "right" => Some(MouseButton::Right),
+        "x1" => Some(MouseButton::X1),
+        "x2" => Some(MouseButton::X2),
+        _ => None
+    }
+}
+
+fn mouse_button_name(mouse_button: MouseButton) -> &'static str {
+    match mouse_button {
+        MouseButton::Left => "left",
+        MouseButton::Middle => "middle",
+        MouseButton::Right => "right",
+        MouseButton::X1 => "x1",
+        MouseButton::X2 => "x2",
+        MouseButton::Unknown => "unknown"
+    }
+}
+
+// Input sources of every action.
+//
+// Text format, one action per line, "#" starts a comment:
+//   ACTION [SOURCE ...]
+// Actions missing in the file keep the default bindings, action without sources is unbound
+pub struct Bindings {
+    sources: HashMap<InputAction, Vec<InputSource>>
+}
+
+impl Bindings {
+    pub fn load(path: &Path) -> Result<Bindings, String> {
+        let source = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        let mut bindings = Bindings::default();
+
+        for (line_index, line) in source.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let mut words = line.split_whitespace();
+
+            let Some(action_name) = words.next() else {
+                continue;
+            };
+
+            let action = InputAction::from_name(action_name)
+                .ok_or_else(|| format!("line {}: unknown action \"{}\"", line_index + 1, action_name))?;
+
+            let sources = words
+                .map(|source_name| InputSource::from_name(source_name)
+                    .ok_or_else(|| format!("line {}: unknown input \"{}\"", line_index + 1, source_name)))
+                .collect::<Result<Vec<InputSource>, String>>()?;
+
+            bindings.sources.insert(action, sources);
+        }
+
+        Ok(bindings)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+        }
+
+        let source: String = InputAction::ALL.iter()
+            .map(|action| {
+                let mut words = vec![action.name().to_string()];
+                words.extend(self.sources(*action).iter().map(|source| source.name()));
+
+                words.join(" ") + "\n"
+            })
+            .collect();
+
+        fs::write(path, source).map_err(|error| error.to_string())
+    }
+
+    pub fn sources(&self, action: InputAction) -> &[InputSource] {
+        self.sources.get(&action).map(|sources| sources.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn actions(&self, source: InputSource) -> Vec<InputAction> {
+        InputAction::ALL.iter()
+            .filter(|action| self.sources(**action).contains(&source))
+            .copied()
+            .collect()
+    }
+
+    // New source replaces sources of the same device kind, so keyboard and gamepad bindings are kept apart.
+    // Source is removed from other actions to avoid conflicts
+    pub fn bind(&mut self, action: InputAction, source: InputSource) {
+        for sources in self.sources.values_mut() {
+            sources.retain(|bound_source| *bound_source != source);
+        }
+
+        let sources = self.sources.entry(action).or_default();
+
+        sources.retain(|bound_source| bound_source.is_gamepad() != source.is_gamepad());
+        sources.insert(0, source);
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use InputSource::{GamepadAxis, GamepadButton, Key};
+
+        let default_sources = [
+            (InputAction::MoveForward, vec![Key(Scancode::W), Key(Scancode::Up), GamepadButton(Button::DPadUp), GamepadAxis { axis: Axis::LeftY, is_positive: false }]),
+            (InputAction::MoveBackward, vec![Key(Scancode::S), Key(Scancode::Down), GamepadButton(Button::DPadDown), GamepadAxis { axis: Axis::LeftY, is_positive: true }]),
+            (InputAction::StrafeLeft, vec![Key(Scancode::A), Key(Scancode::Comma), GamepadAxis { axis: Axis::LeftX, is_positive: false }]),
+            (InputAction::StrafeRight, vec![Key(Scancode::D), Key(Scancode::Period), GamepadAxis { axis: Axis::LeftX, is_positive: true }]),
+            (InputAction::TurnLeft, vec![Key(Scancode::Left), GamepadButton(Button::DPadLeft), GamepadAxis { axis: Axis::RightX, is_positive: false }]),
+            (InputAction::TurnRight, vec![Key(Scancode::Right), GamepadButton(Button::DPadRight), GamepadAxis { axis: Axis::RightX, is_positive: true }]),
+            (InputAction::LookUp, vec![Key(Scancode::PageUp), GamepadAxis { axis: Axis::RightY, is_positive: false }]),
+            (InputAction::LookDown, vec![Key(Scancode::PageDown), GamepadAxis { axis: Axis::RightY, is_positive: true }]),
+            (InputAction::Jump, vec![Key(Scancode::Space), GamepadButton(Button::A)]),
+            (InputAction::Crouch, vec![Key(Scancode::C), GamepadButton(Button::B)]),
+            (InputAction::Use, vec![Key(Scancode::E), GamepadButton(Button::X)]),
+            (InputAction::Fire, vec![Key(Scancode::LCtrl), InputSource::MouseButton(MouseButton::Left), GamepadAxis { axis: Axis::TriggerRight, is_positive: true }]),
+            (InputAction::ToggleMap, vec![Key(Scancode::M), GamepadButton(Button::Back)]),
+            (InputAction::Screenshot, vec![Key(Scancode::F12)]),
+            (InputAction::ToggleRecording, vec![Key(Scancode::F9)]),
+            (InputAction::ToggleFullscreen, vec![Key(Scancode::F11)]),
+            (InputAction::ToggleMouseLook, vec![Key(Scancode::L)]),
+            (InputAction::Quit, vec![Key(Scancode::Escape)]),
+            (InputAction::View3D, vec![Key(Scancode::F1)]),
+            (InputAction::View2D, vec![Key(Scancode::F2)]),
+            (InputAction::ViewMinimap, vec![Key(Scancode::F3)]),
+            (InputAction::ViewSplit, vec![Key(Scancode::F4)]),
+            (InputAction::NextView, vec![Key(Scancode::Tab), GamepadButton(Button::Y)]),
+            (InputAction::ToggleMinimapRotation, vec![Key(Scancode::F5)]),
+            (InputAction::SwitchMinimapShape, vec![Key(Scancode::F6)]),
+            (InputAction::ToggleDebugInfo, vec![Key(Scancode::F7)]),
+            (InputAction::ToggleProfiler, vec![Key(Scancode::F8)]),
+            (InputAction::DecreaseResolution, vec![Key(Scancode::Minus)]),
+            (InputAction::IncreaseResolution, vec![Key(Scancode::Equals)]),
+            (InputAction::RebindControls, vec![Key(Scancode::F10)]),
+            (InputAction::QuickSave, vec![Key(Scancode::Insert)]),
+            (InputAction::QuickLoad, vec![Key(Scancode::Home)])
+        ];
+
+        Self { sources: default_sources.into_iter().collect() }
+    }
+}
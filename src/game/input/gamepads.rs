@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use sdl2::controller::GameController;
+use sdl2::event::Event;
+use sdl2::GameControllerSubsystem;
+
+// Connected game controllers. Controllers are opened and closed by the hot-plug events,
+// SDL sends "added" events for controllers connected before the start too
+pub struct Gamepads {
+    subsystem: Option<GameControllerSubsystem>,
+    controllers: Vec<GameController>
+}
+
+impl Gamepads {
+    // Game works without controllers when the subsystem is not available
+    pub fn new(subsystem: Option<GameControllerSubsystem>) -> Self {
+        Self { subsystem, controllers: vec![] }
+    }
+
+    // Returns message about connected or disconnected controller
+    pub fn handle_event(&mut self, event: &Event) -> Option<String> {
+        match event {
+            Event::ControllerDeviceAdded { which: joystick_index, .. } => {
+                let subsystem = self.subsystem.as_ref()?;
+
+                match subsystem.open(*joystick_index) {
+                    Err(error) => {
+                        log::error!("Unable to open game controller {}: {}", joystick_index, error);
+                        None
+                    }
+
+                    Ok(controller) => {
+                        let message = format!("Controller connected: {}", controller.name());
+                        log::info!("{}", message);
+
+                        self.controllers.push(controller);
+                        Some(message)
+                    }
+                }
+            }
+
+            Event::ControllerDeviceRemoved { which: instance_id, .. } => {
+                let controller_index = self.controllers.iter().position(|controller| controller.instance_id() == *instance_id)?;
+                let controller = self.controllers.remove(controller_index);
+
+                let message = format!("Controller disconnected: {}", controller.name());
+                log::info!("{}", message);
+
+                Some(message)
+            }
+
+            _ => None
+        }
+    }
+
+    // Strength is from 0 to 1, every connected controller rumbles
+    pub fn rumble(&mut self, strength: f32, duration: Duration) {
+        let motor_speed = (strength.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+
+        for controller in self.controllers.iter_mut() {
+            // Controllers without rumble motors return an error, it is not worth logging every time
+            let _ = controller.set_rumble(motor_speed, motor_speed, duration.as_millis() as u32);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// Named actions of the game, physical inputs are mapped to them by the bindings
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    MoveForward,
+    MoveBackward,
+    StrafeLeft,
+    StrafeRight,
+    TurnLeft,
+    TurnRight,
+    LookUp,
+    LookDown,
+    Jump,
+    Crouch,
+    Use,
+    Fire,
+
+    ToggleMap,
+    Screenshot,
+    ToggleRecording,
+    ToggleFullscreen,
+    ToggleMouseLook,
+    Quit,
+
+    View3D,
+    View2D,
+    ViewMinimap,
+    ViewSplit,
+    NextView,
+    ToggleMinimapRotation,
+    SwitchMinimapShape,
+    ToggleDebugInfo,
+    ToggleProfiler,
+    DecreaseResolution,
+    IncreaseResolution,
+    RebindControls,
+    QuickSave,
+    QuickLoad
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 32] = [
+        InputAction::MoveForward,
+        InputAction::MoveBackward,
+        InputAction::StrafeLeft,
+        InputAction::StrafeRight,
+        InputAction::TurnLeft,
+        InputAction::TurnRight,
+        InputAction::LookUp,
+        InputAction::LookDown,
+        InputAction::Jump,
+        InputAction::Crouch,
+        InputAction::Use,
+        InputAction::Fire,
+        InputAction::ToggleMap,
+        InputAction::Screenshot,
+        InputAction::ToggleRecording,
+        InputAction::ToggleFullscreen,
+        InputAction::ToggleMouseLook,
+        InputAction::Quit,
+        InputAction::View3D,
+        InputAction::View2D,
+        InputAction::ViewMinimap,
+        InputAction::ViewSplit,
+        InputAction::NextView,
+        InputAction::ToggleMinimapRotation,
+        InputAction::SwitchMinimapShape,
+        InputAction::ToggleDebugInfo,
+        InputAction::ToggleProfiler,
+        InputAction::DecreaseResolution,
+        InputAction::IncreaseResolution,
+        InputAction::RebindControls,
+        InputAction::QuickSave,
+        InputAction::QuickLoad
+    ];
+
+    // Actions rebound one by one in the game, the rest are rebound in the bindings file
+    pub const GAMEPLAY: [InputAction; 12] = [
+        InputAction::MoveForward,
+        InputAction::MoveBackward,
+        InputAction::StrafeLeft,
+        InputAction::StrafeRight,
+        InputAction::TurnLeft,
+        InputAction::TurnRight,
+        InputAction::LookUp,
+        InputAction::LookDown,
+        InputAction::Jump,
+        InputAction::Crouch,
+        InputAction::Use,
+        InputAction::Fire
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputAction::MoveForward => "move_forward",
+            InputAction::MoveBackward => "move_backward",
+            InputAction::StrafeLeft => "strafe_left",
+            InputAction::StrafeRight => "strafe_right",
+            InputAction::TurnLeft => "turn_left",
+            InputAction::TurnRight => "turn_right",
+            InputAction::LookUp => "look_up",
+            InputAction::LookDown => "look_down",
+            InputAction::Jump => "jump",
+            InputAction::Crouch => "crouch",
+            InputAction::Use => "use",
+            InputAction::Fire => "fire",
+            InputAction::ToggleMap => "toggle_map",
+            InputAction::Screenshot => "screenshot",
+            InputAction::ToggleRecording => "toggle_recording",
+            InputAction::ToggleFullscreen => "toggle_fullscreen",
+            InputAction::ToggleMouseLook => "toggle_mouse_look",
+            InputAction::Quit => "quit",
+            InputAction::View3D => "view_3d",
+            InputAction::View2D => "view_2d",
+            InputAction::ViewMinimap => "view_minimap",
+            InputAction::ViewSplit => "view_split",
+            InputAction::NextView => "next_view",
+            InputAction::ToggleMinimapRotation => "toggle_minimap_rotation",
+            InputAction::SwitchMinimapShape => "switch_minimap_shape",
+            InputAction::ToggleDebugInfo => "toggle_debug_info",
+            InputAction::ToggleProfiler => "toggle_profiler",
+            InputAction::DecreaseResolution => "decrease_resolution",
+            InputAction::IncreaseResolution => "increase_resolution",
+            InputAction::RebindControls => "rebind_controls",
+            InputAction::QuickSave => "quick_save",
+            InputAction::QuickLoad => "quick_load"
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<InputAction> {
+        InputAction::ALL.iter().find(|action| action.name() == name).copied()
+    }
+
+    // Held keys repeat these actions with the system key repeat rate
+    pub fn is_repeatable(&self) -> bool {
+        matches!(self, InputAction::DecreaseResolution | InputAction::IncreaseResolution)
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use glm::{vec2, Vec2};
+use sdl2::controller::Axis;
+use sdl2::event::Event;
+use sdl2::keyboard::Scancode;
+
+use crate::game::input::bindings::{Bindings, InputSource};
+use crate::game::input::input_action::InputAction;
+
+// Axis deflection treated as pressed by digital actions, half of the axis range
+const AXIS_PRESS_THRESHOLD: i16 = 16384;
+const AXIS_MAX_VALUE: f32 = i16::MAX as f32;
+
+// Rebinding of the current action is skipped by this key instead of binding it
+const SKIP_REBINDING_KEY: Scancode = Scancode::Escape;
+
+#[derive(Copy, Clone, Debug)]
+pub struct MouseSettings {
+    // Radians per mouse count
+    pub sensitivity: f32,
+    pub is_inverted_x: bool,
+    pub is_inverted_y: bool
+}
+
+// Parts of the axis range ignored near the rest position
+#[derive(Copy, Clone, Debug)]
+pub struct GamepadSettings {
+    pub stick_deadzone: f32,
+    pub trigger_deadzone: f32
+}
+
+// Digital state of the action and its analog strength, systems read either of them
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ActionState {
+    pub is_active: bool,
+    pub value: f32
+}
+
+pub enum InputEvent {
+    Pressed(InputAction),
+    Rebound(InputAction, InputSource),
+    RebindingSkipped(InputAction)
+}
+
+// Pressed input sources and their actions. Systems read action states instead of the physical inputs
+pub struct InputState {
+    bindings: Bindings,
+    active_sources: HashSet<InputSource>,
+
+    // Last values of gamepad axes from -1 to 1
+    axis_values: HashMap<Axis, f32>,
+    gamepad_settings: GamepadSettings,
+
+    mouse_settings: MouseSettings,
+    is_mouse_look_enabled: bool,
+
+    // Look rotation in radians since the last simulation step
+    look_delta: Vec2,
+
+    // Gameplay action states set by the replay, physical gameplay inputs are ignored while it is played
+    replayed_actions: Option<HashMap<InputAction, ActionState>>,
+
+    // Actions waiting for the new binding, the next pressed input is bound to the first one
+    rebinding_queue: VecDeque<InputAction>
+}
+
+impl InputState {
+    pub fn new(bindings: Bindings, mouse_settings: MouseSettings, gamepad_settings: GamepadSettings) -> Self {
+        Self {
+            bindings,
+            active_sources: HashSet::new(),
+            axis_values: HashMap::new(),
+            gamepad_settings,
+            mouse_settings,
+            is_mouse_look_enabled: false,
+            look_delta: vec2(0.0, 0.0),
+            replayed_actions: None,
+            rebinding_queue: VecDeque::new()
+        }
+    }
+
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    pub fn is_active(&self, action: InputAction) -> bool {
+        match self.replayed_action(action) {
+            Some(action_state) => action_state.is_active,
+            None => self.bindings.sources(action).iter().any(|source| self.active_sources.contains(source))
+        }
+    }
+
+    // Strongest of the action sources from 0 to 1, digital inputs give either 0 or 1
+    pub fn action_value(&self, action: InputAction) -> f32 {
+        if let Some(action_state) = self.replayed_action(action) {
+            return action_state.value;
+        }
+
+        self.bindings.sources(action).iter()
+            .map(|source| match source {
+                InputSource::GamepadAxis { axis, is_positive } => {
+                    let axis_value = self.axis_value(*axis);
+                    (if *is_positive { axis_value } else { -axis_value }).max(0.0)
+                }
+
+                source if self.active_sources.contains(source) => 1.0,
+                _ => 0.0
+            })
+            .fold(0.0, f32::max)
+    }
+
+    pub fn action_state(&self, action: InputAction) -> ActionState {
+        ActionState { is_active: self.is_active(action), value: self.action_value(action) }
+    }
+
+    // Positive action value minus negative one
+    pub fn action_axis(&self, negative: InputAction, positive: InputAction) -> f32 {
+        self.action_value(positive) - self.action_value(negative)
+    }
+
+    // Axis value with the deadzone removed, the rest of the range is stretched back to the whole range
+    fn axis_value(&self, axis: Axis) -> f32 {
+        let raw_value = |axis: Axis| self.axis_values.get(&axis).copied().unwrap_or(0.0);
+
+        match axis {
+            Axis::TriggerLeft | Axis::TriggerRight => apply_deadzone(raw_value(axis), self.gamepad_settings.trigger_deadzone),
+
+            // Stick deadzone is radial, so diagonal directions are not snapped to the axes
+            _ => {
+                let stick = match axis {
+                    Axis::LeftX | Axis::LeftY => vec2(raw_value(Axis::LeftX), raw_value(Axis::LeftY)),
+                    _ => vec2(raw_value(Axis::RightX), raw_value(Axis::RightY))
+                };
+
+                let deflection = glm::length(stick);
+
+                if deflection <= 0.0 {
+                    return 0.0;
+                }
+
+                raw_value(axis) / deflection * apply_deadzone(deflection.min(1.0), self.gamepad_settings.stick_deadzone)
+            }
+        }
+    }
+
+    pub fn is_mouse_look_enabled(&self) -> bool {
+        self.is_mouse_look_enabled
+    }
+    pub fn set_mouse_look_enabled(&mut self, is_mouse_look_enabled: bool) {
+        self.is_mouse_look_enabled = is_mouse_look_enabled;
+        self.look_delta = vec2(0.0, 0.0);
+    }
+
+    // Look rotation in radians since the last call, positive y is looking up
+    pub fn take_look_delta(&mut self) -> Vec2 {
+        std::mem::replace(&mut self.look_delta, vec2(0.0, 0.0))
+    }
+
+    pub fn look_delta(&self) -> Vec2 {
+        self.look_delta
+    }
+    pub fn set_look_delta(&mut self, look_delta: Vec2) {
+        self.look_delta = look_delta;
+    }
+
+    pub fn start_replaying(&mut self) {
+        self.replayed_actions = Some(HashMap::new());
+    }
+
+    pub fn set_replayed_action(&mut self, action: InputAction, action_state: ActionState) {
+        if let Some(replayed_actions) = self.replayed_actions.as_mut() {
+            replayed_actions.insert(action, action_state);
+        }
+    }
+
+    // Hotkey actions are not replayed, so the replay can be watched in any view
+    fn replayed_action(&self, action: InputAction) -> Option<ActionState> {
+        let replayed_actions = self.replayed_actions.as_ref()?;
+
+        if !InputAction::GAMEPLAY.contains(&action) {
+            return None;
+        }
+
+        Some(replayed_actions.get(&action).copied().unwrap_or_default())
+    }
+
+    pub fn start_rebinding(&mut self, actions: &[InputAction]) {
+        self.rebinding_queue = actions.iter().copied().collect();
+    }
+
+    pub fn rebinding_action(&self) -> Option<InputAction> {
+        self.rebinding_queue.front().copied()
+    }
+
+    // Updates pressed sources and returns pressed actions, key repeats press only repeatable actions
+    pub fn handle_event(&mut self, event: &Event) -> Vec<InputEvent> {
+        let mut input_events = vec![];
+
+        match event {
+            Event::MouseMotion { xrel, yrel, .. } if self.is_mouse_look_enabled && self.replayed_actions.is_none() => {
+                let inversion_x = if self.mouse_settings.is_inverted_x { -1.0 } else { 1.0 };
+                let inversion_y = if self.mouse_settings.is_inverted_y { 1.0 } else { -1.0 };
+
+                self.look_delta = self.look_delta + vec2(*xrel as f32 * inversion_x, *yrel as f32 * inversion_y) * self.mouse_settings.sensitivity;
+            }
+
+            Event::ControllerAxisMotion { axis, value, .. } => {
+                self.axis_values.insert(*axis, (*value as f32 / AXIS_MAX_VALUE).clamp(-1.0, 1.0));
+            }
+
+            // Inputs of the disconnected controller would stay pressed otherwise
+            Event::ControllerDeviceRemoved { .. } => {
+                self.axis_values.clear();
+                self.active_sources.retain(|source| !source.is_gamepad());
+            }
+
+            _ => {}
+        }
+
+        for (source, is_active) in source_changes(event) {
+            if !is_active {
+                self.active_sources.remove(&source);
+                continue;
+            }
+
+            let is_repeat = !self.active_sources.insert(source);
+
+            if let Some(action) = self.rebinding_action() {
+                if is_repeat {
+                    continue;
+                }
+
+                self.rebinding_queue.pop_front();
+
+                if source == InputSource::Key(SKIP_REBINDING_KEY) {
+                    input_events.push(InputEvent::RebindingSkipped(action));
+                } else {
+                    self.bindings.bind(action, source);
+                    input_events.push(InputEvent::Rebound(action, source));
+                }
+
+                continue;
+            }
+
+            for action in self.bindings.actions(source) {
+                if !is_repeat || action.is_repeatable() {
+                    input_events.push(InputEvent::Pressed(action));
+                }
+            }
+        }
+
+        input_events
+    }
+}
+
+fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    let magnitude = ((value.abs() - deadzone) / (1.0 - deadzone)).max(0.0);
+    magnitude.copysign(value)
+}
+
+// Sources changed by the event and their new states
+fn source_changes(event: &Event) -> Vec<(InputSource, bool)> {
+    match event {
+        Event::KeyDown { scancode: Some(scancode), .. } => vec![(InputSource::Key(*scancode), true)],
+        Event::KeyUp { scancode: Some(scancode), .. } => vec![(InputSource::Key(*scancode), false)],
+        Event::MouseButtonDown { mouse_btn, .. } => vec![(InputSource::MouseButton(*mouse_btn), true)],
+        Event::MouseButtonUp { mouse_btn, .. } => vec![(InputSource::MouseButton(*mouse_btn), false)],
+        Event::ControllerButtonDown { button, .. } => vec![(InputSource::GamepadButton(*button), true)],
+        Event::ControllerButtonUp { button, .. } => vec![(InputSource::GamepadButton(*button), false)],
+
+        Event::ControllerAxisMotion { axis, value, .. } => vec![
+            (InputSource::GamepadAxis { axis: *axis, is_positive: true }, *value > AXIS_PRESS_THRESHOLD),
+            (InputSource::GamepadAxis { axis: *axis, is_positive: false }, *value < -AXIS_PRESS_THRESHOLD)
+        ],
+
+        _ => vec![]
+    }
+}
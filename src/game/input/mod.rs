@@ -0,0 +1,6 @@
+pub mod input_action;
+pub mod bindings;
+pub mod input_state;
+pub mod gamepads;
+pub mod synthetic_input;
+pub mod replay;
@@ -0,0 +1,468 @@
+use std::fs;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use glm::{vec2, Vec2};
+
+use crate::game::input::input_action::InputAction;
+use crate::game::input::input_state::{ActionState, InputState};
+use crate::game::launch_options::{LaunchOptions, MapType};
+
+const REPLAY_VERSION: u32 = 1;
+
+// Player pose is written once per second of the simulation, so diverged replays are noticed early
+const POSE_CHECKPOINT_STEPS: u64 = 60;
+
+// Pose of the player before the simulation step, compared exactly as the simulation is deterministic
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ReplayPose {
+    pub position: Vec2,
+    pub direction: f32
+}
+
+// Launch options which change the simulation, the replay overrides them when played
+pub struct ReplayHeader {
+    pub map_path: PathBuf,
+    pub map_type: MapType,
+    pub start_pose: Option<(Vec2, Option<f32>)>,
+    pub seed: u64
+}
+
+impl ReplayHeader {
+    pub fn from_options(options: &LaunchOptions) -> Self {
+        Self {
+            map_path: options.map_path.clone(),
+            map_type: options.map_type,
+            start_pose: options.start_pose,
+            seed: options.seed
+        }
+    }
+
+    pub fn apply_to(&self, options: &mut LaunchOptions) {
+        options.map_path = self.map_path.clone();
+        options.map_type = self.map_type;
+        options.start_pose = self.start_pose;
+        options.seed = self.seed;
+    }
+}
+
+enum ReplayEntry {
+    Action(InputAction, ActionState),
+    Look(Vec2),
+    Pose(ReplayPose)
+}
+
+// Gameplay input of every simulation step. Only changes are stored, so the replay is compact.
+//
+// Text format, "#" starts a comment. Header:
+//   version 1
+//   map PATH
+//   map_type TYPE
+//   start X Y [DIRECTION]
+//   seed SEED
+// followed by the steps in order:
+//   STEP ACTION IS_ACTIVE VALUE
+//   STEP look DX DY
+//   STEP pose X Y DIRECTION
+// Directions and look deltas are in radians. Replay ends with the pose of its last step
+pub enum InputReplay {
+    Recording(ReplayRecorder),
+    Playing(ReplayPlayer)
+}
+
+impl InputReplay {
+    // Called at the start of every simulation step, before the systems read the input
+    pub fn step(&mut self, input: &mut InputState, pose: Option<ReplayPose>) {
+        match self {
+            InputReplay::Recording(recorder) => recorder.record_step(input, pose),
+            InputReplay::Playing(player) => player.play_step(input, pose)
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        match self {
+            InputReplay::Recording(_) => false,
+            InputReplay::Playing(player) => player.is_finished
+        }
+    }
+
+    // Recording is completed with the last pose, played replay fails if it has diverged
+    pub fn finish(&mut self) -> Result<(), String> {
+        match self {
+            InputReplay::Recording(recorder) => recorder.finish(),
+            InputReplay::Playing(player) => player.finish()
+        }
+    }
+}
+
+pub struct ReplayRecorder {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    step: u64,
+    action_states: Vec<ActionState>,
+    last_pose: Option<(u64, ReplayPose)>
+}
+
+impl ReplayRecorder {
+    pub fn create(path: &Path, header: &ReplayHeader) -> Result<ReplayRecorder, String> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+        }
+
+        let file = File::create(path).map_err(|error| error.to_string())?;
+        let mut writer = BufWriter::new(file);
+
+        write_header(&mut writer, header).map_err(|error| error.to_string())?;
+
+        Ok(ReplayRecorder {
+            path: path.to_path_buf(),
+            writer: Some(writer),
+            step: 0,
+            action_states: vec![ActionState::default(); InputAction::GAMEPLAY.len()],
+            last_pose: None
+        })
+    }
+
+    fn record_step(&mut self, input: &InputState, pose: Option<ReplayPose>) {
+        if let Err(error) = self.write_step(input, pose) {
+            log::error!("Unable to write replay {}, recording is stopped: {}", self.path.display(), error);
+            self.writer = None;
+        }
+
+        if let Some(pose) = pose {
+            self.last_pose = Some((self.step, pose));
+        }
+
+        self.step += 1;
+    }
+
+    fn write_step(&mut self, input: &InputState, pose: Option<ReplayPose>) -> std::io::Result<()> {
+        let Some(writer) = self.writer.as_mut() else {
+            return Ok(());
+        };
+
+        if let Some(pose) = pose.filter(|_| self.step.is_multiple_of(POSE_CHECKPOINT_STEPS)) {
+            write_pose(writer, self.step, pose)?;
+        }
+
+        for (action, recorded_state) in InputAction::GAMEPLAY.iter().zip(self.action_states.iter_mut()) {
+            let action_state = input.action_state(*action);
+
+            if action_state != *recorded_state {
+                writeln!(writer, "{} {} {} {}", self.step, action.name(), action_state.is_active as u8, action_state.value)?;
+                *recorded_state = action_state;
+            }
+        }
+
+        let look_delta = input.look_delta();
+
+        if look_delta.x != 0.0 || look_delta.y != 0.0 {
+            writeln!(writer, "{} look {} {}", self.step, look_delta.x, look_delta.y)?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        let Some(mut writer) = self.writer.take() else {
+            return Ok(());
+        };
+
+        // Pose of the last step is already written when it is a checkpoint
+        if let Some((step, pose)) = self.last_pose.filter(|(step, _)| !step.is_multiple_of(POSE_CHECKPOINT_STEPS)) {
+            write_pose(&mut writer, step, pose).map_err(|error| error.to_string())?;
+        }
+
+        writer.flush().map_err(|error| error.to_string())?;
+        log::info!("Replay of {} steps has been saved to {}", self.step, self.path.display());
+
+        Ok(())
+    }
+}
+
+pub struct ReplayPlayer {
+    header: ReplayHeader,
+    entries: Vec<(u64, ReplayEntry)>,
+    next_entry: usize,
+    step: u64,
+    last_step: u64,
+    diverged_step: Option<u64>,
+    is_finished: bool
+}
+
+impl ReplayPlayer {
+    pub fn load(path: &Path) -> Result<ReplayPlayer, String> {
+        let source = fs::read_to_string(path).map_err(|error| error.to_string())?;
+
+        let mut version = None;
+        let mut map_path = None;
+        let mut map_type = MapType::Hybrid;
+        let mut start_pose = None;
+        let mut seed = None;
+        let mut entries = vec![];
+
+        for (line_index, line) in source.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let words: Vec<&str> = line.split_whitespace().collect();
+
+            let line_error = |message: &str| format!("line {}: {}", line_index + 1, message);
+            let number = |word: &str| word.parse::<f32>().map_err(|_| line_error(&format!("invalid number \"{}\"", word)));
+
+            // Map path can contain spaces
+            if let Some(path) = line.strip_prefix("map ") {
+                map_path = Some(PathBuf::from(path.trim()));
+                continue;
+            }
+
+            match words.as_slice() {
+                [] => continue,
+
+                ["version", value] => version = Some(value.parse::<u32>().map_err(|_| line_error("invalid version"))?),
+                ["map_type", name] => map_type = MapType::from_name(name).ok_or_else(|| line_error("unknown map type"))?,
+                ["start", x, y] => start_pose = Some((vec2(number(x)?, number(y)?), None)),
+                ["start", x, y, direction] => start_pose = Some((vec2(number(x)?, number(y)?), Some(number(direction)?))),
+                ["seed", value] => seed = Some(value.parse::<u64>().map_err(|_| line_error("invalid seed"))?),
+
+                [step, entry @ ..] => {
+                    let step: u64 = step.parse().map_err(|_| line_error(&format!("unknown entry \"{}\"", step)))?;
+
+                    if entries.last().is_some_and(|(last_step, _)| *last_step > step) {
+                        return Err(line_error("steps should go in order"));
+                    }
+
+                    let entry = match entry {
+                        ["look", x, y] => ReplayEntry::Look(vec2(number(x)?, number(y)?)),
+                        ["pose", x, y, direction] => ReplayEntry::Pose(ReplayPose { position: vec2(number(x)?, number(y)?), direction: number(direction)? }),
+                        [action, is_active, value] => {
+                            let action = InputAction::from_name(action)
+                                .filter(|action| InputAction::GAMEPLAY.contains(action))
+                                .ok_or_else(|| line_error(&format!("unknown gameplay action \"{}\"", action)))?;
+
+                            let is_active = match *is_active {
+                                "0" => false,
+                                "1" => true,
+                                _ => return Err(line_error("action state should be 0 or 1"))
+                            };
+
+                            ReplayEntry::Action(action, ActionState { is_active, value: number(value)? })
+                        }
+                        _ => return Err(line_error("step entry should be an action state, look delta or pose"))
+                    };
+
+                    entries.push((step, entry));
+                }
+            }
+        }
+
+        match version {
+            Some(REPLAY_VERSION) => {}
+            Some(version) => return Err(format!("unsupported replay version {}, expected {}", version, REPLAY_VERSION)),
+            None => return Err("replay version is missing".to_string())
+        }
+
+        let header = ReplayHeader {
+            map_path: map_path.ok_or("map of the replay is missing")?,
+            map_type,
+            start_pose,
+            seed: seed.ok_or("seed of the replay is missing")?
+        };
+
+        let last_step = entries.last().map(|(step, _)| *step).unwrap_or(0);
+
+        Ok(ReplayPlayer { header, entries, next_entry: 0, step: 0, last_step, diverged_step: None, is_finished: false })
+    }
+
+    pub fn header(&self) -> &ReplayHeader {
+        &self.header
+    }
+
+    fn play_step(&mut self, input: &mut InputState, pose: Option<ReplayPose>) {
+        if self.is_finished {
+            return;
+        }
+
+        let mut look_delta = vec2(0.0, 0.0);
+
+        while let Some((step, entry)) = self.entries.get(self.next_entry) {
+            if *step > self.step {
+                break;
+            }
+
+            match entry {
+                ReplayEntry::Action(action, action_state) => input.set_replayed_action(*action, *action_state),
+                ReplayEntry::Look(delta) => look_delta = *delta,
+                ReplayEntry::Pose(recorded_pose) => {
+                    if self.diverged_step.is_none() && pose != Some(*recorded_pose) {
+                        log::error!("Replay has diverged at step {}: player pose is {:?}, recorded pose is {:?}", self.step, pose, recorded_pose);
+                        self.diverged_step = Some(self.step);
+                    }
+                }
+            }
+
+            self.next_entry += 1;
+        }
+
+        input.set_look_delta(look_delta);
+
+        if self.step >= self.last_step {
+            log::info!("Replay of {} steps has been played", self.step + 1);
+            self.is_finished = true;
+        }
+
+        self.step += 1;
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        match self.diverged_step {
+            Some(step) => Err(format!("replay has diverged at step {}", step)),
+            None => Ok(())
+        }
+    }
+}
+
+fn write_header(writer: &mut impl Write, header: &ReplayHeader) -> std::io::Result<()> {
+    writeln!(writer, "# Input replay, played with --replay")?;
+    writeln!(writer, "version {}", REPLAY_VERSION)?;
+    writeln!(writer, "map {}", header.map_path.display())?;
+    writeln!(writer, "map_type {}", header.map_type.name())?;
+
+    match header.start_pose {
+        None => {}
+        Some((position, None)) => writeln!(writer, "start {} {}", position.x, position.y)?,
+        Some((position, Some(direction))) => writeln!(writer, "start {} {} {}", position.x, position.y, direction)?
+    }
+
+    writeln!(writer, "seed {}", header.seed)
+}
+
+fn write_pose(writer: &mut impl Write, step: u64, pose: ReplayPose) -> std::io::Result<()> {
+    writeln!(writer, "{} pose {} {} {}", step, pose.position.x, pose.position.y, pose.direction)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::iter;
+    use std::path::PathBuf;
+    use std::process;
+
+    use glm::vec2;
+
+    use crate::game::input::bindings::Bindings;
+    use crate::game::input::input_action::InputAction;
+    use crate::game::input::input_state::{ActionState, InputState};
+    use crate::game::launch_options::{LaunchOptions, MapType};
+
+    use super::{InputReplay, ReplayHeader, ReplayPlayer, ReplayPose, ReplayRecorder, POSE_CHECKPOINT_STEPS};
+
+    // Longer than two pose checkpoints, ends between them
+    const STEPS_COUNT: u64 = 150;
+
+    fn replay_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("raycaster_replay_{}_{}.replay", process::id(), name))
+    }
+
+    fn create_input() -> InputState {
+        let options = LaunchOptions::parse(iter::empty()).unwrap();
+        let mut input = InputState::new(Bindings::default(), options.mouse_settings, options.gamepad_settings);
+
+        input.start_replaying();
+        input
+    }
+
+    fn header() -> ReplayHeader {
+        ReplayHeader { map_path: PathBuf::from("maps/demo map.map"), map_type: MapType::Tilemap, start_pose: Some((vec2(1.5, 2.5), Some(0.25))), seed: 42 }
+    }
+
+    // Input of the step: forward is held for a while, strafing goes with analog value, look moves on odd steps
+    fn step_input(step: u64, input: &mut InputState) {
+        input.set_replayed_action(InputAction::MoveForward, ActionState { is_active: (10..70).contains(&step), value: if (10..70).contains(&step) { 1.0 } else { 0.0 } });
+        input.set_replayed_action(InputAction::StrafeRight, ActionState { is_active: step >= 40, value: if step >= 40 { 0.5 } else { 0.0 } });
+        input.set_look_delta(if step % 2 == 1 { vec2(0.01, -0.02) } else { vec2(0.0, 0.0) });
+    }
+
+    fn step_pose(step: u64) -> ReplayPose {
+        ReplayPose { position: vec2(1.5 + step as f32 * 0.01, 2.5), direction: 0.25 + step as f32 * 0.005 }
+    }
+
+    fn record(name: &str) -> PathBuf {
+        let path = replay_path(name);
+        let mut replay = InputReplay::Recording(ReplayRecorder::create(&path, &header()).unwrap());
+        let mut input = create_input();
+
+        for step in 0..STEPS_COUNT {
+            step_input(step, &mut input);
+            replay.step(&mut input, Some(step_pose(step)));
+        }
+
+        replay.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn recorded_input_is_played_back() {
+        let path = record("round_trip");
+        let player = ReplayPlayer::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let loaded_header = player.header();
+        assert_eq!(loaded_header.map_path, header().map_path);
+        assert_eq!(loaded_header.map_type, MapType::Tilemap);
+        assert_eq!(loaded_header.start_pose, header().start_pose);
+        assert_eq!(loaded_header.seed, 42);
+
+        let mut replay = InputReplay::Playing(player);
+        let (mut played_input, mut expected_input) = (create_input(), create_input());
+
+        for step in 0..STEPS_COUNT {
+            assert!(!replay.is_finished());
+
+            replay.step(&mut played_input, Some(step_pose(step)));
+            step_input(step, &mut expected_input);
+
+            for action in InputAction::GAMEPLAY {
+                assert_eq!(played_input.action_state(action), expected_input.action_state(action), "{} at step {}", action.name(), step);
+            }
+
+            assert_eq!(played_input.look_delta(), expected_input.look_delta(), "look delta at step {}", step);
+        }
+
+        assert!(replay.is_finished());
+        assert!(replay.finish().is_ok());
+    }
+
+    #[test]
+    fn diverged_pose_is_reported() {
+        let path = record("diverged");
+        let mut replay = InputReplay::Playing(ReplayPlayer::load(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+
+        let mut input = create_input();
+
+        for step in 0..STEPS_COUNT {
+            let mut pose = step_pose(step);
+
+            if step >= 100 {
+                pose.position.x += 0.001;
+            }
+
+            replay.step(&mut input, Some(pose));
+        }
+
+        // Divergence is noticed at the next pose checkpoint
+        assert_eq!(replay.finish(), Err(format!("replay has diverged at step {}", POSE_CHECKPOINT_STEPS * 2)));
+    }
+
+    #[test]
+    fn unsupported_version_is_rejected() {
+        let path = replay_path("version");
+        fs::write(&path, "version 2\nmap maps/demo.map\nseed 1\n").unwrap();
+
+        let result = ReplayPlayer::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.err(), Some("unsupported replay version 2, expected 1".to_string()));
+    }
+}
@@ -0,0 +1,103 @@
+use std::fs;
+use std::path::Path;
+
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod};
+
+use crate::game::input::bindings::InputSource;
+
+// Scripted input changes fed as SDL events, so gamepad and keyboard handling can be tested without hardware.
+//
+// Text format, one input change per line, "#" starts a comment:
+//   FRAME SOURCE VALUE
+// Sources are named as in the bindings file. VALUE is 0 or 1 for keys and buttons,
+// deflection from 0 to 1 to the axis side for axes
+pub struct SyntheticInput {
+    changes: Vec<(u64, InputSource, f32)>,
+    next_change: usize
+}
+
+impl SyntheticInput {
+    pub fn load(path: &Path) -> Result<SyntheticInput, String> {
+        let source = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        let mut changes = vec![];
+
+        for (line_index, line) in source.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+
+            let (frame, source, value) = match words.as_slice() {
+                [frame, source, value] => (*frame, *source, *value),
+                _ => return Err(format!("line {}: input change should be given as \"FRAME SOURCE VALUE\"", line_index + 1))
+            };
+
+            let frame: u64 = frame.parse().map_err(|_| format!("line {}: invalid frame \"{}\"", line_index + 1, frame))?;
+            let source = InputSource::from_name(source).ok_or_else(|| format!("line {}: unknown input \"{}\"", line_index + 1, source))?;
+
+            let value = value.parse::<f32>().ok()
+                .filter(|value| (0.0..=1.0).contains(value))
+                .ok_or_else(|| format!("line {}: value should be from 0 to 1", line_index + 1))?;
+
+            changes.push((frame, source, value));
+        }
+
+        // Changes of the same frame keep the file order
+        changes.sort_by_key(|(frame, _, _)| *frame);
+
+        Ok(SyntheticInput { changes, next_change: 0 })
+    }
+
+    // Events of the changes scheduled up to the given frame
+    pub fn take_events(&mut self, frame: u64) -> Vec<Event> {
+        let mut events = vec![];
+
+        while let Some((change_frame, source, value)) = self.changes.get(self.next_change) {
+            if *change_frame > frame {
+                break;
+            }
+
+            events.push(source_event(*source, *value));
+            self.next_change += 1;
+        }
+
+        events
+    }
+}
+
+fn source_event(source: InputSource, value: f32) -> Event {
+    let is_pressed = value > 0.0;
+
+    match source {
+        InputSource::Key(scancode) => {
+            let keycode = Keycode::from_scancode(scancode);
+
+            if is_pressed {
+                Event::KeyDown { timestamp: 0, window_id: 0, keycode, scancode: Some(scancode), keymod: Mod::NOMOD, repeat: false }
+            } else {
+                Event::KeyUp { timestamp: 0, window_id: 0, keycode, scancode: Some(scancode), keymod: Mod::NOMOD, repeat: false }
+            }
+        }
+
+        InputSource::MouseButton(mouse_btn) => if is_pressed {
+            Event::MouseButtonDown { timestamp: 0, window_id: 0, which: 0, mouse_btn, clicks: 1, x: 0, y: 0 }
+        } else {
+            Event::MouseButtonUp { timestamp: 0, window_id: 0, which: 0, mouse_btn, clicks: 1, x: 0, y: 0 }
+        },
+
+        InputSource::GamepadButton(button) => if is_pressed {
+            Event::ControllerButtonDown { timestamp: 0, which: 0, button }
+        } else {
+            Event::ControllerButtonUp { timestamp: 0, which: 0, button }
+        },
+
+        InputSource::GamepadAxis { axis, is_positive } => {
+            let side = if is_positive { 1.0 } else { -1.0 };
+            Event::ControllerAxisMotion { timestamp: 0, which: 0, axis, value: (side * value * i16::MAX as f32) as i16 }
+        }
+    }
+}
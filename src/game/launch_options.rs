@@ -0,0 +1,359 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use glm::{uvec2, vec2, UVec2, Vec2};
+
+use crate::game::graphics::frame_recorder::RecordingFormat;
+use crate::game::graphics::view_mode::ViewMode;
+use crate::game::input::input_state::{GamepadSettings, MouseSettings};
+use crate::game::save::save_service::is_valid_slot_name;
+
+pub const USAGE: &str = "\
+Usage: rust-raycaster [OPTIONS] [MAP]
+
+Options:
+  --map PATH                 Map file, \"maps/demo.map\" by default
+  --map-type TYPE            Map layers to use: tilemap, linemap or hybrid (all layers of the map)
+  --window WIDTHxHEIGHT      Window size and internal resolution, 800x600 by default
+  --fullscreen               Start in fullscreen mode
+  --fps FPS                  Target frames per second, 30 by default
+  --columns COUNT            Rays per frame, scene width by default
+  --render-distance TILES    Maximal rendering distance, 7 by default
+  --threads COUNT            Ray casting threads, all CPU cores by default. 1 casts rays on the main thread
+  --view MODE                Initial view mode: 3d, 2d, minimap or split, minimap by default
+  --log-level LEVEL          Logging filter, e.g. info or debug. LOG_LEVEL environment variable by default
+  --start X,Y[,ANGLE]        Player start position and direction in degrees, overrides the map start
+  --record FORMAT            Record frames from the start: png or y4m
+  --headless                 Render without visible window and exit after given frames count
+  --frames COUNT             Frames to render in headless mode
+  --output DIR               Output directory of headless frames, \"frames\" by default
+  --profile-csv PATH         Log per-system frame timings to the CSV file
+  --mouse-sensitivity DEG    Mouse look degrees per mouse count, 0.15 by default
+  --invert-mouse-x           Invert horizontal mouse look
+  --invert-mouse-y           Invert vertical mouse look
+  --stick-deadzone PART      Ignored part of the gamepad sticks range, 0.2 by default
+  --trigger-deadzone PART    Ignored part of the gamepad triggers range, 0.1 by default
+  --input-script PATH        Feed scripted input changes, e.g. for testing without a gamepad
+  --record-replay PATH       Record gameplay input of every simulation step to the replay file
+  --replay PATH              Play the recorded replay instead of the gameplay input and exit at its end
+  --seed SEED                Seed of the level script random numbers, random by default
+  --save-slot NAME           Slot of the quick save and quick load, \"quick\" by default
+  --load NAME                Load the saved game of the slot, its level is started
+  --bindings PATH            Input bindings file, \"bindings.cfg\" by default, saved after rebinding in the game
+  --help                     Print this message
+
+Values can be given as \"--option value\" or \"--option=value\"";
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MapType {
+    Tilemap,
+    Linemap,
+    Hybrid
+}
+
+impl MapType {
+    pub fn from_name(name: &str) -> Option<MapType> {
+        match name {
+            "tilemap" => Some(MapType::Tilemap),
+            "linemap" => Some(MapType::Linemap),
+            "hybrid" => Some(MapType::Hybrid),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MapType::Tilemap => "tilemap",
+            MapType::Linemap => "linemap",
+            MapType::Hybrid => "hybrid"
+        }
+    }
+}
+
+pub struct HeadlessOptions {
+    pub frames: u32,
+    pub output: PathBuf
+}
+
+pub struct LaunchOptions {
+    pub map_path: PathBuf,
+    pub map_type: MapType,
+    pub window_size: UVec2,
+    pub is_fullscreen: bool,
+    pub target_fps: u32,
+    pub columns: Option<u32>,
+    pub rendering_distance: f32,
+    pub ray_casting_threads: usize,
+    pub view_mode: ViewMode,
+    pub log_level: Option<String>,
+    pub start_pose: Option<(Vec2, Option<f32>)>,
+    pub recording: Option<RecordingFormat>,
+    pub headless: Option<HeadlessOptions>,
+    pub profile_csv_path: Option<PathBuf>,
+    pub bindings_path: PathBuf,
+    pub mouse_settings: MouseSettings,
+    pub gamepad_settings: GamepadSettings,
+    pub input_script_path: Option<PathBuf>,
+    pub replay_recording_path: Option<PathBuf>,
+    pub replay_path: Option<PathBuf>,
+    pub seed: u64,
+    pub save_slot: String,
+    pub load_slot: Option<String>,
+    pub is_help_requested: bool
+}
+
+impl LaunchOptions {
+    pub fn parse(arguments: impl Iterator<Item = String>) -> Result<LaunchOptions, String> {
+        let mut options = LaunchOptions {
+            map_path: PathBuf::from("maps/demo.map"),
+            map_type: MapType::Hybrid,
+            window_size: uvec2(800, 600),
+            is_fullscreen: false,
+            target_fps: 30,
+            columns: None,
+            rendering_distance: 7.0,
+            ray_casting_threads: available_threads(),
+            view_mode: ViewMode::Minimap,
+            log_level: None,
+            start_pose: None,
+            recording: None,
+            headless: None,
+            profile_csv_path: None,
+            bindings_path: PathBuf::from("bindings.cfg"),
+            mouse_settings: MouseSettings { sensitivity: 0.15f32.to_radians(), is_inverted_x: false, is_inverted_y: false },
+            gamepad_settings: GamepadSettings { stick_deadzone: 0.2, trigger_deadzone: 0.1 },
+            input_script_path: None,
+            replay_recording_path: None,
+            replay_path: None,
+            seed: random_seed(),
+            save_slot: "quick".to_string(),
+            load_slot: None,
+            is_help_requested: false
+        };
+
+        let mut is_headless = false;
+        let mut headless_frames: Option<u32> = None;
+        let mut headless_output = PathBuf::from("frames");
+
+        let mut arguments = arguments.peekable();
+
+        while let Some(argument) = arguments.next() {
+            let (name, inline_value) = match argument.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (argument.clone(), None)
+            };
+
+            // Flags have no value, other options take the inline value or the next argument
+            let mut value = || inline_value.clone()
+                .or_else(|| arguments.next())
+                .ok_or_else(|| format!("option {} requires a value", name));
+
+            match name.as_str() {
+                "--help" | "-h" => options.is_help_requested = true,
+                "--fullscreen" => options.is_fullscreen = true,
+                "--headless" => is_headless = true,
+                "--invert-mouse-x" => options.mouse_settings.is_inverted_x = true,
+                "--invert-mouse-y" => options.mouse_settings.is_inverted_y = true,
+
+                "--map" => options.map_path = PathBuf::from(value()?),
+                "--map-type" => {
+                    let map_type = value()?;
+                    options.map_type = MapType::from_name(&map_type)
+                        .ok_or_else(|| format!("unknown map type \"{}\", expected tilemap, linemap or hybrid", map_type))?;
+                }
+                "--window" => options.window_size = parse_window_size(&value()?)?,
+                "--fps" => options.target_fps = parse_positive(&name, &value()?)?,
+                "--columns" => options.columns = Some(parse_positive(&name, &value()?)?),
+                "--threads" => options.ray_casting_threads = parse_positive(&name, &value()?)? as usize,
+                "--render-distance" => options.rendering_distance = parse_positive_real(&name, &value()?)?,
+                "--view" => {
+                    let view_mode = value()?;
+                    options.view_mode = ViewMode::from_name(&view_mode)
+                        .ok_or_else(|| format!("unknown view mode \"{}\", expected 3d, 2d, minimap or split", view_mode))?;
+                }
+                "--log-level" => options.log_level = Some(value()?),
+                "--start" => options.start_pose = Some(parse_start_pose(&value()?)?),
+                "--record" => {
+                    let format = value()?;
+                    options.recording = Some(RecordingFormat::from_name(&format)
+                        .ok_or_else(|| format!("unknown recording format \"{}\", expected png or y4m", format))?);
+                }
+                "--frames" => headless_frames = Some(parse_positive(&name, &value()?)?),
+                "--output" => headless_output = PathBuf::from(value()?),
+                "--profile-csv" => options.profile_csv_path = Some(PathBuf::from(value()?)),
+                "--bindings" => options.bindings_path = PathBuf::from(value()?),
+                "--stick-deadzone" => options.gamepad_settings.stick_deadzone = parse_deadzone(&name, &value()?)?,
+                "--trigger-deadzone" => options.gamepad_settings.trigger_deadzone = parse_deadzone(&name, &value()?)?,
+                "--input-script" => options.input_script_path = Some(PathBuf::from(value()?)),
+                "--record-replay" => options.replay_recording_path = Some(PathBuf::from(value()?)),
+                "--replay" => options.replay_path = Some(PathBuf::from(value()?)),
+                "--seed" => options.seed = parse_number(&name, &value()?)?,
+                "--save-slot" => options.save_slot = parse_slot(&name, &value()?)?,
+                "--load" => options.load_slot = Some(parse_slot(&name, &value()?)?),
+                "--mouse-sensitivity" => options.mouse_settings.sensitivity = parse_positive_real(&name, &value()?)?.to_radians(),
+
+                _ if !name.starts_with('-') => options.map_path = PathBuf::from(argument),
+                _ => return Err(format!("unknown option {}", name))
+            }
+        }
+
+        match (is_headless, headless_frames) {
+            (true, Some(frames)) => options.headless = Some(HeadlessOptions { frames, output: headless_output }),
+            (true, None) => return Err("headless mode requires --frames".to_string()),
+            (false, Some(_)) => return Err("option --frames can be used only with --headless".to_string()),
+            (false, None) => {}
+        }
+
+        if options.replay_path.is_some() && options.replay_recording_path.is_some() {
+            return Err("options --replay and --record-replay can't be used together".to_string());
+        }
+
+        // Replays are played from the level start
+        if options.load_slot.is_some() && (options.replay_path.is_some() || options.replay_recording_path.is_some()) {
+            return Err("option --load can't be used with replays".to_string());
+        }
+
+        Ok(options)
+    }
+}
+
+// Seeds differ between launches unless the seed is given
+fn random_seed() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|time| time.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+fn available_threads() -> usize {
+    thread::available_parallelism()
+        .map(|threads_count| threads_count.get())
+        .unwrap_or(1)
+}
+
+fn parse_number<T: FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value.trim().parse().map_err(|_| format!("option {} has invalid value \"{}\"", name, value))
+}
+
+fn parse_positive(name: &str, value: &str) -> Result<u32, String> {
+    match parse_number(name, value)? {
+        0 => Err(format!("option {} should be positive", name)),
+        number => Ok(number)
+    }
+}
+
+// Infinity and NaN are not accepted
+fn parse_positive_real(name: &str, value: &str) -> Result<f32, String> {
+    let number: f32 = parse_number(name, value)?;
+
+    if !number.is_finite() || number <= 0.0 {
+        return Err(format!("option {} should be positive", name));
+    }
+
+    Ok(number)
+}
+
+fn parse_deadzone(name: &str, value: &str) -> Result<f32, String> {
+    let deadzone: f32 = parse_number(name, value)?;
+
+    if !(0.0..1.0).contains(&deadzone) {
+        return Err(format!("option {} should be from 0 to 1", name));
+    }
+
+    Ok(deadzone)
+}
+
+fn parse_slot(name: &str, value: &str) -> Result<String, String> {
+    match is_valid_slot_name(value) {
+        true => Ok(value.to_string()),
+        false => Err(format!("option {} should contain only letters, digits, \"-\" and \"_\"", name))
+    }
+}
+
+fn parse_window_size(value: &str) -> Result<UVec2, String> {
+    let (width, height) = value.split_once('x')
+        .ok_or_else(|| format!("window size \"{}\" should be given as WIDTHxHEIGHT", value))?;
+
+    Ok(uvec2(parse_positive("--window", width)?, parse_positive("--window", height)?))
+}
+
+fn parse_start_pose(value: &str) -> Result<(Vec2, Option<f32>), String> {
+    let parts: Vec<&str> = value.split(',').collect();
+
+    if parts.len() < 2 || parts.len() > 3 {
+        return Err(format!("start pose \"{}\" should be given as X,Y or X,Y,ANGLE", value));
+    }
+
+    let position = vec2(parse_number("--start", parts[0])?, parse_number("--start", parts[1])?);
+    let direction = match parts.get(2) {
+        None => None,
+        Some(angle) => Some(parse_number::<f32>("--start", angle)?.to_radians())
+    };
+
+    Ok((position, direction))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use glm::uvec2;
+
+    use super::{LaunchOptions, MapType};
+
+    fn parse(arguments: &[&str]) -> Result<LaunchOptions, String> {
+        LaunchOptions::parse(arguments.iter().map(|argument| argument.to_string()))
+    }
+
+    #[test]
+    fn values_are_given_inline_or_separately() {
+        let options = parse(&["--map-type=linemap", "--window", "320x200", "--fps=60", "--threads", "2", "maps/other.map"]).unwrap();
+
+        assert_eq!(options.map_type, MapType::Linemap);
+        assert_eq!(options.window_size, uvec2(320, 200));
+        assert_eq!(options.target_fps, 60);
+        assert_eq!(options.ray_casting_threads, 2);
+        assert_eq!(options.map_path, PathBuf::from("maps/other.map"));
+    }
+
+    #[test]
+    fn missing_and_invalid_values_are_rejected() {
+        assert!(parse(&["--fps"]).is_err());
+        assert!(parse(&["--fps=0"]).is_err());
+        assert!(parse(&["--window=320"]).is_err());
+        assert!(parse(&["--map-type", "voxels"]).is_err());
+        assert!(parse(&["--unknown"]).is_err());
+    }
+
+    #[test]
+    fn real_values_should_be_positive_and_finite() {
+        assert_eq!(parse(&["--render-distance=12.5"]).unwrap().rendering_distance, 12.5);
+
+        for value in ["0", "-1", "nan", "inf"] {
+            assert!(parse(&["--render-distance", value]).is_err(), "render distance {}", value);
+            assert!(parse(&["--mouse-sensitivity", value]).is_err(), "mouse sensitivity {}", value);
+        }
+    }
+
+    #[test]
+    fn headless_mode_requires_frames_count() {
+        let options = parse(&["--headless", "--frames", "10", "--output=out"]).unwrap();
+        let headless = options.headless.unwrap();
+
+        assert_eq!(headless.frames, 10);
+        assert_eq!(headless.output, PathBuf::from("out"));
+
+        assert!(parse(&["--headless"]).is_err());
+        assert!(parse(&["--frames", "10"]).is_err());
+        assert!(parse(&[]).unwrap().headless.is_none());
+    }
+
+    #[test]
+    fn conflicting_options_are_rejected() {
+        assert!(parse(&["--replay", "a.replay", "--record-replay", "b.replay"]).is_err());
+        assert!(parse(&["--load", "quick", "--replay", "a.replay"]).is_err());
+        assert!(parse(&["--load", "quick", "--record-replay", "b.replay"]).is_err());
+        assert!(parse(&["--load", "../quick"]).is_err());
+    }
+}
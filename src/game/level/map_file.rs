@@ -0,0 +1,365 @@
+use std::str::FromStr;
+
+use glm::{uvec2, vec2, UVec2, Vec2};
+use sdl2::rect::Rect;
+
+use crate::game::ecs::component::door_component::{DoorBinding, DoorComponent, DoorKind};
+use crate::game::ecs::component::moving_segment_component::MovingSegmentComponent;
+use crate::game::ecs::component::push_wall_component::PushWallComponent;
+use crate::game::ecs::component::trigger_component::{TriggerComponent, TriggerRegion};
+use crate::game::event::event_action::EventAction;
+use crate::game::model::linemap::Linemap;
+use crate::game::model::object_color::ObjectColor;
+use crate::game::model::tile::{Tile, TileAxis, TileDiagonal, TileEdge, TileShape};
+use crate::game::model::ResourceId;
+
+pub enum MapLayer {
+    Tilemap(ResourceId),
+    Linemap(ResourceId)
+}
+
+pub enum MapAction {
+    // Index of the door in the map file, doors entities are known only after loading
+    OpenDoor {
+        door_index: usize
+    },
+
+    Event(EventAction)
+}
+
+// Parsed map file: resources, level layers and entities.
+//
+// Text format, one statement per line, "#" starts a comment. Angles are given in degrees:
+//   tile ID COLOR air|solid|edge EDGE|centered AXIS|diagonal DIAGONAL
+//   tilemap ID, rows of tile ids, end
+//   linemap ID, "line COLOR X1 Y1 X2 Y2" and "rect COLOR X Y WIDTH HEIGHT" statements, end
+//   layer tilemap|linemap ID
+//   player X Y ANGLE
+//   key NAME
+//   health HEALTH, ammo AMMO
+//   door sliding|swinging COLOR tile X Y AXIS|line X1 Y1 X2 Y2 [locked [KEY]]
+//   push_wall TILEMAP X Y EDGE DISTANCE EMPTY_TILE
+//   moving_segment LINEMAP LINE DX DY
+//   trigger tiles X,Y [X,Y ...]|rect X1 Y1 X2 Y2 [enter=EVENT] [exit=EVENT] [use=EVENT]
+//   action EVENT set_tile TILEMAP X Y TILE|open_door DOOR_INDEX|teleport X Y [ANGLE]|message TEXT|damage AMOUNT|end_level
+pub struct MapFile {
+    pub tiles: Vec<Tile>,
+    pub tilemaps: Vec<(ResourceId, Vec<Vec<ResourceId>>)>,
+    pub linemaps: Vec<Linemap>,
+    pub layers: Vec<MapLayer>,
+    pub player_position: Vec2,
+    pub player_direction: f32,
+    pub player_keys: Vec<String>,
+    pub player_health: u32,
+    pub player_ammo: u32,
+    pub doors: Vec<DoorComponent>,
+    pub push_walls: Vec<PushWallComponent>,
+    pub moving_segments: Vec<MovingSegmentComponent>,
+    pub triggers: Vec<TriggerComponent>,
+    pub actions: Vec<(String, MapAction)>
+}
+
+type Statements<'a> = dyn Iterator<Item = (usize, Vec<&'a str>)> + 'a;
+
+impl MapFile {
+    pub fn parse(source: &str) -> Result<MapFile, String> {
+        let mut map_file = MapFile {
+            tiles: vec![],
+            tilemaps: vec![],
+            linemaps: vec![],
+            layers: vec![],
+            player_position: vec2(1.5, 1.5),
+            player_direction: 0.0,
+            player_keys: vec![],
+            player_health: 100,
+            player_ammo: 0,
+            doors: vec![],
+            push_walls: vec![],
+            moving_segments: vec![],
+            triggers: vec![],
+            actions: vec![]
+        };
+
+        let mut statements = source.lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.split('#').next().unwrap_or("").split_whitespace().collect::<Vec<&str>>()))
+            .filter(|(_, tokens)| !tokens.is_empty());
+
+        while let Some((line_number, tokens)) = statements.next() {
+            map_file.parse_statement(&tokens, &mut statements)
+                .map_err(|error| format!("line {}: {}", line_number, error))?;
+        }
+
+        if map_file.layers.is_empty() {
+            return Err("map has no layers".to_string());
+        }
+
+        Ok(map_file)
+    }
+
+    fn parse_statement<'a>(&mut self, tokens: &[&str], statements: &mut Statements<'a>) -> Result<(), String> {
+        match tokens[0] {
+            "tile" => self.tiles.push(parse_tile(tokens)?),
+
+            "tilemap" => {
+                let id = parse_token(tokens, 1)?;
+                self.tilemaps.push((id, parse_tilemap_rows(statements)?));
+            }
+
+            "linemap" => {
+                let id = parse_token(tokens, 1)?;
+                self.linemaps.push(parse_linemap(id, statements)?);
+            }
+
+            "layer" => match token(tokens, 1)? {
+                "tilemap" => self.layers.push(MapLayer::Tilemap(parse_token(tokens, 2)?)),
+                "linemap" => self.layers.push(MapLayer::Linemap(parse_token(tokens, 2)?)),
+                layer_type => return Err(format!("unknown layer type \"{}\"", layer_type))
+            },
+
+            "player" => {
+                self.player_position = vec2(parse_token(tokens, 1)?, parse_token(tokens, 2)?);
+                self.player_direction = parse_token::<f32>(tokens, 3)?.to_radians();
+            }
+
+            "key" => self.player_keys.push(token(tokens, 1)?.to_string()),
+            "health" => self.player_health = parse_token(tokens, 1)?,
+            "ammo" => self.player_ammo = parse_token(tokens, 1)?,
+
+            "door" => self.doors.push(parse_door(tokens)?),
+
+            "push_wall" => self.push_walls.push(PushWallComponent::new(
+                parse_token(tokens, 1)?,
+                uvec2(parse_token(tokens, 2)?, parse_token(tokens, 3)?),
+                parse_edge(token(tokens, 4)?)?,
+                parse_token(tokens, 5)?,
+                parse_token(tokens, 6)?
+            )),
+
+            "moving_segment" => self.moving_segments.push(MovingSegmentComponent::new(
+                parse_token(tokens, 1)?,
+                parse_token(tokens, 2)?,
+                vec2(parse_token(tokens, 3)?, parse_token(tokens, 4)?)
+            )),
+
+            "trigger" => self.triggers.push(parse_trigger(tokens)?),
+
+            "action" => {
+                let event_name = token(tokens, 1)?.to_string();
+                self.actions.push((event_name, parse_action(&tokens[2..])?));
+            }
+
+            statement => return Err(format!("unknown statement \"{}\"", statement))
+        }
+
+        Ok(())
+    }
+}
+
+fn token<'a>(tokens: &[&'a str], index: usize) -> Result<&'a str, String> {
+    tokens.get(index).copied().ok_or_else(|| format!("statement \"{}\" has too few values", tokens[0]))
+}
+
+fn parse_token<T: FromStr>(tokens: &[&str], index: usize) -> Result<T, String> {
+    let value = token(tokens, index)?;
+    value.parse().map_err(|_| format!("invalid value \"{}\" of statement \"{}\"", value, tokens[0]))
+}
+
+fn parse_color(name: &str) -> Result<ObjectColor, String> {
+    ObjectColor::from_name(name).ok_or_else(|| format!("unknown color \"{}\"", name))
+}
+
+fn parse_edge(name: &str) -> Result<TileEdge, String> {
+    TileEdge::from_name(name).ok_or_else(|| format!("unknown edge \"{}\", expected north, south, west or east", name))
+}
+
+fn parse_axis(name: &str) -> Result<TileAxis, String> {
+    TileAxis::from_name(name).ok_or_else(|| format!("unknown axis \"{}\", expected horizontal or vertical", name))
+}
+
+fn parse_tile(tokens: &[&str]) -> Result<Tile, String> {
+    let id = parse_token(tokens, 1)?;
+    let color = parse_color(token(tokens, 2)?)?;
+
+    let shape = match token(tokens, 3)? {
+        "air" => return Ok(Tile::new(id, color, false)),
+        "solid" => return Ok(Tile::new(id, color, true)),
+        "edge" => TileShape::EdgeWall { edge: parse_edge(token(tokens, 4)?)? },
+        "centered" => TileShape::CenteredWall { axis: parse_axis(token(tokens, 4)?)? },
+        "diagonal" => TileShape::Diagonal {
+            diagonal: match token(tokens, 4)? {
+                "falling" => TileDiagonal::Falling,
+                "rising" => TileDiagonal::Rising,
+                diagonal => return Err(format!("unknown diagonal \"{}\", expected falling or rising", diagonal))
+            }
+        },
+        shape => return Err(format!("unknown tile shape \"{}\"", shape))
+    };
+
+    Ok(Tile::with_shape(id, color, shape))
+}
+
+fn parse_tilemap_rows(statements: &mut Statements) -> Result<Vec<Vec<ResourceId>>, String> {
+    let mut rows: Vec<Vec<ResourceId>> = vec![];
+
+    for (line_number, tokens) in &mut *statements {
+        if tokens[0] == "end" {
+            return match rows.first() {
+                None => Err("tilemap has no rows".to_string()),
+                Some(first_row) if rows.iter().any(|row| row.len() != first_row.len()) => Err("tilemap rows have different lengths".to_string()),
+                Some(_) => Ok(rows)
+            };
+        }
+
+        let row = tokens.iter()
+            .map(|tile_id| tile_id.parse().map_err(|_| format!("invalid tile id \"{}\" at line {}", tile_id, line_number)))
+            .collect::<Result<Vec<ResourceId>, String>>()?;
+
+        rows.push(row);
+    }
+
+    Err("tilemap has no \"end\"".to_string())
+}
+
+fn parse_linemap(id: ResourceId, statements: &mut Statements) -> Result<Linemap, String> {
+    let mut linemap = Linemap::new(id);
+
+    for (line_number, tokens) in &mut *statements {
+        if tokens[0] == "end" {
+            return Ok(linemap);
+        }
+
+        parse_linemap_statement(&mut linemap, &tokens).map_err(|error| format!("{} at line {}", error, line_number))?;
+    }
+
+    Err("linemap has no \"end\"".to_string())
+}
+
+fn parse_linemap_statement(linemap: &mut Linemap, tokens: &[&str]) -> Result<(), String> {
+    let color = parse_color(token(tokens, 1)?)?;
+
+    match tokens[0] {
+        "line" => {
+            let from = vec2(parse_token(tokens, 2)?, parse_token(tokens, 3)?);
+            let to = vec2(parse_token(tokens, 4)?, parse_token(tokens, 5)?);
+
+            linemap.add_line(color, from, to);
+        }
+
+        "rect" => {
+            let rect = Rect::new(parse_token(tokens, 2)?, parse_token(tokens, 3)?, parse_token(tokens, 4)?, parse_token(tokens, 5)?);
+
+            linemap.add_rect(color, rect);
+        }
+
+        statement => return Err(format!("unknown linemap statement \"{}\"", statement))
+    }
+
+    Ok(())
+}
+
+fn parse_door(tokens: &[&str]) -> Result<DoorComponent, String> {
+    let kind = token(tokens, 1)?;
+    let kind = DoorKind::from_name(kind).ok_or_else(|| format!("unknown door kind \"{}\", expected sliding or swinging", kind))?;
+
+    let color = parse_color(token(tokens, 2)?)?;
+
+    let (binding, lock_index) = match token(tokens, 3)? {
+        "tile" => {
+            let position = uvec2(parse_token(tokens, 4)?, parse_token(tokens, 5)?);
+            (DoorBinding::Tile { position, axis: parse_axis(token(tokens, 6)?)? }, 7)
+        }
+
+        "line" => {
+            let from = vec2(parse_token(tokens, 4)?, parse_token(tokens, 5)?);
+            let to = vec2(parse_token(tokens, 6)?, parse_token(tokens, 7)?);
+            (DoorBinding::Line { from, to }, 8)
+        }
+
+        binding => return Err(format!("unknown door binding \"{}\", expected tile or line", binding))
+    };
+
+    let door = DoorComponent::new(kind, binding, color);
+
+    match tokens.get(lock_index) {
+        None => Ok(door),
+        Some(&"locked") => Ok(door.with_lock(tokens.get(lock_index + 1).copied())),
+        Some(value) => Err(format!("unexpected door value \"{}\"", value))
+    }
+}
+
+fn parse_trigger(tokens: &[&str]) -> Result<TriggerComponent, String> {
+    let (region, events_index) = match token(tokens, 1)? {
+        "rect" => {
+            let from = vec2(parse_token(tokens, 2)?, parse_token(tokens, 3)?);
+            let to = vec2(parse_token(tokens, 4)?, parse_token(tokens, 5)?);
+            (TriggerRegion::Rect { from, to }, 6)
+        }
+
+        "tiles" => {
+            let tiles = tokens[2..].iter()
+                .take_while(|value| !value.contains('='))
+                .map(|value| parse_tile_position(value))
+                .collect::<Result<Vec<UVec2>, String>>()?;
+
+            let events_index = 2 + tiles.len();
+            (TriggerRegion::Tiles { tiles }, events_index)
+        }
+
+        region => return Err(format!("unknown trigger region \"{}\", expected rect or tiles", region))
+    };
+
+    let mut trigger = TriggerComponent::new(region);
+
+    for event in &tokens[events_index..] {
+        trigger = match event.split_once('=') {
+            Some(("enter", event_name)) => trigger.on_enter(event_name),
+            Some(("exit", event_name)) => trigger.on_exit(event_name),
+            Some(("use", event_name)) => trigger.on_use(event_name),
+            _ => return Err(format!("unknown trigger event \"{}\", expected enter=, exit= or use=", event))
+        };
+    }
+
+    Ok(trigger)
+}
+
+fn parse_tile_position(value: &str) -> Result<UVec2, String> {
+    value.split_once(',')
+        .and_then(|(x, y)| Some(uvec2(x.parse().ok()?, y.parse().ok()?)))
+        .ok_or_else(|| format!("invalid tile position \"{}\", expected X,Y", value))
+}
+
+fn parse_action(tokens: &[&str]) -> Result<MapAction, String> {
+    let action = match tokens.first() {
+        None => return Err("action has no type".to_string()),
+        Some(action) => *action
+    };
+
+    let event_action = match action {
+        "open_door" => return Ok(MapAction::OpenDoor { door_index: parse_token(tokens, 1)? }),
+
+        "set_tile" => EventAction::SetTile {
+            tilemap: parse_token(tokens, 1)?,
+            position: uvec2(parse_token(tokens, 2)?, parse_token(tokens, 3)?),
+            tile: parse_token(tokens, 4)?
+        },
+
+        "teleport" => EventAction::Teleport {
+            position: vec2(parse_token(tokens, 1)?, parse_token(tokens, 2)?),
+            direction: match tokens.get(3) {
+                None => None,
+                Some(_) => Some(parse_token::<f32>(tokens, 3)?.to_radians())
+            }
+        },
+
+        "message" => EventAction::ShowMessage { text: tokens[1..].join(" ") },
+
+        "damage" => EventAction::Damage { amount: parse_token(tokens, 1)? },
+
+        "end_level" => EventAction::EndLevel,
+
+        action => return Err(format!("unknown action \"{}\"", action))
+    };
+
+    Ok(MapAction::Event(event_action))
+}
@@ -0,0 +1,141 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use ecs_rust::world::World;
+
+use crate::game::ecs::component::direction_component::DirectionComponent;
+use crate::game::ecs::component::ammo_component::AmmoComponent;
+use crate::game::ecs::component::health_component::HealthComponent;
+use crate::game::ecs::component::keys_component::KeysComponent;
+use crate::game::ecs::component::linemap_component::LinemapComponent;
+use crate::game::ecs::component::player_flag_component::PlayerFlagComponent;
+use crate::game::ecs::component::previous_pose_component::PreviousPoseComponent;
+use crate::game::ecs::component::velocity_component::VelocityComponent;
+use crate::game::ecs::component::vertical_motion_component::VerticalMotionComponent;
+use crate::game::ecs::component::position_component::PositionComponent;
+use crate::game::ecs::component::tilemap_component::TilemapComponent;
+use crate::game::event::event_action::EventAction;
+use crate::game::event::game_event::EventDispatcher;
+use crate::game::game_state::Repositories;
+use crate::game::launch_options::{LaunchOptions, MapType};
+use crate::game::level::map_file::{MapAction, MapFile, MapLayer};
+use crate::game::model::tilemap::Tilemap;
+
+pub mod map_file;
+
+pub fn load_map_file(path: &Path) -> Result<MapFile, String> {
+    let source = fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+    MapFile::parse(&source).map_err(|error| format!("{}: {}", path.display(), error))
+}
+
+// Moves tiles, tilemaps and linemaps of the map to the repositories
+pub fn register_map_resources(map_file: &mut MapFile, repositories: &Repositories) -> Result<(), String> {
+    let mut tiles_repository = repositories.tiles_repository().borrow_mut();
+
+    for tile in map_file.tiles.drain(..) {
+        tiles_repository.register_resource(Arc::new(tile));
+    }
+
+    for (tilemap_id, rows) in map_file.tilemaps.drain(..) {
+        let tiles = rows.iter()
+            .map(|row| row.iter()
+                .map(|tile_id| tiles_repository.get_resource(tile_id)
+                    .ok_or_else(|| format!("tile {} of tilemap {} is not found", tile_id, tilemap_id)))
+                .collect::<Result<Vec<_>, String>>())
+            .collect::<Result<Vec<_>, String>>()?;
+
+        repositories.tilemap_repository().borrow_mut().register_resource(Arc::new(Tilemap::from_raw_tilemap(tilemap_id, tiles)));
+    }
+
+    for linemap in map_file.linemaps.drain(..) {
+        repositories.linemap_repository().borrow_mut().register_resource(Arc::new(linemap));
+    }
+
+    Ok(())
+}
+
+// Creates entities of the map layers, player and level objects. Map type selects which layers are used
+pub fn spawn_level(map_file: MapFile, options: &LaunchOptions, world: &mut World, dispatcher: &mut EventDispatcher) -> Result<(), String> {
+    let layers: Vec<&MapLayer> = map_file.layers.iter()
+        .filter(|layer| matches!(
+            (options.map_type, layer),
+            (MapType::Hybrid, _) | (MapType::Tilemap, MapLayer::Tilemap(_)) | (MapType::Linemap, MapLayer::Linemap(_))
+        ))
+        .collect();
+
+    if layers.is_empty() {
+        return Err(format!("map has no {:?} layers", options.map_type));
+    }
+
+    for layer in layers {
+        let layer_entity_id = world.create_entity();
+
+        match layer {
+            MapLayer::Tilemap(tilemap_id) => world.add_component_to_entity(layer_entity_id, TilemapComponent::new(*tilemap_id)),
+            MapLayer::Linemap(linemap_id) => world.add_component_to_entity(layer_entity_id, LinemapComponent::new(*linemap_id))
+        };
+
+        log::info!("Creating map layer entity with id {}", layer_entity_id);
+    }
+
+    {
+        let (player_position, player_direction) = match options.start_pose {
+            None => (map_file.player_position, map_file.player_direction),
+            Some((position, direction)) => (position, direction.unwrap_or(map_file.player_direction))
+        };
+
+        let player_entity_id = world.create_entity();
+
+        log::info!("Creating player entity with id {}", player_entity_id);
+
+        world.add_component_to_entity(player_entity_id, PositionComponent::new(player_position));
+        world.add_component_to_entity(player_entity_id, DirectionComponent::new(player_direction));
+        world.add_component_to_entity(player_entity_id, PreviousPoseComponent::new(player_position, player_direction));
+        world.add_component_to_entity(player_entity_id, VelocityComponent::new());
+        world.add_component_to_entity(player_entity_id, VerticalMotionComponent::new());
+        world.add_component_to_entity(player_entity_id, PlayerFlagComponent::new());
+        world.add_component_to_entity(player_entity_id, KeysComponent::new(map_file.player_keys));
+        world.add_component_to_entity(player_entity_id, HealthComponent::new(map_file.player_health));
+        world.add_component_to_entity(player_entity_id, AmmoComponent::new(map_file.player_ammo));
+    }
+
+    let door_entity_ids: Vec<usize> = map_file.doors.into_iter()
+        .map(|door| {
+            let door_entity_id = world.create_entity();
+            world.add_component_to_entity(door_entity_id, door);
+            door_entity_id
+        })
+        .collect();
+
+    for push_wall in map_file.push_walls {
+        let push_wall_entity_id = world.create_entity();
+        world.add_component_to_entity(push_wall_entity_id, push_wall);
+    }
+
+    for moving_segment in map_file.moving_segments {
+        let moving_segment_entity_id = world.create_entity();
+        world.add_component_to_entity(moving_segment_entity_id, moving_segment);
+    }
+
+    for trigger in map_file.triggers {
+        let trigger_entity_id = world.create_entity();
+        world.add_component_to_entity(trigger_entity_id, trigger);
+    }
+
+    for (event_name, action) in map_file.actions {
+        let event_action = match action {
+            MapAction::Event(event_action) => event_action,
+            MapAction::OpenDoor { door_index } => match door_entity_ids.get(door_index) {
+                None => return Err(format!("door {} of event {} is not found", door_index, event_name)),
+                Some(door_entity_id) => EventAction::OpenDoor { door: *door_entity_id }
+            }
+        };
+
+        dispatcher.register_handler(&event_name, Box::new(event_action));
+    }
+
+    log::info!("Level has been created with {} doors", door_entity_ids.len());
+
+    Ok(())
+}
@@ -1,241 +1,553 @@
+use std::cell::RefCell;
+use std::env;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::time;
 use std::time::Duration;
 
 use ecs_rust::world::World;
-use glm::{vec2, Vec2};
-use sdl2::keyboard::Keycode;
-use sdl2::rect::Rect;
+use sdl2::event::Event;
 use sdl2::Sdl;
 
+use crate::game::ecs::component::ammo_component::AmmoComponent;
 use crate::game::ecs::component::direction_component::DirectionComponent;
+use crate::game::ecs::component::door_component::DoorComponent;
+use crate::game::ecs::component::health_component::HealthComponent;
+use crate::game::ecs::component::keys_component::KeysComponent;
 use crate::game::ecs::component::linemap_component::LinemapComponent;
+use crate::game::ecs::component::moving_segment_component::MovingSegmentComponent;
 use crate::game::ecs::component::player_flag_component::PlayerFlagComponent;
+use crate::game::ecs::component::previous_pose_component::PreviousPoseComponent;
 use crate::game::ecs::component::position_component::PositionComponent;
+use crate::game::ecs::component::push_wall_component::PushWallComponent;
 use crate::game::ecs::component::tilemap_component::TilemapComponent;
+use crate::game::ecs::component::trigger_component::TriggerComponent;
+use crate::game::ecs::component::velocity_component::VelocityComponent;
+use crate::game::ecs::component::vertical_motion_component::VerticalMotionComponent;
 use crate::game::ecs::system::moving_system::MovingSystem;
-use crate::game::ecs::system::tilemap_collision_resolving_system::TilemapCollisionResolvingSystem;
+use crate::game::ecs::fixed_step_systems::FixedStepSystems;
+use crate::game::ecs::raycast_worlds::RaycastWorlds;
+use crate::game::ecs::simulation_clock::{SimulationClock, SIMULATION_STEP};
+use crate::game::ecs::system::collision_resolving_system::CollisionResolvingSystem;
+use crate::game::ecs::system::door_system::DoorSystem;
+use crate::game::ecs::system::input_replay_system::InputReplaySystem;
+use crate::game::ecs::system::moving_geometry_system::MovingGeometrySystem;
+use crate::game::ecs::system::pose_history_system::PoseHistorySystem;
+use crate::game::ecs::system::rumble_system::RumbleSystem;
+use crate::game::ecs::system::save_system::SaveSystem;
+use crate::game::ecs::system::script_timer_system::ScriptTimerSystem;
+use crate::game::ecs::system::trigger_system::TriggerSystem;
+use crate::game::ecs::system::use_system::UseSystem;
+use crate::game::ecs::use_action::UseAction;
+use crate::game::ecs::system::vertical_moving_system::VerticalMovingSystem;
 use crate::game::event::events::Events;
-use crate::game::game_state::{GameState, Repositories};
+use crate::game::game_state::GameState;
 use crate::game::graphics::ecs::system::camera_position_sync_system::CameraPositionSyncSystem;
-use crate::game::graphics::ecs::system::linemap_2d_rendering_system::Linemap2DRenderingSystem;
-use crate::game::graphics::ecs::system::linemap_3d_rendering_system::Linemap3DRenderingSystem;
+use crate::game::graphics::ecs::system::hud_rendering_system::HudRenderingSystem;
 use crate::game::graphics::ecs::system::rendering_clear_system::RenderingClearSystem;
 use crate::game::graphics::ecs::system::rendering_swapbuffers_system::RenderingSwapBuffersSystem;
-use crate::game::graphics::ecs::system::tilemap_2d_rendering_system::Tilemap2DRenderingSystem;
-use crate::game::graphics::ecs::system::tilemap_3d_rendering_system::Tilemap3DRenderingSystem;
+use crate::game::graphics::ecs::system::world_2d_rendering_system::World2DRenderingSystem;
+use crate::game::graphics::ecs::system::world_3d_rendering_system::World3DRenderingSystem;
 use crate::game::graphics::Graphics;
-use crate::game::model::linemap::Linemap;
-use crate::game::model::object_color::ObjectColor;
-use crate::game::model::tile::Tile;
-use crate::game::model::tilemap::Tilemap;
+use crate::game::graphics::view_mode::ViewMode;
+use crate::game::input::bindings::Bindings;
+use crate::game::input::input_action::InputAction;
+use crate::game::input::input_state::InputEvent;
+use crate::game::input::replay::{InputReplay, ReplayHeader, ReplayPlayer, ReplayRecorder};
+use crate::game::level::map_file::MapFile;
+use crate::game::model::exploration::Exploration;
+use crate::game::profiler::{FrameProfiler, ProfiledSystem};
+use crate::game::save::save_game::SaveHeader;
+use crate::game::save::saved_components::SavedComponents;
+use crate::game::script::level_script::{LevelScript, ScriptEventHandler};
 
 mod graphics;
 mod game_state;
 mod ecs;
 mod event;
 mod model;
+mod script;
+mod level;
+mod launch_options;
+mod profiler;
+mod input;
+mod save;
+
+pub use crate::game::launch_options::{LaunchOptions, USAGE};
+
+const RESOLUTION_SCALE_STEP: f32 = 0.25;
 
 pub struct Game {
     graphics: Graphics,
     events: Events,
     world: World,
 
-    game_state: GameState
+    game_state: GameState,
+    profiler: Rc<RefCell<FrameProfiler>>,
+    clock: Rc<RefCell<SimulationClock>>,
+    exploration_path: PathBuf,
+    bindings_path: PathBuf,
+    save_slot: String,
+    is_replaying: bool,
+    target_fps: u32,
+    frame_index: u64,
+    // Frames left to render in headless mode
+    headless_frames: Option<u32>
 }
 
 impl Game {
 
-    pub fn initialize_game(sdl_context: Sdl) -> Game {
+    pub fn initialize_game(sdl_context: Sdl, mut options: LaunchOptions) -> Result<Game, String> {
         log::info!("Initializing game");
 
-        let mut game_state = GameState::new();
+        // Played replay starts the same level it was recorded on
+        let replay = Game::open_replay(&mut options)?;
+
+        // Loaded save starts the level it was saved on
+        let save_directory = PathBuf::from(env::var("SAVE_DIR").unwrap_or("saves".to_string()));
+        let mut game_state = GameState::new(save_directory);
+        Game::open_save(&mut options, &game_state)?;
+
+        let options = &options;
 
-        let mut graphics = Graphics::initialize_graphics(&sdl_context, game_state.repositories());
-        let event_pump = sdl_context.event_pump().unwrap();
+        log::info!("Random seed is {}", options.seed);
+
+        let mut graphics = Graphics::initialize_graphics(&sdl_context, options);
+
+        let bindings = Game::load_bindings(&options.bindings_path)?;
+        let mut events = Events::initialize_events(&sdl_context, bindings, replay, options)?;
+
+        // Mouse is not captured by the hidden headless window, and it is not needed to watch a replay
+        if options.headless.is_none() && options.replay_path.is_none() {
+            events.set_mouse_look_enabled(true);
+        }
 
-        let mut events = Events::new(event_pump);
+        log::info!("Loading map {}", options.map_path.display());
 
-        log::info!("Loading resources");
+        let mut map_file = level::load_map_file(&options.map_path)?;
+        level::register_map_resources(&mut map_file, game_state.repositories())?;
 
-        Game::load_resources(game_state.repositories());
+        log::info!("Map resources loaded");
 
-        log::info!("Resources loaded");
+        let exploration_path = Game::exploration_path(&options.map_path);
+        Game::load_exploration(&exploration_path, &graphics);
 
         log::info!("Initializing ECS world");
 
-        let world = Game::create_ecs_world(&mut graphics, &mut events, &mut game_state);
+        let profiler = Rc::new(RefCell::new(FrameProfiler::new(Duration::from_secs(1) / options.target_fps)));
+
+        if let Some(profile_csv_path) = &options.profile_csv_path {
+            profiler.borrow_mut().log_to_csv(profile_csv_path)
+                .map_err(|error| format!("unable to create profile CSV {}: {}", profile_csv_path.display(), error))?;
+
+            log::info!("Frame profiles are logged to {}", profile_csv_path.display());
+        }
+
+        let clock = Rc::new(RefCell::new(SimulationClock::new(SIMULATION_STEP)));
+        let world = Game::create_ecs_world(&mut graphics, &mut events, &mut game_state, &profiler, &clock, map_file, options)?;
 
         log::info!("ECS world has been initialized");
 
+        match (&options.headless, options.recording) {
+            (Some(headless), _) => graphics.frame_recorder().borrow_mut().start_at(headless.output.clone(), false),
+            (None, Some(_)) => graphics.frame_recorder().borrow_mut().start(),
+            (None, None) => {}
+        }
+
         log::info!("Game has been initialized");
 
-        Game {
+        Ok(Game {
             graphics,
             events,
             world,
 
-            game_state
-        }
+            game_state,
+            profiler,
+            clock,
+            exploration_path,
+            bindings_path: options.bindings_path.clone(),
+            save_slot: options.save_slot.clone(),
+            is_replaying: options.replay_path.is_some() || options.replay_recording_path.is_some(),
+            target_fps: options.target_fps,
+            frame_index: 0,
+            headless_frames: options.headless.as_ref().map(|headless| headless.frames)
+        })
     }
 
-    fn create_ecs_world(graphics: &mut Graphics, events: &mut Events, game_state: &mut GameState) -> World {
+    fn create_ecs_world(graphics: &mut Graphics, events: &mut Events, game_state: &mut GameState, profiler: &Rc<RefCell<FrameProfiler>>, clock: &Rc<RefCell<SimulationClock>>, map_file: MapFile, options: &LaunchOptions) -> Result<World, String> {
         let mut world = World::new();
-
-        // Registering components
-        world
-            .register_component::<PositionComponent>()
-            .register_component::<DirectionComponent>()
-            .register_component::<TilemapComponent>()
-            .register_component::<PlayerFlagComponent>()
-            .register_component::<LinemapComponent>();
+        let raycast_worlds = RaycastWorlds::new(game_state.repositories());
+
+        // Registering components, all of them are saved
+        let mut saved_components = SavedComponents::new();
+
+        saved_components
+            .register::<PositionComponent>()
+            .register::<DirectionComponent>()
+            .register::<PreviousPoseComponent>()
+            .register::<VelocityComponent>()
+            .register::<VerticalMotionComponent>()
+            .register::<TilemapComponent>()
+            .register::<PlayerFlagComponent>()
+            .register::<LinemapComponent>()
+            .register::<DoorComponent>()
+            .register::<KeysComponent>()
+            .register::<PushWallComponent>()
+            .register::<MovingSegmentComponent>()
+            .register::<TriggerComponent>()
+            .register::<HealthComponent>()
+            .register::<AmmoComponent>();
+
+        saved_components.register_in(&mut world);
 
         // Creating systems
-        world
+        let mut simulation_systems = FixedStepSystems::new(clock);
+
+        simulation_systems.add_system(ProfiledSystem::new("pose", PoseHistorySystem::new(), profiler));
+
+        // Replay records or replaces the gameplay input before it is read by the systems
+        if let Some(replay) = events.replay() {
+            simulation_systems.add_system(ProfiledSystem::new("replay", InputReplaySystem::new(replay, events.input()), profiler));
+        }
+
+        // Single "use" press activates only the nearest object, whichever system owns it
+        let use_action = Rc::new(RefCell::new(UseAction::new(events.input())));
+
+        simulation_systems
             // Input and events handling systems
-            .add_system(MovingSystem::new(&events.event_pump()))
-            .add_system(TilemapCollisionResolvingSystem::new(&game_state.repositories().tilemap_repository()))
+            .add_system(ProfiledSystem::new("moving", MovingSystem::new(events.input(), clock), profiler))
+            .add_system(ProfiledSystem::new("vertical", VerticalMovingSystem::new(events.input(), clock), profiler))
+            .add_system(ProfiledSystem::new("use", UseSystem::new(&use_action, game_state.repositories()), profiler))
+            .add_system(ProfiledSystem::new("doors", DoorSystem::new(&use_action, clock), profiler))
+            .add_system(ProfiledSystem::new("geometry", MovingGeometrySystem::new(&use_action, game_state.repositories(), clock), profiler));
+
+        // Level script timers are fired before the frame events are dispatched
+        let level_script = Game::load_level_script(&options.map_path.with_extension("rhai"), options.seed, events);
+
+        if let Some(level_script) = &level_script {
+            simulation_systems.add_system(ProfiledSystem::new("script", ScriptTimerSystem::new(level_script, events.dispatcher(), clock), profiler));
+        }
+
+        simulation_systems
+            .add_system(ProfiledSystem::new("triggers", TriggerSystem::new(&use_action, events.dispatcher(), game_state.repositories()), profiler))
+            .add_system(ProfiledSystem::new("collision", CollisionResolvingSystem::new(&raycast_worlds), profiler));
+
+        world
+            // Simulation is updated with fixed time steps, graphic is rendered once per frame
+            .add_system(simulation_systems)
+            .add_system(ProfiledSystem::new("save", SaveSystem::new(game_state.save_service(), saved_components, SaveHeader::from_options(options), game_state.repositories(), level_script.as_ref()), profiler))
+            .add_system(ProfiledSystem::new("rumble", RumbleSystem::new(events.gamepads()), profiler))
 
             // Graphic
-            .add_system(RenderingClearSystem::new(&graphics.renderer()))
-            .add_system(CameraPositionSyncSystem::new(graphics.rendering_state()))
-            .add_system(Tilemap3DRenderingSystem::new(&graphics.renderer(), &graphics.rendering_state(), game_state.repositories().tilemap_repository()))
-            .add_system(Linemap3DRenderingSystem::new(&graphics.renderer(), &graphics.rendering_state(), game_state.repositories().linemap_repository()))
-            .add_system(Tilemap2DRenderingSystem::new(&graphics.renderer(), &graphics.rendering_state(), game_state.repositories().tilemap_repository()))
-            .add_system(Linemap2DRenderingSystem::new(&graphics.renderer(), &graphics.rendering_state(), game_state.repositories().linemap_repository()))
-            .add_system(RenderingSwapBuffersSystem::new(&graphics.renderer()));
+            .add_system(ProfiledSystem::new("clear", RenderingClearSystem::new(&graphics.renderer()), profiler))
+            .add_system(ProfiledSystem::new("camera", CameraPositionSyncSystem::new(graphics.rendering_state(), clock), profiler))
+            .add_system(ProfiledSystem::new("render_3d", World3DRenderingSystem::new(&graphics.renderer(), &graphics.rendering_state(), &raycast_worlds, graphics.exploration(), profiler), profiler))
+            .add_system(ProfiledSystem::new("render_2d", World2DRenderingSystem::new(&graphics.renderer(), &graphics.rendering_state(), &raycast_worlds, graphics.exploration()), profiler))
+            .add_system(ProfiledSystem::new("hud", HudRenderingSystem::new(&graphics.renderer(), &graphics.rendering_state(), graphics.hud(), profiler), profiler))
+            .add_system(ProfiledSystem::new("swap", RenderingSwapBuffersSystem::new(&graphics.renderer(), graphics.screenshot_service(), graphics.frame_recorder()), profiler));
 
         // Creating entities
-        {
-            let player_entity_id = world.create_entity();
+        level::spawn_level(map_file, options, &mut world, &mut events.dispatcher().borrow_mut())?;
 
-            log::info!("Creating player entity with id {}", player_entity_id);
+        // Saved game replaces the spawned level state on the first frame
+        if let Some(load_slot) = &options.load_slot {
+            game_state.save_service().borrow_mut().request_load(load_slot);
+        }
+
+        Ok(world)
+    }
 
-            world.add_component_to_entity(player_entity_id, PositionComponent::new(vec2(3.0, 3.0)));
-            world.add_component_to_entity(player_entity_id, DirectionComponent::new(0.0f32));
-            world.add_component_to_entity(player_entity_id, PlayerFlagComponent::new());
+    // Level script lives next to the map file and has the same name
+    fn load_level_script(script_path: &Path, seed: u64, events: &Events) -> Option<Rc<RefCell<LevelScript>>> {
+        if !script_path.exists() {
+            log::info!("Level script {} is not found, level has no scripted logic", script_path.display());
+            return None;
         }
 
-        {
-            // let tilemap_entity_id = world.create_entity();
-            //
-            // log::info!("Creating tilemap entity with id {}", tilemap_entity_id);
-            //
-            // world.add_component_to_entity(tilemap_entity_id, TilemapComponent::new(1));
+        match LevelScript::load(script_path, seed) {
+            Err(error) => {
+                log::error!("Unable to load level script: {}", error);
+                None
+            }
+
+            Ok(level_script) => {
+                log::info!("Level script {} has been loaded", script_path.display());
+
+                let level_script = Rc::new(RefCell::new(level_script));
+                events.dispatcher().borrow_mut().register_global_handler(Box::new(ScriptEventHandler::new(&level_script)));
+
+                Some(level_script)
+            }
+        }
+    }
+
+    // Replay is either recorded or played, the played replay overrides the level options
+    fn open_replay(options: &mut LaunchOptions) -> Result<Option<InputReplay>, String> {
+        let replay = match (&options.replay_path, &options.replay_recording_path) {
+            (Some(replay_path), _) => {
+                let player = ReplayPlayer::load(replay_path)
+                    .map_err(|error| format!("unable to load replay {}: {}", replay_path.display(), error))?;
+
+                log::info!("Playing replay {}", replay_path.display());
+
+                player.header().apply_to(options);
+                InputReplay::Playing(player)
+            }
+
+            (None, Some(replay_recording_path)) => {
+                let recorder = ReplayRecorder::create(replay_recording_path, &ReplayHeader::from_options(options))
+                    .map_err(|error| format!("unable to create replay {}: {}", replay_recording_path.display(), error))?;
+
+                log::info!("Recording replay to {}", replay_recording_path.display());
+                InputReplay::Recording(recorder)
+            }
+
+            (None, None) => return Ok(None)
+        };
+
+        Ok(Some(replay))
+    }
+
+    // Header of the loaded save overrides the level options
+    fn open_save(options: &mut LaunchOptions, game_state: &GameState) -> Result<(), String> {
+        let Some(load_slot) = &options.load_slot else {
+            return Ok(());
+        };
+
+        let save_path = game_state.save_service().borrow().slot_path(load_slot);
+        let header = SaveHeader::read(&save_path)
+            .map_err(|error| format!("unable to load save {}: {}", save_path.display(), error))?;
+
+        log::info!("Loading saved game {}", save_path.display());
+
+        header.apply_to(options);
+        Ok(())
+    }
+
+    // Missing bindings file is not an error, default bindings are used and saved after the first rebinding
+    fn load_bindings(bindings_path: &Path) -> Result<Bindings, String> {
+        if !bindings_path.exists() {
+            log::info!("Bindings file {} is not found, default bindings are used", bindings_path.display());
+            return Ok(Bindings::default());
+        }
+
+        let bindings = Bindings::load(bindings_path)
+            .map_err(|error| format!("unable to load bindings {}: {}", bindings_path.display(), error))?;
+
+        log::info!("Input bindings have been loaded from {}", bindings_path.display());
+
+        Ok(bindings)
+    }
+
+    fn save_bindings(&self) {
+        match self.events.input().borrow().bindings().save(&self.bindings_path) {
+            Ok(()) => self.graphics.hud().borrow_mut().push_message(format!("Bindings saved to {}", self.bindings_path.display())),
+            Err(error) => log::error!("Unable to save bindings {}: {}", self.bindings_path.display(), error)
         }
+    }
 
-        {
-            let linemap_entity_id = world.create_entity();
+    // Explored walls are stored per map, next to screenshots and recordings by default
+    fn exploration_path(map_path: &Path) -> PathBuf {
+        let exploration_directory = env::var("EXPLORATION_DIR").unwrap_or("exploration".to_string());
+        let map_name = map_path.file_stem().and_then(|name| name.to_str()).unwrap_or("map");
 
-            log::info!("Creating linemap entity with id {}", linemap_entity_id);
+        PathBuf::from(exploration_directory).join(format!("{}.explored", map_name))
+    }
 
-            world.add_component_to_entity(linemap_entity_id, LinemapComponent::new(1));
+    fn load_exploration(exploration_path: &Path, graphics: &Graphics) {
+        if !exploration_path.exists() {
+            return;
         }
 
-        return world;
+        match Exploration::load(exploration_path) {
+            Ok(exploration) => {
+                log::info!("Loaded {} explored cells from {}", exploration.explored_cells_count(), exploration_path.display());
+                *graphics.exploration().borrow_mut() = exploration;
+            }
+
+            Err(error) => log::error!("Unable to load explored map {}: {}", exploration_path.display(), error)
+        }
     }
 
-    fn load_resources(repositories: &Repositories) {
-        // TODO Load resources from file
-
-        let mut tiles_repository = repositories.tiles_repository().borrow_mut();
-
-        tiles_repository
-            .register_resource(Rc::new(Tile::new(0, ObjectColor::WHITE.clone(), false)))
-            .register_resource(Rc::new(Tile::new(1, ObjectColor::GREEN.clone(), true)))
-            .register_resource(Rc::new(Tile::new(2, ObjectColor::RED.clone(), true)))
-            .register_resource(Rc::new(Tile::new(3, ObjectColor::YELLOW.clone(), true)));
-
-        let air = tiles_repository.get_resource(&0).unwrap();
-        let green = tiles_repository.get_resource(&1).unwrap();
-        let red = tiles_repository.get_resource(&2).unwrap();
-        let yellow = tiles_repository.get_resource(&3).unwrap();
-
-        let tiles = vec![
-            vec![green, green, green, green, green, green, green, green, green, green],
-            vec![green, air,   air,   air,   air,   air,   air,   air,   air,   green],
-            vec![green, air,   air,   air,   air,   air,   air,   air,   air,   green],
-            vec![green, air,   air,   air,   red,   yellow,red,   air,   air,   green],
-            vec![green, air,   air,   air,   yellow,air,   red,   air,   air,   green],
-            vec![green, air,   air,   air,   red,   yellow,red,   air,   air,   green],
-            vec![green, air,   air,   air,   air,   air,   air,   air,   air,   green],
-            vec![green, air,   air,   air,   air,   air,   air,   air,   air,   green],
-            vec![green, air,   air,   air,   air,   air,   air,   air,   air,   green],
-            vec![green, green, green, green, green, green, green, green, green, green]
-        ];
-
-        repositories.tilemap_repository().borrow_mut()
-            .register_resource(Rc::new(Tilemap::from_raw_tilemap(1, tiles)));
-
-        let mut linemap = Linemap::new(1);
-
-        linemap
-            .add_rect(ObjectColor::RED.clone(), Rect::new(0, 0, 10, 10))
-            .add_rect(ObjectColor::GREEN.clone(), Rect::new(1, 1, 1, 8))
-
-            // Chevron arrow
-            .add_line(ObjectColor::BLUE.clone(), Vec2::new(2.9f32, 8.2f32), Vec2::new(4.1f32, 8.2f32))
-            .add_line(ObjectColor::BLUE.clone(), Vec2::new(4.1f32, 8.2f32), Vec2::new(4.6f32, 7.3f32))
-            .add_line(ObjectColor::BLUE.clone(), Vec2::new(4.6f32, 7.3f32), Vec2::new(4.1f32, 6.5f32))
-            .add_line(ObjectColor::BLUE.clone(), Vec2::new(4.1f32, 6.5f32), Vec2::new(2.9f32, 6.5f32))
-            .add_line(ObjectColor::BLUE.clone(), Vec2::new(2.9f32, 6.5f32), Vec2::new(3.4f32, 7.3f32))
-            .add_line(ObjectColor::BLUE.clone(), Vec2::new(3.4f32, 7.3f32), Vec2::new(2.9f32, 8.2f32))
-
-            // Triangle
-            .add_line(ObjectColor::MAGENTA.clone(), Vec2::new(6.0f32, 9.5f32), Vec2::new(9.5f32, 9.5f32))
-            .add_line(ObjectColor::CYAN.clone(), Vec2::new(9.5f32, 9.5f32), Vec2::new(7.5f32, 6.5f32))
-            .add_line(ObjectColor::YELLOW.clone(), Vec2::new(7.5f32, 6.5f32), Vec2::new(6.0f32, 9.5f32))
-
-            // Four-point star
-            .add_line(ObjectColor::RED.clone(), Vec2::new(4.8f32, 5.2f32), Vec2::new(5.1f32, 3.6f32))
-            .add_line(ObjectColor::RED.clone(), Vec2::new(5.1f32, 3.6f32), Vec2::new(4.4f32, 1.9f32))
-            .add_line(ObjectColor::RED.clone(), Vec2::new(4.4f32, 1.9f32), Vec2::new(5.8f32, 3.1f32))
-            .add_line(ObjectColor::RED.clone(), Vec2::new(5.8f32, 3.1f32), Vec2::new(7.5f32, 3.3f32))
-            .add_line(ObjectColor::RED.clone(), Vec2::new(7.5f32, 3.3f32), Vec2::new(5.9f32, 3.9f32))
-            .add_line(ObjectColor::RED.clone(), Vec2::new(5.9f32, 3.9f32), Vec2::new(4.8f32, 5.2f32));
-
-        repositories.linemap_repository().borrow_mut().register_resource(Rc::new(linemap));
+    fn save_exploration(&self) {
+        match self.graphics.exploration().borrow().save(&self.exploration_path) {
+            Ok(()) => log::info!("Explored map has been saved to {}", self.exploration_path.display()),
+            Err(error) => log::error!("Unable to save explored map {}: {}", self.exploration_path.display(), error)
+        }
     }
 
-    pub fn run_game_loop(&mut self) {
+    // Fails when the played replay has diverged from the recording
+    pub fn run_game_loop(&mut self) -> Result<(), String> {
+        let time_per_frame = Duration::from_secs(1) / self.target_fps;
+        let mut last_frame_start_time = time::Instant::now();
+
         'main_game_loop: loop {
             let frame_start_time = time::Instant::now();
 
+            // Headless frames are simulated with the target frame time, so batch renders don't depend on the machine speed
+            let elapsed_time = match self.headless_frames {
+                Some(_) => time_per_frame,
+                None => frame_start_time - last_frame_start_time
+            };
+
+            last_frame_start_time = frame_start_time;
+            self.clock.borrow_mut().advance(elapsed_time);
+
+            let simulated_time = {
+                let clock = self.clock.borrow();
+                clock.delta_time() * clock.steps()
+            };
+
+            self.graphics.hud().borrow_mut().advance(simulated_time);
+
             self.handle_events();
 
             self.world.update();
 
+            self.handle_game_events();
+
+            if self.events.replay().is_some_and(|replay| replay.borrow().is_finished()) {
+                self.game_state.set_is_game_running(false);
+            }
+
+            self.profiler.borrow_mut().finish_frame(frame_start_time.elapsed());
+            self.frame_index += 1;
+
             if !self.game_state.is_game_running() {
                 break 'main_game_loop;
             }
 
-            let frame_end_time = time::Instant::now();
+            // Headless frames are rendered as fast as possible
+            if let Some(frames_left) = self.headless_frames.as_mut() {
+                *frames_left -= 1;
 
-            // FPS stabilisation
+                if *frames_left == 0 {
+                    log::info!("All headless frames have been rendered");
+                    break 'main_game_loop;
+                }
 
-            const TARGET_FPS: i32 = 30;
-            const TIME_PER_FRAME_NANOS: i32 = 1_000_000_000 / TARGET_FPS;
+                continue;
+            }
+
+            // FPS stabilisation
 
-            let frame_diff_nanos = (frame_end_time - frame_start_time).as_nanos() as i32;
-            let wait_time = TIME_PER_FRAME_NANOS - frame_diff_nanos;
+            let frame_time = frame_start_time.elapsed();
 
-            if wait_time > 0 {
-                ::std::thread::sleep(Duration::new(0, wait_time as u32));
+            if frame_time < time_per_frame {
+                ::std::thread::sleep(time_per_frame - frame_time);
             }
         }
+
+        // Batch renders and replays don't change the explored map of real playthroughs
+        if self.headless_frames.is_none() && !self.is_replaying {
+            self.save_exploration();
+        }
+
+        match self.events.replay() {
+            Some(replay) => replay.borrow_mut().finish(),
+            None => Ok(())
+        }
+    }
+
+    fn handle_game_events(&mut self) {
+        let mut dispatcher = self.events.dispatcher().borrow_mut();
+
+        for message in dispatcher.take_messages() {
+            log::info!("Message: {}", message);
+            self.graphics.hud().borrow_mut().push_message(message);
+        }
+
+        for message in self.game_state.save_service().borrow_mut().take_messages() {
+            self.graphics.hud().borrow_mut().push_message(message);
+        }
+
+        if dispatcher.is_level_finished() {
+            log::info!("Level has been finished");
+            self.game_state.set_is_game_running(false);
+        }
     }
 
     fn handle_events(&mut self) {
-        for event in self.events.event_pump().borrow_mut().poll_iter() {
-            use sdl2::event::Event;
+        let events = self.events.poll_events(self.frame_index);
 
-            match event {
-                Event::Quit {..} | Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
-                self.game_state.set_is_game_running(false)
-            },
+        for event in events {
+            if let Event::Quit { .. } = event {
+                self.game_state.set_is_game_running(false);
+            }
 
-            _ => {}
+            if let Some(message) = self.events.gamepads().borrow_mut().handle_event(&event) {
+                self.graphics.hud().borrow_mut().push_message(message);
             }
+
+            let input_events = self.events.input().borrow_mut().handle_event(&event);
+
+            for input_event in input_events {
+                match input_event {
+                    InputEvent::Pressed(action) => self.handle_action(action),
+
+                    InputEvent::Rebound(action, source) => {
+                        self.graphics.hud().borrow_mut().push_message(format!("{} bound to {}", action.name(), source.name()));
+                        self.continue_rebinding();
+                    }
+
+                    InputEvent::RebindingSkipped(action) => {
+                        self.graphics.hud().borrow_mut().push_message(format!("{} is not changed", action.name()));
+                        self.continue_rebinding();
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_action(&mut self, action: InputAction) {
+        match action {
+            InputAction::Quit => self.game_state.set_is_game_running(false),
+            InputAction::Screenshot => self.graphics.screenshot_service().borrow_mut().request(),
+            InputAction::ToggleRecording => self.graphics.frame_recorder().borrow_mut().toggle(),
+            InputAction::ToggleFullscreen => self.graphics.toggle_fullscreen(),
+            InputAction::ToggleMouseLook => {
+                let is_mouse_look_enabled = !self.events.input().borrow().is_mouse_look_enabled();
+                self.events.set_mouse_look_enabled(is_mouse_look_enabled);
+            }
+
+            InputAction::View3D => self.graphics.set_view_mode(ViewMode::World3D),
+            InputAction::View2D => self.graphics.set_view_mode(ViewMode::World2D),
+            InputAction::ViewMinimap => self.graphics.set_view_mode(ViewMode::Minimap),
+            InputAction::ViewSplit => self.graphics.set_view_mode(ViewMode::Split),
+            InputAction::NextView => self.graphics.switch_view_mode(),
+
+            InputAction::ToggleMap => self.graphics.toggle_automap(),
+            InputAction::ToggleMinimapRotation => self.graphics.toggle_minimap_rotation(),
+            InputAction::SwitchMinimapShape => self.graphics.switch_minimap_shape(),
+            InputAction::ToggleDebugInfo => self.graphics.toggle_debug_info(),
+            InputAction::ToggleProfiler => self.graphics.toggle_profiler_overlay(),
+
+            InputAction::DecreaseResolution => self.graphics.change_resolution_scale(-RESOLUTION_SCALE_STEP),
+            InputAction::IncreaseResolution => self.graphics.change_resolution_scale(RESOLUTION_SCALE_STEP),
+
+            InputAction::RebindControls => {
+                self.events.input().borrow_mut().start_rebinding(&InputAction::GAMEPLAY);
+                self.continue_rebinding();
+            }
+
+            InputAction::QuickSave => self.game_state.save_service().borrow_mut().request_save(&self.save_slot),
+
+            // Loaded state is not a part of the replay, so it would diverge
+            InputAction::QuickLoad if self.is_replaying => self.graphics.hud().borrow_mut().push_message("Loading is disabled with replays".to_string()),
+            InputAction::QuickLoad => self.game_state.save_service().borrow_mut().request_load(&self.save_slot),
+
+            // Gameplay actions are read by the systems from the input state
+            _ => {}
+        }
+    }
+
+    // Prompts for the next rebound action, bindings are saved when all actions are rebound
+    fn continue_rebinding(&mut self) {
+        let rebinding_action = self.events.input().borrow().rebinding_action();
+
+        match rebinding_action {
+            Some(action) => self.graphics.hud().borrow_mut().push_message(format!("Press input for {}, Esc to skip", action.name())),
+            None => self.save_bindings()
         }
     }
 }
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use glm::Vec2;
+
+use crate::game::model::map_painter::MapPainter;
+use crate::game::model::ray::{Hit, Ray};
+use crate::game::model::raycast_world::RaycastWorld;
+
+// Several maps combined into a single level, e.g. tilemap with linemap overlay for diagonal walls and details
+pub struct CompositeWorld {
+    layers: Vec<Arc<dyn RaycastWorld>>
+}
+
+impl CompositeWorld {
+    pub fn new(layers: Vec<Arc<dyn RaycastWorld>>) -> Self {
+        assert!(!layers.is_empty(), "Composite world must have at least one layer");
+        Self { layers }
+    }
+
+}
+
+impl RaycastWorld for CompositeWorld {
+    // Nearest wall hit across all layers
+    fn cast_ray(&self, start_position: Vec2, ray_angle: f32, maximal_distance: f32) -> (Ray, Hit) {
+        let mut nearest: Option<(Ray, Hit)> = None;
+        let mut intersection_tests = 0;
+
+        for layer in &self.layers {
+            let (ray, hit) = layer.cast_ray(start_position, ray_angle, maximal_distance);
+            intersection_tests += ray.intersection_tests();
+
+            let is_nearer = match (&nearest, &hit) {
+                (None, _) => true,
+                (Some(_), Hit::None) => false,
+                (Some((_, Hit::None)), Hit::Wall { .. }) => true,
+                (Some((nearest_ray, Hit::Wall { .. })), Hit::Wall { .. }) => ray.distance() < nearest_ray.distance()
+            };
+
+            if is_nearer {
+                nearest = Some((ray, hit));
+            }
+        }
+
+        let (mut ray, hit) = nearest.expect("Composite world must have at least one layer");
+        ray.set_intersection_tests(intersection_tests);
+
+        (ray, hit)
+    }
+
+    fn is_colliding(&self, position: Vec2, radius: f32) -> bool {
+        self.layers.iter().any(|layer| layer.is_colliding(position, radius))
+    }
+
+    fn bounds(&self) -> Vec2 {
+        self.layers.iter()
+            .map(|layer| layer.bounds())
+            .fold(Vec2::new(0.0, 0.0), |bounds, layer_bounds| Vec2::new(bounds.x.max(layer_bounds.x), bounds.y.max(layer_bounds.y)))
+    }
+
+    // Layers are drawn in order, so overlays are on top of the base map
+    fn render_2d(&self, painter: &mut dyn MapPainter) {
+        for layer in &self.layers {
+            layer.render_2d(painter);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use glm::Vec2;
+
+use crate::game::model::linemap::Linemap;
+use crate::game::model::map_painter::MapPainter;
+use crate::game::model::object_color::ObjectColor;
+use crate::game::model::ray::{Hit, Ray};
+use crate::game::model::raycast_world::RaycastWorld;
+
+// Snapshot of the level doors with their current open offsets. Rebuilt each frame from door components
+pub struct DoorsWorld {
+    linemap: Linemap,
+    // Same order as the linemap lines
+    is_blocking: Vec<bool>
+}
+
+impl DoorsWorld {
+    pub fn new() -> Self {
+        Self { linemap: Linemap::new(0), is_blocking: vec![] }
+    }
+
+    pub fn add_door(&mut self, color: ObjectColor, segment: (Vec2, Vec2), is_blocking: bool) -> &mut DoorsWorld {
+        self.linemap.add_line(color, segment.0, segment.1);
+        self.is_blocking.push(is_blocking);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.is_blocking.is_empty()
+    }
+}
+
+impl RaycastWorld for DoorsWorld {
+    fn cast_ray(&self, start_position: Vec2, ray_angle: f32, maximal_distance: f32) -> (Ray, Hit) {
+        self.linemap.cast_ray(start_position, ray_angle, maximal_distance)
+    }
+
+    // Opened doors are drawn, but can be passed through
+    fn is_colliding(&self, position: Vec2, radius: f32) -> bool {
+        self.linemap.lines().iter()
+            .zip(&self.is_blocking)
+            .any(|(line, is_blocking)| *is_blocking && line.distance_to(position) <= radius)
+    }
+
+    fn bounds(&self) -> Vec2 {
+        self.linemap.bounds()
+    }
+
+    // Only door lines, without background, so doors do not overdraw other layers
+    fn render_2d(&self, painter: &mut dyn MapPainter) {
+        self.linemap.render_2d(painter)
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use glm::Vec2;
+
+use crate::game::model::ray::{Hit, Ray};
+
+// Distance from the hit point to both sides of the wall, so walls lying on cell borders are explored in both cells
+const HIT_CELL_OFFSET: f32 = 0.01;
+
+// Map cells containing walls which were hit by the camera rays
+#[derive(Default)]
+pub struct Exploration {
+    explored_cells: HashSet<(i32, i32)>
+}
+
+impl Exploration {
+    pub fn load(path: &Path) -> Result<Exploration, String> {
+        let source = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        let mut exploration = Exploration::default();
+
+        for (line_index, line) in source.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let cell = line.split_once(' ')
+                .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+                .ok_or_else(|| format!("line {}: explored cell should be given as \"X Y\"", line_index + 1))?;
+
+            exploration.explored_cells.insert(cell);
+        }
+
+        Ok(exploration)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+        }
+
+        let mut cells: Vec<&(i32, i32)> = self.explored_cells.iter().collect();
+        cells.sort();
+
+        let source: String = cells.iter()
+            .map(|(x, y)| format!("{} {}\n", x, y))
+            .collect();
+
+        fs::write(path, source).map_err(|error| error.to_string())
+    }
+
+    pub fn explored_cells_count(&self) -> usize {
+        self.explored_cells.len()
+    }
+
+    pub fn record_hits<'h>(&mut self, hits: impl IntoIterator<Item = (&'h Ray, &'h Hit)>) {
+        for (ray, hit) in hits {
+            if let Hit::Wall { .. } = hit {
+                let direction = Vec2::new(ray.direction_angle().cos(), ray.direction_angle().sin());
+
+                self.explored_cells.insert(cell_of(ray.end_position() + direction * HIT_CELL_OFFSET));
+                self.explored_cells.insert(cell_of(ray.end_position() - direction * HIT_CELL_OFFSET));
+            }
+        }
+    }
+
+    pub fn is_explored(&self, position: Vec2) -> bool {
+        self.explored_cells.contains(&cell_of(position))
+    }
+}
+
+fn cell_of(position: Vec2) -> (i32, i32) {
+    (position.x.floor() as i32, position.y.floor() as i32)
+}
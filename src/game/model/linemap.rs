@@ -2,12 +2,16 @@ use glm::{abs, Vec2};
 use num_traits::{zero, Zero};
 use sdl2::rect::{Point, Rect};
 
+use crate::game::model::map_painter::MapPainter;
 use crate::game::model::object_color::ObjectColor;
+use crate::game::model::ray::{cast_ray_linemap, Hit, Ray};
+use crate::game::model::raycast_world::RaycastWorld;
 use crate::game::model::repository::Resource;
 use crate::game::model::ResourceId;
 
 const EPSILON: f32 = 0.005;
 
+#[derive(Clone)]
 pub struct Line {
     color: ObjectColor,
     from: Vec2,
@@ -59,6 +63,11 @@ impl Line {
         return contains_in_range(self.from.y, self.to.y, y);
     }
 
+    // Distance from the point to the nearest point of the segment
+    pub fn distance_to(&self, point: Vec2) -> f32 {
+        distance_to_segment(self.from, self.to, point)
+    }
+
     pub fn find_intersection(first: &Line, second: &Line) -> Option<Vec2> {
 
         let intersection = if is_parallel_y(first) {
@@ -83,6 +92,7 @@ impl Line {
     }
 }
 
+#[derive(Clone)]
 pub struct Linemap {
     id: ResourceId,
     lines: Vec<Line>,
@@ -115,6 +125,29 @@ impl Linemap {
         return self;
     }
 
+    // Moves existing line. Line coefficients and map sizes are recalculated, so casting and collision see the new position
+    pub fn set_line(&mut self, index: usize, from: Vec2, to: Vec2) {
+        let line = match self.lines.get_mut(index) {
+            None => return,
+            Some(line) => line
+        };
+
+        *line = Line::new(line.color, from, to);
+
+        self.sizes = self.lines.iter().fold(zero(), |sizes: Vec2, line| {
+            Vec2::new(
+                sizes.x.max(line.from.x).max(line.to.x),
+                sizes.y.max(line.from.y).max(line.to.y)
+            )
+        });
+    }
+
+    pub fn set_line_color(&mut self, index: usize, color: ObjectColor) {
+        if let Some(line) = self.lines.get_mut(index) {
+            line.color = color;
+        }
+    }
+
     pub fn add_rect(&mut self, color: ObjectColor, rect: Rect) -> &mut Linemap {
         self.add_line(color.clone(), point_to_vec2(rect.top_left()), point_to_vec2(rect.top_right()));
         self.add_line(color.clone(), point_to_vec2(rect.top_right()), point_to_vec2(rect.bottom_right()));
@@ -131,6 +164,39 @@ impl Resource for Linemap {
     }
 }
 
+impl RaycastWorld for Linemap {
+    fn cast_ray(&self, start_position: Vec2, ray_angle: f32, maximal_distance: f32) -> (Ray, Hit) {
+        cast_ray_linemap(self, start_position, ray_angle, maximal_distance)
+    }
+
+    fn is_colliding(&self, position: Vec2, radius: f32) -> bool {
+        self.lines.iter().any(|line| line.distance_to(position) <= radius)
+    }
+
+    fn bounds(&self) -> Vec2 {
+        *self.sizes()
+    }
+
+    // Background is filled by the map view, so linemap layers can be drawn over other layers
+    fn render_2d(&self, painter: &mut dyn MapPainter) {
+        for line in &self.lines {
+            painter.paint_line(*line.from(), *line.to(), line.color())
+        }
+    }
+}
+
+pub fn distance_to_segment(from: Vec2, to: Vec2, point: Vec2) -> f32 {
+    let segment = to - from;
+    let segment_length_squared = glm::dot(segment, segment);
+
+    if segment_length_squared < EPSILON * EPSILON {
+        return glm::distance(from, point);
+    }
+
+    let projection = (glm::dot(point - from, segment) / segment_length_squared).clamp(0.0, 1.0);
+    glm::distance(from + segment * projection, point)
+}
+
 fn point_to_vec2(point: Point) -> Vec2 {
     Vec2::new(point.x as f32, point.y as f32)
 }
@@ -0,0 +1,11 @@
+use glm::Vec2;
+
+use crate::game::model::object_color::ObjectColor;
+
+// Target of the top-down map drawing, implemented by the graphics. Positions are given in map units
+pub trait MapPainter {
+    // Tile sized square with top left corner at given position
+    fn paint_tile(&mut self, position: Vec2, color: &ObjectColor);
+
+    fn paint_line(&mut self, from: Vec2, to: Vec2, color: &ObjectColor);
+}
@@ -3,5 +3,11 @@ pub mod tilemap;
 pub mod repository;
 pub mod linemap;
 pub mod object_color;
+pub mod ray;
+pub mod raycast_world;
+pub mod map_painter;
+pub mod composite_world;
+pub mod doors_world;
+pub mod exploration;
 
 pub type ResourceId = u32;
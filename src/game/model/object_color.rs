@@ -48,4 +48,29 @@ impl ObjectColor {
     pub const WHITE: ObjectColor = ObjectColor::COLOR {
         color: &Color::WHITE
     };
-}
\ No newline at end of file
+
+    pub fn from_name(name: &str) -> Option<ObjectColor> {
+        match name {
+            "black" => Some(ObjectColor::BLACK),
+            "gray" => Some(ObjectColor::GRAY),
+            "red" => Some(ObjectColor::RED),
+            "green" => Some(ObjectColor::GREEN),
+            "blue" => Some(ObjectColor::BLUE),
+            "magenta" => Some(ObjectColor::MAGENTA),
+            "cyan" => Some(ObjectColor::CYAN),
+            "yellow" => Some(ObjectColor::YELLOW),
+            "white" => Some(ObjectColor::WHITE),
+            _ => None
+        }
+    }
+
+    // Textures have no names
+    pub fn name(&self) -> Option<&'static str> {
+        let ObjectColor::COLOR { color } = self else {
+            return None;
+        };
+
+        ["black", "gray", "red", "green", "blue", "magenta", "cyan", "yellow", "white"].into_iter()
+            .find(|name| matches!(ObjectColor::from_name(name), Some(ObjectColor::COLOR { color: named_color }) if named_color == *color))
+    }
+}
@@ -0,0 +1,153 @@
+use glm::{uvec2, vec2, UVec2, Vec2};
+
+use crate::game::model::linemap::{Line, Linemap};
+use crate::game::model::object_color::ObjectColor;
+use crate::game::model::tilemap::Tilemap;
+
+pub enum Hit {
+    None,
+    Wall {
+        color: ObjectColor,
+        is_collision_enabled: bool
+    }
+}
+
+pub struct Ray {
+    start_position: Vec2,
+    end_position: Vec2,
+    direction_angle: f32,
+    distance: f32,
+    maximal_distance: f32,
+    // Ray and wall segment intersection tests done while casting, reported by the profiler
+    intersection_tests: u32
+}
+
+impl Ray {
+    pub fn new(start_position: Vec2, end_position: Vec2, direction_angle: f32, maximal_distance: f32) -> Self {
+        Self { start_position, end_position, direction_angle, distance: 0.0, maximal_distance, intersection_tests: 0 }
+    }
+    pub fn start_position(&self) -> Vec2 {
+        self.start_position
+    }
+    pub fn end_position(&self) -> Vec2 {
+        self.end_position
+    }
+    pub fn direction_angle(&self) -> f32 {
+        self.direction_angle
+    }
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+    pub fn maximal_distance(&self) -> f32 {
+        self.maximal_distance
+    }
+    pub fn intersection_tests(&self) -> u32 {
+        self.intersection_tests
+    }
+    pub fn set_intersection_tests(&mut self, intersection_tests: u32) {
+        self.intersection_tests = intersection_tests;
+    }
+}
+
+pub fn cast_ray_tilemap(tilemap: &Tilemap, start_position: Vec2, ray_angle: f32, maximal_distance: f32) -> (Ray, Hit) {
+    // Todo Use binary algorithm with dynamic step size
+
+    const STEP_SIZE: f32 = 0.05;
+
+    let mut ray = Ray::new(start_position, start_position.clone(), ray_angle, maximal_distance);
+
+    let ray_end_position = vec2(
+        start_position.x + maximal_distance * ray_angle.cos(),
+        start_position.y + maximal_distance * ray_angle.sin()
+    );
+    let ray_line = Line::new(ObjectColor::WHITE, start_position, ray_end_position);
+    let mut checked_shaped_tile: Option<UVec2> = None;
+
+    while ray.distance < maximal_distance {
+        ray.end_position.x += STEP_SIZE * ray_angle.cos();
+        ray.end_position.y += STEP_SIZE * ray_angle.sin();
+
+        let current_tile = uvec2(ray.end_position.x as u32, ray.end_position.y as u32);
+
+        if current_tile.x >= tilemap.sizes().x || current_tile.y >= tilemap.sizes().y {
+            break;
+        }
+
+        if let Some(moving_tile) = tilemap.moving_tile_at(ray.end_position) {
+            let hit = Hit::Wall {
+                color: *moving_tile.tile().color(),
+                is_collision_enabled: true
+            };
+
+            return (ray, hit)
+        }
+
+        let placed_tile = tilemap.get_tile(current_tile);
+
+        if placed_tile.is_some_and(|tile| tile.tile().is_collision_enabled() ) {
+            let placed_tile = placed_tile.unwrap();
+            let hit = Hit::Wall {
+                color: placed_tile.tile().color().clone(),
+                is_collision_enabled: placed_tile.tile().is_collision_enabled()
+            };
+
+            match placed_tile.tile().shape().segment() {
+                None => return (ray, hit),
+
+                // Tiles occupying part of the cell are checked once, when ray enters the cell
+                Some(segment) if checked_shaped_tile != Some(current_tile) => {
+                    checked_shaped_tile = Some(current_tile);
+
+                    let tile_origin = vec2(current_tile.x as f32, current_tile.y as f32);
+                    let wall_line = Line::new(*placed_tile.tile().color(), tile_origin + segment.0, tile_origin + segment.1);
+
+                    ray.intersection_tests += 1;
+
+                    if let Some(intersection) = Line::find_intersection(&wall_line, &ray_line) {
+                        ray.end_position = intersection;
+                        ray.distance = glm::distance(start_position, intersection);
+                        return (ray, hit)
+                    }
+                }
+
+                Some(_) => {}
+            }
+        }
+
+        ray.distance += STEP_SIZE;
+    }
+
+    return (ray, Hit::None);
+}
+
+pub fn cast_ray_linemap(linemap: &Linemap, start_position: Vec2, ray_angle: f32, maximal_distance: f32) -> (Ray, Hit) {
+    let mut ray = Ray::new(start_position, start_position.clone(), ray_angle, maximal_distance);
+    ray.distance = maximal_distance;
+
+    ray.end_position.x += maximal_distance * ray_angle.cos();
+    ray.end_position.y += maximal_distance * ray_angle.sin();
+
+    let ray_line = Line::new(ObjectColor::WHITE, ray.start_position.clone(), ray.end_position.clone());
+    let mut hit = Hit::None;
+
+    ray.intersection_tests = linemap.lines().len() as u32;
+
+    for line in linemap.lines() {
+        match Line::find_intersection(line, &ray_line) {
+            None => {}
+            Some(intersection) => {
+                let intersection_distance = glm::distance(ray.start_position, intersection);
+                if intersection_distance < ray.distance {
+                    ray.end_position = intersection;
+                    ray.distance = intersection_distance;
+                    hit = Hit::Wall {
+                        color: line.color().clone(),
+                        is_collision_enabled: true
+                    }
+                }
+            }
+        };
+    }
+
+    return (ray, hit)
+}
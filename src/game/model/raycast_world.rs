@@ -0,0 +1,19 @@
+use glm::Vec2;
+
+use crate::game::model::map_painter::MapPainter;
+use crate::game::model::ray::{Hit, Ray};
+
+// Common interface of all map types. Rendering and collision systems work with maps only through it
+pub trait RaycastWorld: Send + Sync {
+    // Casts ray from start position until the first wall or maximal distance
+    fn cast_ray(&self, start_position: Vec2, ray_angle: f32, maximal_distance: f32) -> (Ray, Hit);
+
+    // Checks if circle with given center and radius intersects any solid geometry
+    fn is_colliding(&self, position: Vec2, radius: f32) -> bool;
+
+    // Sizes of the map, starting from the origin
+    fn bounds(&self) -> Vec2;
+
+    // Draws map geometry for the top-down views
+    fn render_2d(&self, painter: &mut dyn MapPainter);
+}
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::sync::Arc;
 use crate::game::model::ResourceId;
 
 pub trait Resource {
@@ -7,7 +7,7 @@ pub trait Resource {
 }
 
 pub struct Repository<T : Resource> {
-    data: HashMap<ResourceId, Rc<T>>
+    data: HashMap<ResourceId, Arc<T>>
 }
 
 impl<T : Resource> Repository<T> {
@@ -15,11 +15,20 @@ impl<T : Resource> Repository<T> {
         Self { data: HashMap::with_capacity(32) }
     }
 
-    pub fn get_resource(&self, id: &ResourceId) -> Option<&Rc<T>> {
+    pub fn get_resource(&self, id: &ResourceId) -> Option<&Arc<T>> {
         self.data.get(&id)
     }
 
-    pub fn register_resource(&mut self, resource: Rc<T>) -> &mut Self {
+    // Resource is copied only if it is shared, so snapshots taken by other threads keep the previous version
+    pub fn get_resource_mut(&mut self, id: &ResourceId) -> Option<&mut T> where T: Clone {
+        self.data.get_mut(id).map(Arc::make_mut)
+    }
+
+    pub fn resources(&self) -> impl Iterator<Item = &Arc<T>> {
+        self.data.values()
+    }
+
+    pub fn register_resource(&mut self, resource: Arc<T>) -> &mut Self {
         self.data.insert(resource.id(), resource);
         self
     }
@@ -1,11 +1,132 @@
+use glm::{vec2, Vec2};
+
 use crate::game::model::object_color::ObjectColor;
 use crate::game::model::repository::Resource;
 use crate::game::model::ResourceId;
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TileEdge {
+    North,
+    South,
+    West,
+    East
+}
+
+impl TileEdge {
+    // Step to the neighbour cell behind the edge
+    pub fn direction(&self) -> Vec2 {
+        match self {
+            TileEdge::North => vec2(0.0, -1.0),
+            TileEdge::South => vec2(0.0, 1.0),
+            TileEdge::West => vec2(-1.0, 0.0),
+            TileEdge::East => vec2(1.0, 0.0)
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<TileEdge> {
+        match name {
+            "north" => Some(TileEdge::North),
+            "south" => Some(TileEdge::South),
+            "west" => Some(TileEdge::West),
+            "east" => Some(TileEdge::East),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TileEdge::North => "north",
+            TileEdge::South => "south",
+            TileEdge::West => "west",
+            TileEdge::East => "east"
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TileAxis {
+    Horizontal,
+    Vertical
+}
+
+impl TileAxis {
+    pub fn from_name(name: &str) -> Option<TileAxis> {
+        match name {
+            "horizontal" => Some(TileAxis::Horizontal),
+            "vertical" => Some(TileAxis::Vertical),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TileAxis::Horizontal => "horizontal",
+            TileAxis::Vertical => "vertical"
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TileDiagonal {
+    // From top left to bottom right corner
+    Falling,
+    // From bottom left to top right corner
+    Rising
+}
+
+// Geometry of the tile inside its cell
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TileShape {
+    // Whole cell is occupied
+    Solid,
+
+    // Thin wall along one of the cell edges
+    EdgeWall {
+        edge: TileEdge
+    },
+
+    // Thin wall through the cell center
+    CenteredWall {
+        axis: TileAxis
+    },
+
+    // Thin wall from corner to the opposite corner
+    Diagonal {
+        diagonal: TileDiagonal
+    }
+}
+
+impl TileShape {
+    // Wall segment in cell coordinates ([0, 1] on both axes). Solid tiles have no segment
+    pub fn segment(&self) -> Option<(Vec2, Vec2)> {
+        match self {
+            TileShape::Solid => None,
+
+            TileShape::EdgeWall { edge } => Some(match edge {
+                TileEdge::North => (vec2(0.0, 0.0), vec2(1.0, 0.0)),
+                TileEdge::South => (vec2(0.0, 1.0), vec2(1.0, 1.0)),
+                TileEdge::West => (vec2(0.0, 0.0), vec2(0.0, 1.0)),
+                TileEdge::East => (vec2(1.0, 0.0), vec2(1.0, 1.0))
+            }),
+
+            TileShape::CenteredWall { axis } => Some(match axis {
+                TileAxis::Horizontal => (vec2(0.0, 0.5), vec2(1.0, 0.5)),
+                TileAxis::Vertical => (vec2(0.5, 0.0), vec2(0.5, 1.0))
+            }),
+
+            TileShape::Diagonal { diagonal } => Some(match diagonal {
+                TileDiagonal::Falling => (vec2(0.0, 0.0), vec2(1.0, 1.0)),
+                TileDiagonal::Rising => (vec2(0.0, 1.0), vec2(1.0, 0.0))
+            })
+        }
+    }
+}
+
 pub struct Tile {
     id: ResourceId,
     color: ObjectColor,
-    is_collision_enabled: bool
+    is_collision_enabled: bool,
+    shape: TileShape
 }
 
 impl Tile {
@@ -13,7 +134,18 @@ impl Tile {
         Self {
             id,
             color,
-            is_collision_enabled
+            is_collision_enabled,
+            shape: TileShape::Solid
+        }
+    }
+
+    // Tile occupying only part of the cell
+    pub fn with_shape(id: ResourceId, color: ObjectColor, shape: TileShape) -> Self {
+        Self {
+            id,
+            color,
+            is_collision_enabled: true,
+            shape
         }
     }
 
@@ -24,6 +156,10 @@ impl Tile {
     pub fn is_collision_enabled(&self) -> bool {
         self.is_collision_enabled
     }
+
+    pub fn shape(&self) -> &TileShape {
+        &self.shape
+    }
 }
 
 impl Resource for Tile {
@@ -1,13 +1,19 @@
-use std::rc::Rc;
+use std::collections::HashMap;
+use std::sync::Arc;
 
-use glm::{UVec2, uvec2};
+use glm::{UVec2, uvec2, vec2, Vec2};
 
+use crate::game::model::linemap::distance_to_segment;
+use crate::game::model::map_painter::MapPainter;
+use crate::game::model::object_color::ObjectColor;
+use crate::game::model::ray::{cast_ray_tilemap, Hit, Ray};
+use crate::game::model::raycast_world::RaycastWorld;
 use crate::game::model::repository::Resource;
 use crate::game::model::ResourceId;
 use crate::game::model::tile::Tile;
 
 pub struct PlacedTile {
-    tile: Rc<Tile>
+    tile: Arc<Tile>
 }
 
 impl Clone for PlacedTile {
@@ -17,34 +23,67 @@ impl Clone for PlacedTile {
 }
 
 impl PlacedTile {
-    pub fn new(tile: &Rc<Tile>) -> Self {
+    pub fn new(tile: &Arc<Tile>) -> Self {
         Self { tile: tile.clone() }
     }
 
 
-    pub fn tile(&self) -> &Rc<Tile> {
+    pub fn tile(&self) -> &Arc<Tile> {
         &self.tile
     }
 }
 
+// Tile moving between cells, e.g. push-wall. Occupies the whole tile sized square from its position
+#[derive(Clone)]
+pub struct MovingTile {
+    tile: Arc<Tile>,
+    position: Vec2
+}
+
+impl MovingTile {
+    pub fn tile(&self) -> &Arc<Tile> {
+        &self.tile
+    }
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.position.x <= point.x && point.x < self.position.x + 1.0 &&
+            self.position.y <= point.y && point.y < self.position.y + 1.0
+    }
+
+    pub fn distance_to(&self, point: Vec2) -> f32 {
+        let nearest_point = vec2(
+            point.x.clamp(self.position.x, self.position.x + 1.0),
+            point.y.clamp(self.position.y, self.position.y + 1.0)
+        );
+
+        glm::distance(nearest_point, point)
+    }
+}
+
+#[derive(Clone)]
 pub struct Tilemap {
     id: ResourceId,
     tiles: Vec<Vec<PlacedTile>>,
+    moving_tiles: HashMap<usize, MovingTile>,
     sizes: UVec2
 }
 
 impl Tilemap {
-    pub fn new(id: ResourceId, sizes: UVec2, empty_tile: &Rc<Tile>) -> Self {
+    pub fn new(id: ResourceId, sizes: UVec2, empty_tile: &Arc<Tile>) -> Self {
         let tiles = vec![vec![PlacedTile::new(empty_tile); sizes.x as usize]; sizes.y as usize];
         Self {
             id,
             tiles,
+            moving_tiles: HashMap::new(),
             sizes
         }
     }
 
-    pub fn from_raw_tilemap(id: ResourceId, raw_tilemap: Vec<Vec<&Rc<Tile>>>) -> Self {
-        let sizes = uvec2(raw_tilemap.len() as u32, raw_tilemap.get(0).unwrap().len() as u32);
+    pub fn from_raw_tilemap(id: ResourceId, raw_tilemap: Vec<Vec<&Arc<Tile>>>) -> Self {
+        let sizes = uvec2(raw_tilemap.get(0).unwrap().len() as u32, raw_tilemap.len() as u32);
 
         let tiles = raw_tilemap.iter()
             .map(|row| row.iter()
@@ -56,6 +95,7 @@ impl Tilemap {
         Self {
             id,
             tiles,
+            moving_tiles: HashMap::new(),
             sizes
         }
     }
@@ -67,12 +107,37 @@ impl Tilemap {
             .flatten()
     }
 
-    pub fn set_tile(&mut self, position: UVec2, tile: &Rc<Tile>) {
+    pub fn set_tile(&mut self, position: UVec2, tile: &Arc<Tile>) {
         self.tiles
             .get_mut(position.y as usize)
             .map(|row| row[position.x as usize] = PlacedTile::new(tile));
     }
 
+    pub fn moving_tiles(&self) -> impl Iterator<Item = &MovingTile> {
+        self.moving_tiles.values()
+    }
+
+    pub fn keyed_moving_tiles(&self) -> impl Iterator<Item = (usize, &MovingTile)> {
+        self.moving_tiles.iter().map(|(key, moving_tile)| (*key, moving_tile))
+    }
+
+    pub fn moving_tile_at(&self, point: Vec2) -> Option<&MovingTile> {
+        self.moving_tiles.values().find(|moving_tile| moving_tile.contains(point))
+    }
+
+    // Places or moves the tile with given key to the position between cells
+    pub fn set_moving_tile(&mut self, key: usize, tile: &Arc<Tile>, position: Vec2) {
+        self.moving_tiles.insert(key, MovingTile { tile: tile.clone(), position });
+    }
+
+    pub fn remove_moving_tile(&mut self, key: usize) {
+        self.moving_tiles.remove(&key);
+    }
+
+    pub fn clear_moving_tiles(&mut self) {
+        self.moving_tiles.clear();
+    }
+
     pub fn sizes(&self) -> UVec2 {
         self.sizes
     }
@@ -83,3 +148,75 @@ impl Resource for Tilemap {
         self.id
     }
 }
+
+impl RaycastWorld for Tilemap {
+    fn cast_ray(&self, start_position: Vec2, ray_angle: f32, maximal_distance: f32) -> (Ray, Hit) {
+        cast_ray_tilemap(self, start_position, ray_angle, maximal_distance)
+    }
+
+    fn is_colliding(&self, position: Vec2, radius: f32) -> bool {
+        if self.moving_tiles.values().any(|moving_tile| moving_tile.distance_to(position) <= radius) {
+            return true;
+        }
+
+        let from = uvec2((position.x - radius) as u32, (position.y - radius) as u32);
+        let to = uvec2((position.x + radius) as u32, (position.y + radius) as u32);
+
+        for y in from.y..=to.y {
+            for x in from.x..=to.x {
+                let tile = match self.get_tile(uvec2(x, y)) {
+                    Some(placed_tile) if placed_tile.tile().is_collision_enabled() => placed_tile.tile(),
+                    _ => continue
+                };
+
+                let tile_origin = vec2(x as f32, y as f32);
+
+                let distance = match tile.shape().segment() {
+                    None => {
+                        let nearest_tile_point = vec2(
+                            position.x.clamp(tile_origin.x, tile_origin.x + 1.0),
+                            position.y.clamp(tile_origin.y, tile_origin.y + 1.0)
+                        );
+
+                        glm::distance(nearest_tile_point, position)
+                    }
+
+                    Some((from, to)) => distance_to_segment(tile_origin + from, tile_origin + to, position)
+                };
+
+                if distance <= radius {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn bounds(&self) -> Vec2 {
+        vec2(self.sizes.x as f32, self.sizes.y as f32)
+    }
+
+    fn render_2d(&self, painter: &mut dyn MapPainter) {
+        for x in 0..self.sizes.x {
+            for y in 0..self.sizes.y {
+                let tile = self.get_tile(uvec2(x, y)).unwrap().tile();
+                let tile_origin = vec2(x as f32, y as f32);
+
+                match tile.shape().segment() {
+                    None => painter.paint_tile(tile_origin, tile.color()),
+
+                    // Tile occupying part of the cell is drawn as a line over the empty cell
+                    Some((from, to)) => {
+                        painter.paint_tile(tile_origin, &ObjectColor::WHITE);
+                        painter.paint_line(tile_origin + from, tile_origin + to, tile.color());
+                    }
+                }
+            }
+        }
+
+        for moving_tile in self.moving_tiles() {
+            painter.paint_tile(moving_tile.position(), moving_tile.tile().color());
+        }
+    }
+}
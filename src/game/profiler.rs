@@ -0,0 +1,203 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::system::System;
+
+// Frames kept for the overlay graph and averages
+pub const PROFILE_HISTORY_LEN: usize = 120;
+
+#[derive(Clone, Default)]
+pub struct FrameProfile {
+    sections: Vec<(&'static str, Duration)>,
+    ray_casting_time: Duration,
+    intersection_tests: u64,
+    frame_time: Duration
+}
+
+impl FrameProfile {
+    pub fn sections(&self) -> &Vec<(&'static str, Duration)> {
+        &self.sections
+    }
+    pub fn ray_casting_time(&self) -> Duration {
+        self.ray_casting_time
+    }
+    pub fn intersection_tests(&self) -> u64 {
+        self.intersection_tests
+    }
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+}
+
+// Collects time of every profiled section during the frame and keeps the recent frames.
+// Sections are registered once, so every frame has the same sections even if some systems were not updated
+pub struct FrameProfiler {
+    section_names: Vec<&'static str>,
+    section_times: Vec<Duration>,
+    current_frame: FrameProfile,
+    history: VecDeque<FrameProfile>,
+    target_frame_time: Duration,
+    csv_writer: Option<BufWriter<File>>,
+    frames_count: u64
+}
+
+impl FrameProfiler {
+    pub fn new(target_frame_time: Duration) -> Self {
+        Self {
+            section_names: vec![],
+            section_times: vec![],
+            current_frame: FrameProfile::default(),
+            history: VecDeque::with_capacity(PROFILE_HISTORY_LEN),
+            target_frame_time,
+            csv_writer: None,
+            frames_count: 0
+        }
+    }
+
+    // Every finished frame is written as a CSV row, header is written with the first frame
+    pub fn log_to_csv(&mut self, path: &Path) -> Result<(), String> {
+        let file = File::create(path).map_err(|error| error.to_string())?;
+        self.csv_writer = Some(BufWriter::new(file));
+
+        Ok(())
+    }
+
+    pub fn register_section(&mut self, name: &'static str) -> usize {
+        match self.section_names.iter().position(|section_name| *section_name == name) {
+            Some(section_index) => section_index,
+            None => {
+                self.section_names.push(name);
+                self.section_times.push(Duration::ZERO);
+                self.section_names.len() - 1
+            }
+        }
+    }
+
+    // Section time is summed up when the section runs several times per frame
+    pub fn record_section(&mut self, name: &'static str, time: Duration) {
+        let section_index = self.register_section(name);
+        self.section_times[section_index] += time;
+    }
+
+    // Only camera rays of the 3d view are recorded
+    pub fn record_ray_casting(&mut self, time: Duration, intersection_tests: u64) {
+        self.current_frame.ray_casting_time += time;
+        self.current_frame.intersection_tests += intersection_tests;
+    }
+
+    pub fn finish_frame(&mut self, frame_time: Duration) {
+        let mut frame = std::mem::take(&mut self.current_frame);
+        frame.sections = self.section_names.iter().copied().zip(self.section_times.iter().copied()).collect();
+        frame.frame_time = frame_time;
+
+        self.section_times.iter_mut().for_each(|time| *time = Duration::ZERO);
+
+        if let Err(error) = self.write_csv_row(&frame) {
+            log::error!("Unable to write frame profile, CSV logging is stopped: {}", error);
+            self.csv_writer = None;
+        }
+
+        if self.history.len() == PROFILE_HISTORY_LEN {
+            self.history.pop_front();
+        }
+
+        self.history.push_back(frame);
+        self.frames_count += 1;
+    }
+
+    pub fn history(&self) -> &VecDeque<FrameProfile> {
+        &self.history
+    }
+
+    pub fn target_frame_time(&self) -> Duration {
+        self.target_frame_time
+    }
+
+    // Average of the recent frames, sections are matched by order
+    pub fn average_frame(&self) -> Option<FrameProfile> {
+        let last_frame = self.history.back()?;
+        let frames_count = self.history.len() as u32;
+
+        let mut average = FrameProfile {
+            sections: last_frame.sections.iter().map(|(name, _)| (*name, Duration::ZERO)).collect(),
+            ..FrameProfile::default()
+        };
+
+        for frame in &self.history {
+            for (average_section, (_, time)) in average.sections.iter_mut().zip(&frame.sections) {
+                average_section.1 += *time / frames_count;
+            }
+
+            average.ray_casting_time += frame.ray_casting_time / frames_count;
+            average.intersection_tests += frame.intersection_tests / frames_count as u64;
+            average.frame_time += frame.frame_time / frames_count;
+        }
+
+        Some(average)
+    }
+
+    fn write_csv_row(&mut self, frame: &FrameProfile) -> std::io::Result<()> {
+        let Some(csv_writer) = self.csv_writer.as_mut() else {
+            return Ok(());
+        };
+
+        if self.frames_count == 0 {
+            let section_names: Vec<&str> = frame.sections.iter().map(|(name, _)| *name).collect();
+            writeln!(csv_writer, "frame,frame_ms,{},ray_casting_ms,intersection_tests", section_names.join(","))?;
+        }
+
+        let section_times: Vec<String> = frame.sections.iter()
+            .map(|(_, time)| format_milliseconds(*time))
+            .collect();
+
+        writeln!(
+            csv_writer, "{},{},{},{},{}",
+            self.frames_count,
+            format_milliseconds(frame.frame_time),
+            section_times.join(","),
+            format_milliseconds(frame.ray_casting_time),
+            frame.intersection_tests
+        )
+    }
+}
+
+impl Drop for FrameProfiler {
+    fn drop(&mut self) {
+        if let Some(csv_writer) = self.csv_writer.as_mut() {
+            let _ = csv_writer.flush();
+        }
+    }
+}
+
+fn format_milliseconds(time: Duration) -> String {
+    format!("{:.3}", time.as_secs_f64() * 1000.0)
+}
+
+// Measures update time of the wrapped system
+pub struct ProfiledSystem<S: System> {
+    name: &'static str,
+    system: S,
+    profiler: Rc<RefCell<FrameProfiler>>
+}
+
+impl<S: System> ProfiledSystem<S> {
+    pub fn new(name: &'static str, system: S, profiler: &Rc<RefCell<FrameProfiler>>) -> Self {
+        profiler.borrow_mut().register_section(name);
+        Self { name, system, profiler: profiler.clone() }
+    }
+}
+
+impl<S: System> System for ProfiledSystem<S> {
+    fn update(&mut self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor) {
+        let update_start = Instant::now();
+        self.system.update(manager, accessor);
+
+        self.profiler.borrow_mut().record_section(self.name, update_start.elapsed());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod saved_component;
+pub mod saved_components;
+pub mod save_game;
+pub mod save_service;
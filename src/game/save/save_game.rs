@@ -0,0 +1,595 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use glm::{uvec2, Vec2};
+
+use crate::game::game_state::Repositories;
+use crate::game::launch_options::{LaunchOptions, MapType};
+use crate::game::model::object_color::ObjectColor;
+use crate::game::model::repository::Resource;
+use crate::game::model::tile::Tile;
+use crate::game::model::ResourceId;
+use crate::game::save::saved_component::{FieldReader, FieldWriter};
+use crate::game::save::saved_components::{ComponentLoader, SavedComponents};
+use crate::game::script::level_script::{LevelScript, ScriptSnapshot};
+
+// Saves of other versions are rejected, the version is increased on every change of the format
+const SAVE_VERSION: u32 = 1;
+
+// Level the game is saved on, the same level should be started to load the save
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaveHeader {
+    pub map_path: PathBuf,
+    pub map_type: MapType
+}
+
+impl SaveHeader {
+    pub fn from_options(options: &LaunchOptions) -> Self {
+        Self {
+            map_path: options.map_path.clone(),
+            map_type: options.map_type
+        }
+    }
+
+    pub fn apply_to(&self, options: &mut LaunchOptions) {
+        options.map_path = self.map_path.clone();
+        options.map_type = self.map_type;
+    }
+
+    // Reads only the header, so the level of the save can be started before the save is loaded
+    pub fn read(path: &Path) -> Result<SaveHeader, String> {
+        let source = fs::read_to_string(path).map_err(|error| error.to_string())?;
+
+        let mut header = SaveHeader { map_path: PathBuf::new(), map_type: MapType::Hybrid };
+        let mut is_version_checked = false;
+
+        for (line_index, line) in save_lines(&source) {
+            if !is_version_checked {
+                check_version(line).map_err(|error| format!("line {}: {}", line_index + 1, error))?;
+                is_version_checked = true;
+                continue;
+            }
+
+            if !read_header_line(&mut header, line).map_err(|error| format!("line {}: {}", line_index + 1, error))? {
+                break;
+            }
+        }
+
+        match header.map_path.as_os_str().is_empty() {
+            true => Err("map of the save is missing".to_string()),
+            false => Ok(header)
+        }
+    }
+}
+
+// Whole game state: components of all entities, changed map data and the level script state.
+//
+// Text format, lines starting with "#" are comments. Header:
+//   version 1
+//   map PATH
+//   map_type TYPE
+// followed by the entities and the maps:
+//   ENTITY COMPONENT [VALUE ...]
+//   tiles TILEMAP ROW TILE ...
+//   moving_tile TILEMAP KEY TILE X Y
+//   line LINEMAP INDEX COLOR FROM_X FROM_Y TO_X TO_Y
+//   script_variables "JSON"
+//   script_timer "EVENT" NANOSECONDS
+//   script_random STATE
+// Strings are quoted. Every value is checked before the loading, so broken saves don't change the game
+pub struct SaveGame {
+    entities: BTreeMap<usize, Vec<ComponentLoader>>,
+    tile_rows: Vec<(ResourceId, u32, Vec<Arc<Tile>>)>,
+    moving_tiles: Vec<(ResourceId, usize, Arc<Tile>, Vec2)>,
+    lines: Vec<(ResourceId, usize, ObjectColor, Vec2, Vec2)>,
+    script: Option<ScriptSnapshot>
+}
+
+pub fn write_save(
+    path: &Path,
+    header: &SaveHeader,
+    manager: &EntityManager,
+    accessor: &mut EntityIdAccessor,
+    components: &SavedComponents,
+    repositories: &Repositories,
+    level_script: Option<&Rc<RefCell<LevelScript>>>
+) -> Result<(), String> {
+    let mut lines = vec![
+        "# Saved game".to_string(),
+        format!("version {}", SAVE_VERSION),
+        format!("map {}", header.map_path.display()),
+        format!("map_type {}", header.map_type.name())
+    ];
+
+    for entity_id in components.entity_ids(manager, accessor) {
+        for component_line in components.save_entity(manager, entity_id)? {
+            lines.push(format!("{} {}", entity_id, component_line));
+        }
+    }
+
+    // Resources are sorted, so saves of the same state are equal
+    let tilemap_repository = repositories.tilemap_repository().borrow();
+    let mut tilemaps: Vec<_> = tilemap_repository.resources().collect();
+    tilemaps.sort_by_key(|tilemap| tilemap.id());
+
+    for tilemap in tilemaps {
+        for y in 0..tilemap.sizes().y {
+            let mut fields = FieldWriter::default();
+            fields.push("tiles").push(tilemap.id()).push(y);
+
+            for x in 0..tilemap.sizes().x {
+                let placed_tile = tilemap.get_tile(uvec2(x, y)).ok_or("tilemap row is shorter than the tilemap")?;
+                fields.push(placed_tile.tile().id());
+            }
+
+            lines.push(fields.into_line());
+        }
+
+        let mut moving_tiles: Vec<_> = tilemap.keyed_moving_tiles().collect();
+        moving_tiles.sort_by_key(|(key, _)| *key);
+
+        for (key, moving_tile) in moving_tiles {
+            let mut fields = FieldWriter::default();
+            fields.push("moving_tile").push(tilemap.id()).push(key).push(moving_tile.tile().id()).push_vec2(moving_tile.position());
+
+            lines.push(fields.into_line());
+        }
+    }
+
+    let linemap_repository = repositories.linemap_repository().borrow();
+    let mut linemaps: Vec<_> = linemap_repository.resources().collect();
+    linemaps.sort_by_key(|linemap| linemap.id());
+
+    for linemap in linemaps {
+        for (line_index, line) in linemap.lines().iter().enumerate() {
+            let mut fields = FieldWriter::default();
+            fields.push("line").push(linemap.id()).push(line_index).push_color(line.color())?.push_vec2(*line.from()).push_vec2(*line.to());
+
+            lines.push(fields.into_line());
+        }
+    }
+
+    if let Some(level_script) = level_script {
+        let snapshot = level_script.borrow().snapshot();
+
+        let mut fields = FieldWriter::default();
+        fields.push("script_variables").push_string(&rhai::format_map_as_json(&snapshot.variables));
+        lines.push(fields.into_line());
+
+        for (event_name, remaining) in &snapshot.timers {
+            let mut fields = FieldWriter::default();
+            fields.push("script_timer").push_string(event_name).push_duration(*remaining);
+            lines.push(fields.into_line());
+        }
+
+        lines.push(format!("script_random {}", snapshot.random_state));
+    }
+
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+    }
+
+    // Previous save of the slot is replaced only by the complete file
+    let temporary_path = path.with_extension("tmp");
+    fs::write(&temporary_path, lines.join("\n") + "\n").map_err(|error| error.to_string())?;
+    fs::rename(&temporary_path, path).map_err(|error| error.to_string())
+}
+
+impl SaveGame {
+    pub fn read(
+        path: &Path,
+        expected_header: &SaveHeader,
+        components: &SavedComponents,
+        repositories: &Repositories,
+        level_script: Option<&Rc<RefCell<LevelScript>>>,
+        current_entity_ids_bound: usize
+    ) -> Result<SaveGame, String> {
+        let source = fs::read_to_string(path).map_err(|error| error.to_string())?;
+
+        let mut header = SaveHeader { map_path: PathBuf::new(), map_type: MapType::Hybrid };
+        let mut save_game = SaveGame { entities: BTreeMap::new(), tile_rows: vec![], moving_tiles: vec![], lines: vec![], script: None };
+        let mut is_version_checked = false;
+
+        for (line_index, line) in save_lines(&source) {
+            let line_error = |error: String| format!("line {}: {}", line_index + 1, error);
+
+            // Version is checked first, lines of other versions may be not understood
+            if !is_version_checked {
+                check_version(line).map_err(line_error)?;
+                is_version_checked = true;
+                continue;
+            }
+
+            if read_header_line(&mut header, line).map_err(line_error)? {
+                continue;
+            }
+
+            if header != *expected_header {
+                return Err(format!("save is made on {} ({}), current level is {} ({})",
+                    header.map_path.display(), header.map_type.name(), expected_header.map_path.display(), expected_header.map_type.name()));
+            }
+
+            save_game.read_line(line, components, repositories, level_script).map_err(line_error)?;
+        }
+
+        if !is_version_checked {
+            return Err("save is empty".to_string());
+        }
+
+        if level_script.is_some() && save_game.script.is_none() {
+            return Err("state of the level script is missing".to_string());
+        }
+
+        // Entities get their saved ids back one by one, so ids far beyond the existing and saved entities would hang the game
+        let entity_ids_bound = current_entity_ids_bound + save_game.entities.len();
+
+        if let Some((entity_id, _)) = save_game.entities.last_key_value().filter(|(entity_id, _)| **entity_id >= entity_ids_bound) {
+            return Err(format!("entity id {} is out of range, ids below {} are expected", entity_id, entity_ids_bound));
+        }
+
+        Ok(save_game)
+    }
+
+    fn read_line(&mut self, line: &str, components: &SavedComponents, repositories: &Repositories, level_script: Option<&Rc<RefCell<LevelScript>>>) -> Result<(), String> {
+        let mut fields = FieldReader::parse(line)?;
+        let keyword = fields.next_word()?.to_string();
+
+        if let Ok(entity_id) = keyword.parse::<usize>() {
+            let component_name = fields.next_word()?.to_string();
+            let loader = components.load_component(&component_name, &mut fields, repositories)
+                .map_err(|error| format!("{} of entity {}: {}", component_name, entity_id, error))?;
+
+            self.entities.entry(entity_id).or_default().push(loader);
+            return Ok(());
+        }
+
+        let tile = |tile_id: ResourceId| repositories.tiles_repository().borrow().get_resource(&tile_id).cloned()
+            .ok_or_else(|| format!("tile {} is not found", tile_id));
+
+        match keyword.as_str() {
+            "tiles" => {
+                let (tilemap_id, y): (ResourceId, u32) = (fields.next()?, fields.next()?);
+
+                let sizes = repositories.tilemap_repository().borrow().get_resource(&tilemap_id)
+                    .map(|tilemap| tilemap.sizes())
+                    .ok_or_else(|| format!("tilemap {} is not found", tilemap_id))?;
+
+                if y >= sizes.y {
+                    return Err(format!("tilemap {} has no row {}", tilemap_id, y));
+                }
+
+                let tiles = (0..sizes.x)
+                    .map(|_| fields.next().and_then(tile))
+                    .collect::<Result<Vec<Arc<Tile>>, String>>()?;
+
+                self.tile_rows.push((tilemap_id, y, tiles));
+            }
+
+            "moving_tile" => {
+                let (tilemap_id, key): (ResourceId, usize) = (fields.next()?, fields.next()?);
+
+                if repositories.tilemap_repository().borrow().get_resource(&tilemap_id).is_none() {
+                    return Err(format!("tilemap {} is not found", tilemap_id));
+                }
+
+                self.moving_tiles.push((tilemap_id, key, tile(fields.next()?)?, fields.next_vec2()?));
+            }
+
+            "line" => {
+                let (linemap_id, line_index): (ResourceId, usize) = (fields.next()?, fields.next()?);
+
+                let lines_count = repositories.linemap_repository().borrow().get_resource(&linemap_id)
+                    .map(|linemap| linemap.lines().len())
+                    .ok_or_else(|| format!("linemap {} is not found", linemap_id))?;
+
+                if line_index >= lines_count {
+                    return Err(format!("linemap {} has no line {}", linemap_id, line_index));
+                }
+
+                self.lines.push((linemap_id, line_index, fields.next_color()?, fields.next_vec2()?, fields.next_vec2()?));
+            }
+
+            "script_variables" | "script_timer" | "script_random" => {
+                let level_script = level_script.ok_or("level has no script")?;
+                let script = self.script.get_or_insert_with(|| ScriptSnapshot { variables: Default::default(), timers: vec![], random_state: 1 });
+
+                match keyword.as_str() {
+                    "script_variables" => script.variables = level_script.borrow().parse_variables(&fields.next_string()?)?,
+                    "script_timer" => script.timers.push((fields.next_string()?, fields.next_duration()?)),
+                    _ => script.random_state = fields.next()?
+                }
+            }
+
+            keyword => return Err(format!("unknown statement \"{}\"", keyword))
+        }
+
+        fields.finish()
+    }
+
+    pub fn apply(self, manager: &mut EntityManager, accessor: &mut EntityIdAccessor, components: &SavedComponents, repositories: &Repositories, level_script: Option<&Rc<RefCell<LevelScript>>>) {
+        // Cached entity ids are updated only when the frame of the component change differs from the caching one
+        let current_entity_ids = components.entity_ids(manager, accessor);
+        manager.increment_frame();
+
+        // Saved ids are freed too, so every saved entity can get its id back
+        for entity_id in current_entity_ids.iter().chain(self.entities.keys()) {
+            manager.remove_entity(*entity_id);
+        }
+
+        let entity_ids: Vec<usize> = self.entities.keys().copied().collect();
+        create_entities_with_ids(manager, &entity_ids);
+
+        for (entity_id, loaders) in self.entities {
+            for loader in loaders {
+                loader(manager, entity_id);
+            }
+        }
+
+        let mut tilemap_repository = repositories.tilemap_repository().borrow_mut();
+
+        // Moving tiles are replaced completely, stopped push-walls have none
+        for (tilemap_id, _, _) in &self.tile_rows {
+            if let Some(tilemap) = tilemap_repository.get_resource_mut(tilemap_id) {
+                tilemap.clear_moving_tiles();
+            }
+        }
+
+        for (tilemap_id, y, tiles) in self.tile_rows {
+            if let Some(tilemap) = tilemap_repository.get_resource_mut(&tilemap_id) {
+                for (x, tile) in tiles.iter().enumerate() {
+                    tilemap.set_tile(uvec2(x as u32, y), tile);
+                }
+            }
+        }
+
+        for (tilemap_id, key, tile, position) in self.moving_tiles {
+            if let Some(tilemap) = tilemap_repository.get_resource_mut(&tilemap_id) {
+                tilemap.set_moving_tile(key, &tile, position);
+            }
+        }
+
+        let mut linemap_repository = repositories.linemap_repository().borrow_mut();
+
+        for (linemap_id, line_index, color, from, to) in self.lines {
+            if let Some(linemap) = linemap_repository.get_resource_mut(&linemap_id) {
+                linemap.set_line(line_index, from, to);
+                linemap.set_line_color(line_index, color);
+            }
+        }
+
+        if let (Some(level_script), Some(script)) = (level_script, self.script) {
+            level_script.borrow_mut().restore(script);
+        }
+    }
+}
+
+// Entities get their saved ids back, as the ids are kept by the event handlers.
+// Entity manager reuses the freed ids first, so entities are created until the id is given out
+fn create_entities_with_ids(manager: &mut EntityManager, entity_ids: &[usize]) {
+    let mut spare_ids = HashSet::new();
+
+    for entity_id in entity_ids {
+        while !spare_ids.remove(entity_id) {
+            spare_ids.insert(manager.create_entity());
+        }
+    }
+
+    for spare_id in spare_ids {
+        manager.remove_entity(spare_id);
+    }
+}
+
+fn save_lines(source: &str) -> impl Iterator<Item = (usize, &str)> {
+    source.lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn check_version(line: &str) -> Result<(), String> {
+    let version = line.strip_prefix("version ")
+        .and_then(|version| version.trim().parse::<u32>().ok())
+        .ok_or("save should start with the version")?;
+
+    match version {
+        SAVE_VERSION => Ok(()),
+        version => Err(format!("save version {} is not supported, expected version {}", version, SAVE_VERSION))
+    }
+}
+
+// Returns false when the line is not a header line
+fn read_header_line(header: &mut SaveHeader, line: &str) -> Result<bool, String> {
+    // Map path can contain spaces
+    if let Some(path) = line.strip_prefix("map ") {
+        header.map_path = PathBuf::from(path.trim());
+        return Ok(true);
+    }
+
+    if let Some(name) = line.strip_prefix("map_type ") {
+        header.map_type = MapType::from_name(name.trim()).ok_or_else(|| format!("unknown map type \"{}\"", name.trim()))?;
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process;
+    use std::sync::Arc;
+
+    use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+    use glm::{uvec2, vec2};
+
+    use crate::game::ecs::component::position_component::PositionComponent;
+    use crate::game::ecs::component::velocity_component::VelocityComponent;
+    use crate::game::game_state::Repositories;
+    use crate::game::launch_options::MapType;
+    use crate::game::model::linemap::Linemap;
+    use crate::game::model::object_color::ObjectColor;
+    use crate::game::model::repository::Resource;
+    use crate::game::model::tile::Tile;
+    use crate::game::model::tilemap::Tilemap;
+    use crate::game::save::saved_components::SavedComponents;
+
+    use super::{write_save, SaveGame, SaveHeader};
+
+    fn save_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("raycaster_save_{}_{}.save", process::id(), name))
+    }
+
+    fn header() -> SaveHeader {
+        SaveHeader { map_path: PathBuf::from("maps/test.map"), map_type: MapType::Hybrid }
+    }
+
+    fn components() -> SavedComponents {
+        let mut components = SavedComponents::new();
+        components.register::<PositionComponent>().register::<VelocityComponent>();
+        components
+    }
+
+    // Empty and wall tiles, 2x2 tilemap and linemap with a single line
+    fn repositories() -> Repositories {
+        let repositories = Repositories::new();
+
+        let empty = Arc::new(Tile::new(0, ObjectColor::WHITE, false));
+        let wall = Arc::new(Tile::new(1, ObjectColor::RED, true));
+        repositories.tiles_repository().borrow_mut().register_resource(empty.clone()).register_resource(wall.clone());
+
+        let tilemap = Tilemap::from_raw_tilemap(0, vec![vec![&wall, &empty], vec![&empty, &empty]]);
+        repositories.tilemap_repository().borrow_mut().register_resource(Arc::new(tilemap));
+
+        let mut linemap = Linemap::new(0);
+        linemap.add_line(ObjectColor::BLUE, vec2(0.0, 0.0), vec2(1.0, 0.0));
+        repositories.linemap_repository().borrow_mut().register_resource(Arc::new(linemap));
+
+        repositories
+    }
+
+    fn create_manager() -> EntityManager {
+        let mut manager = EntityManager::new();
+        manager.register::<PositionComponent>().register::<VelocityComponent>();
+        manager
+    }
+
+    // Reads the save with the given lines after the header
+    fn read_lines(name: &str, lines: &str, current_entity_ids_bound: usize) -> Result<SaveGame, String> {
+        let path = save_path(name);
+        fs::write(&path, format!("version 1\nmap maps/test.map\nmap_type hybrid\n{}", lines)).unwrap();
+
+        let result = SaveGame::read(&path, &header(), &components(), &repositories(), None, current_entity_ids_bound);
+        fs::remove_file(&path).unwrap();
+
+        result
+    }
+
+    #[test]
+    fn saved_game_is_restored() {
+        let (components, saved_repositories) = (components(), repositories());
+        let (mut manager, mut accessor) = (create_manager(), EntityIdAccessor::new());
+
+        // Removed entity leaves a hole in the ids, saved entities should get their ids back
+        let player_id = manager.create_entity();
+        let removed_id = manager.create_entity();
+        let item_id = manager.create_entity();
+        manager.remove_entity(removed_id);
+
+        manager.add_component_to_entity(player_id, PositionComponent::new(vec2(1.5, 1.5)));
+        manager.add_component_to_entity(player_id, VelocityComponent::new());
+        manager.add_component_to_entity(item_id, PositionComponent::new(vec2(0.5, 1.5)));
+
+        {
+            let mut tilemap_repository = saved_repositories.tilemap_repository().borrow_mut();
+            let tilemap = tilemap_repository.get_resource_mut(&0).unwrap();
+            let wall = saved_repositories.tiles_repository().borrow().get_resource(&1).unwrap().clone();
+
+            tilemap.set_tile(uvec2(1, 1), &wall);
+            tilemap.set_moving_tile(7, &wall, vec2(0.5, 0.0));
+
+            saved_repositories.linemap_repository().borrow_mut().get_resource_mut(&0).unwrap().set_line(0, vec2(0.0, 0.5), vec2(1.0, 0.5));
+        }
+
+        let path = save_path("round_trip");
+        write_save(&path, &header(), &manager, &mut accessor, &components, &saved_repositories, None).unwrap();
+
+        // Game goes on after saving
+        let mut later_manager = create_manager();
+        let mut later_accessor = EntityIdAccessor::new();
+        let later_id = later_manager.create_entity();
+        later_manager.add_component_to_entity(later_id, PositionComponent::new(vec2(0.1, 0.1)));
+
+        let later_repositories = repositories();
+        let current_entity_ids_bound = components.entity_ids(&later_manager, &mut later_accessor).last().map_or(0, |entity_id| entity_id + 1);
+
+        let save_game = SaveGame::read(&path, &header(), &components, &later_repositories, None, current_entity_ids_bound).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        save_game.apply(&mut later_manager, &mut later_accessor, &components, &later_repositories, None);
+
+        assert_eq!(components.entity_ids(&later_manager, &mut later_accessor), vec![player_id, item_id]);
+        assert_eq!(later_manager.borrow_component::<PositionComponent>(player_id).unwrap().position, vec2(1.5, 1.5));
+        assert_eq!(later_manager.borrow_component::<PositionComponent>(item_id).unwrap().position, vec2(0.5, 1.5));
+        assert!(later_manager.borrow_component::<VelocityComponent>(player_id).is_some());
+        assert!(later_manager.borrow_component::<VelocityComponent>(item_id).is_none());
+
+        let tilemap_repository = later_repositories.tilemap_repository().borrow();
+        let tilemap = tilemap_repository.get_resource(&0).unwrap();
+        assert_eq!(tilemap.get_tile(uvec2(1, 1)).unwrap().tile().id(), 1);
+        assert_eq!(tilemap.keyed_moving_tiles().map(|(key, moving_tile)| (key, moving_tile.position())).collect::<Vec<_>>(), vec![(7, vec2(0.5, 0.0))]);
+
+        let linemap_repository = later_repositories.linemap_repository().borrow();
+        let line = &linemap_repository.get_resource(&0).unwrap().lines()[0];
+        assert_eq!((*line.from(), *line.to()), (vec2(0.0, 0.5), vec2(1.0, 0.5)));
+    }
+
+    #[test]
+    fn other_version_is_rejected() {
+        let path = save_path("version");
+        fs::write(&path, "version 2\nmap maps/test.map\n").unwrap();
+
+        let result = SaveGame::read(&path, &header(), &components(), &repositories(), None, 0);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.err(), Some("line 1: save version 2 is not supported, expected version 1".to_string()));
+    }
+
+    #[test]
+    fn save_of_other_level_is_rejected() {
+        let path = save_path("header");
+        fs::write(&path, "version 1\nmap maps/other.map\nmap_type hybrid\n0 position 1 1\n").unwrap();
+
+        let result = SaveGame::read(&path, &header(), &components(), &repositories(), None, 0);
+        fs::remove_file(&path).unwrap();
+
+        assert!(result.err().is_some_and(|error| error.starts_with("save is made on maps/other.map")));
+    }
+
+    #[test]
+    fn unknown_component_is_rejected() {
+        let result = read_lines("component", "0 health 100 100\n", 0);
+        assert_eq!(result.err(), Some("line 4: health of entity 0: unknown component \"health\"".to_string()));
+    }
+
+    #[test]
+    fn entity_id_out_of_range_is_rejected() {
+        assert!(read_lines("entity_id_in_range", "0 position 1 1\n4 position 2 2\n", 3).is_ok());
+
+        let result = read_lines("entity_id_out_of_range", "0 position 1 1\n1000000000 position 2 2\n", 3);
+        assert_eq!(result.err(), Some("entity id 1000000000 is out of range, ids below 5 are expected".to_string()));
+    }
+
+    #[test]
+    fn truncated_tile_row_is_rejected() {
+        assert!(read_lines("tile_row", "tiles 0 1 0 1\n", 0).is_ok());
+        assert!(read_lines("truncated_tile_row", "tiles 0 1 0\n", 0).is_err());
+        assert!(read_lines("missing_tile_row", "tiles 0 2 0 1\n", 0).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+pub enum SaveRequest {
+    Save(String),
+    Load(String)
+}
+
+// Save and load requests of the named slots, handled by the save system between the simulation and rendering
+pub struct SaveService {
+    directory: PathBuf,
+    request: Option<SaveRequest>,
+    messages: Vec<String>
+}
+
+impl SaveService {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory, request: None, messages: vec![] }
+    }
+
+    pub fn slot_path(&self, slot: &str) -> PathBuf {
+        self.directory.join(format!("{}.sav", slot))
+    }
+
+    // Only the last request of the frame is handled
+    pub fn request_save(&mut self, slot: &str) {
+        self.request = Some(SaveRequest::Save(slot.to_string()));
+    }
+
+    pub fn request_load(&mut self, slot: &str) {
+        self.request = Some(SaveRequest::Load(slot.to_string()));
+    }
+
+    pub fn take_request(&mut self) -> Option<SaveRequest> {
+        self.request.take()
+    }
+
+    pub fn push_message(&mut self, message: String) {
+        self.messages.push(message);
+    }
+
+    pub fn take_messages(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.messages)
+    }
+}
+
+// Slot names become file names, so only safe characters are allowed
+pub fn is_valid_slot_name(slot: &str) -> bool {
+    !slot.is_empty() && slot.chars().all(|character| character.is_ascii_alphanumeric() || character == '-' || character == '_')
+}
@@ -0,0 +1,203 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+use ecs_rust::component::Component;
+use glm::{uvec2, vec2, UVec2, Vec2};
+
+use crate::game::game_state::Repositories;
+use crate::game::model::object_color::ObjectColor;
+
+// Component written to the saved game as a line of fields
+pub trait SavedComponent: Component + Sized + 'static {
+    // Name of the component line in the save file
+    const NAME: &'static str;
+
+    fn save(&self, fields: &mut FieldWriter) -> Result<(), String>;
+
+    // Resources are given to resolve saved resource ids
+    fn load(fields: &mut FieldReader, repositories: &Repositories) -> Result<Self, String>;
+}
+
+// Fields of the save file line. Strings are quoted, so they can contain spaces
+#[derive(Default)]
+pub struct FieldWriter {
+    fields: Vec<String>
+}
+
+impl FieldWriter {
+    pub fn push(&mut self, value: impl Display) -> &mut Self {
+        self.fields.push(value.to_string());
+        self
+    }
+
+    pub fn push_vec2(&mut self, value: Vec2) -> &mut Self {
+        self.push(value.x).push(value.y)
+    }
+
+    pub fn push_uvec2(&mut self, value: UVec2) -> &mut Self {
+        self.push(value.x).push(value.y)
+    }
+
+    pub fn push_bool(&mut self, value: bool) -> &mut Self {
+        self.push(value as u8)
+    }
+
+    pub fn push_duration(&mut self, value: Duration) -> &mut Self {
+        self.push(value.as_nanos())
+    }
+
+    pub fn push_string(&mut self, value: &str) -> &mut Self {
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+        self.push(format!("\"{}\"", escaped))
+    }
+
+    // Missing string is written as "-", which differs from the quoted "-" string
+    pub fn push_optional_string(&mut self, value: Option<&String>) -> &mut Self {
+        match value {
+            None => self.push("-"),
+            Some(value) => self.push_string(value)
+        }
+    }
+
+    pub fn push_color(&mut self, color: &ObjectColor) -> Result<&mut Self, String> {
+        let name = color.name().ok_or("textured colors can't be saved")?;
+        Ok(self.push(name))
+    }
+
+    pub fn into_line(self) -> String {
+        self.fields.join(" ")
+    }
+}
+
+pub struct FieldReader {
+    fields: Vec<String>,
+    next_field: usize
+}
+
+impl FieldReader {
+    pub fn parse(line: &str) -> Result<FieldReader, String> {
+        Ok(FieldReader { fields: split_fields(line)?, next_field: 0 })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.next_field >= self.fields.len()
+    }
+
+    // Raw field, strings are returned with quotes
+    pub fn next_word(&mut self) -> Result<&str, String> {
+        let field = self.fields.get(self.next_field).ok_or("line has too few values")?;
+        self.next_field += 1;
+
+        Ok(field)
+    }
+
+    pub fn next<T: FromStr>(&mut self) -> Result<T, String> {
+        let word = self.next_word()?;
+        word.parse().map_err(|_| format!("invalid value \"{}\"", word))
+    }
+
+    pub fn next_vec2(&mut self) -> Result<Vec2, String> {
+        Ok(vec2(self.next()?, self.next()?))
+    }
+
+    pub fn next_uvec2(&mut self) -> Result<UVec2, String> {
+        Ok(uvec2(self.next()?, self.next()?))
+    }
+
+    pub fn next_bool(&mut self) -> Result<bool, String> {
+        match self.next_word()? {
+            "0" => Ok(false),
+            "1" => Ok(true),
+            word => Err(format!("invalid flag \"{}\", expected 0 or 1", word))
+        }
+    }
+
+    pub fn next_duration(&mut self) -> Result<Duration, String> {
+        Ok(Duration::from_nanos(self.next()?))
+    }
+
+    pub fn next_string(&mut self) -> Result<String, String> {
+        let word = self.next_word()?;
+        unquote(word).ok_or_else(|| format!("expected quoted string instead of \"{}\"", word))
+    }
+
+    pub fn next_optional_string(&mut self) -> Result<Option<String>, String> {
+        if self.fields.get(self.next_field).is_some_and(|field| field == "-") {
+            self.next_field += 1;
+            return Ok(None);
+        }
+
+        self.next_string().map(Some)
+    }
+
+    pub fn next_color(&mut self) -> Result<ObjectColor, String> {
+        let name = self.next_word()?;
+        ObjectColor::from_name(name).ok_or_else(|| format!("unknown color \"{}\"", name))
+    }
+
+    // Line should be read completely, extra values mean the line is not understood
+    pub fn finish(&self) -> Result<(), String> {
+        match self.fields.get(self.next_field) {
+            None => Ok(()),
+            Some(field) => Err(format!("unexpected value \"{}\"", field))
+        }
+    }
+}
+
+// Splits by whitespaces, quoted strings are kept as single fields with their escapes
+fn split_fields(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = vec![];
+    let mut characters = line.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        if character.is_whitespace() {
+            continue;
+        }
+
+        let mut field = character.to_string();
+
+        if character == '"' {
+            loop {
+                match characters.next() {
+                    None => return Err("string is not closed".to_string()),
+                    Some('\\') => {
+                        field.push('\\');
+                        field.push(characters.next().ok_or("string is not closed")?);
+                    }
+                    Some('"') => {
+                        field.push('"');
+                        break;
+                    }
+                    Some(character) => field.push(character)
+                }
+            }
+        } else {
+            while let Some(character) = characters.next_if(|character| !character.is_whitespace()) {
+                field.push(character);
+            }
+        }
+
+        fields.push(field);
+    }
+
+    Ok(fields)
+}
+
+fn unquote(field: &str) -> Option<String> {
+    let content = field.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::new();
+    let mut characters = content.chars();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => match characters.next()? {
+                'n' => value.push('\n'),
+                character => value.push(character)
+            },
+            character => value.push(character)
+        }
+    }
+
+    Some(value)
+}
@@ -0,0 +1,112 @@
+use std::marker::PhantomData;
+
+use ecs_rust::entity_manager::{EntityIdAccessor, EntityManager};
+use ecs_rust::world::World;
+
+use crate::game::game_state::Repositories;
+use crate::game::save::saved_component::{FieldReader, FieldWriter, SavedComponent};
+
+// Adds the loaded component to the entity, created only when the whole save is read
+pub type ComponentLoader = Box<dyn FnOnce(&mut EntityManager, usize)>;
+
+trait SavedComponentType {
+    fn name(&self) -> &'static str;
+    fn register(&self, world: &mut World);
+    fn entity_ids(&self, manager: &EntityManager, accessor: &mut EntityIdAccessor) -> Vec<usize>;
+    fn save(&self, manager: &EntityManager, entity_id: usize) -> Option<Result<String, String>>;
+    fn load(&self, fields: &mut FieldReader, repositories: &Repositories) -> Result<ComponentLoader, String>;
+}
+
+struct SavedComponentTypeOf<T: SavedComponent> {
+    component: PhantomData<T>
+}
+
+impl<T: SavedComponent> SavedComponentType for SavedComponentTypeOf<T> {
+    fn name(&self) -> &'static str {
+        T::NAME
+    }
+
+    fn register(&self, world: &mut World) {
+        world.register_component::<T>();
+    }
+
+    fn entity_ids(&self, manager: &EntityManager, accessor: &mut EntityIdAccessor) -> Vec<usize> {
+        accessor.borrow_ids::<T>(manager).cloned().unwrap_or_default()
+    }
+
+    fn save(&self, manager: &EntityManager, entity_id: usize) -> Option<Result<String, String>> {
+        let component = manager.borrow_component::<T>(entity_id)?;
+        let mut fields = FieldWriter::default();
+
+        Some(component.save(&mut fields).map(|_| fields.into_line()))
+    }
+
+    fn load(&self, fields: &mut FieldReader, repositories: &Repositories) -> Result<ComponentLoader, String> {
+        let component = T::load(fields, repositories)?;
+
+        Ok(Box::new(move |manager: &mut EntityManager, entity_id| {
+            manager.add_component_to_entity(entity_id, component);
+        }))
+    }
+}
+
+// Components of the world. Every component is registered here, so all of them are saved
+pub struct SavedComponents {
+    component_types: Vec<Box<dyn SavedComponentType>>
+}
+
+impl SavedComponents {
+    pub fn new() -> Self {
+        Self { component_types: vec![] }
+    }
+
+    pub fn register<T: SavedComponent>(&mut self) -> &mut Self {
+        self.component_types.push(Box::new(SavedComponentTypeOf::<T> { component: PhantomData }));
+        self
+    }
+
+    pub fn register_in(&self, world: &mut World) {
+        for component_type in &self.component_types {
+            component_type.register(world);
+        }
+    }
+
+    // Ids of the entities which have any component, in ascending order
+    pub fn entity_ids(&self, manager: &EntityManager, accessor: &mut EntityIdAccessor) -> Vec<usize> {
+        let mut entity_ids: Vec<usize> = self.component_types.iter()
+            .flat_map(|component_type| component_type.entity_ids(manager, accessor))
+            .collect();
+
+        entity_ids.sort_unstable();
+        entity_ids.dedup();
+
+        entity_ids
+    }
+
+    // Component lines of the entity, each starts with the component name
+    pub fn save_entity(&self, manager: &EntityManager, entity_id: usize) -> Result<Vec<String>, String> {
+        let mut lines = vec![];
+
+        for component_type in &self.component_types {
+            let Some(line) = component_type.save(manager, entity_id) else {
+                continue;
+            };
+
+            let line = line.map_err(|error| format!("{} of entity {}: {}", component_type.name(), entity_id, error))?;
+            lines.push(format!("{} {}", component_type.name(), line).trim_end().to_string());
+        }
+
+        Ok(lines)
+    }
+
+    pub fn load_component(&self, name: &str, fields: &mut FieldReader, repositories: &Repositories) -> Result<ComponentLoader, String> {
+        let component_type = self.component_types.iter()
+            .find(|component_type| component_type.name() == name)
+            .ok_or_else(|| format!("unknown component \"{}\"", name))?;
+
+        let loader = component_type.load(fields, repositories)?;
+        fields.finish()?;
+
+        Ok(loader)
+    }
+}
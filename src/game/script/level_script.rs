@@ -0,0 +1,332 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use glm::{uvec2, vec2, UVec2, Vec2};
+use num_traits::zero;
+use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope, AST};
+
+use crate::game::ecs::component::direction_component::DirectionComponent;
+use crate::game::ecs::component::door_component::{DoorBinding, DoorComponent, DoorKind};
+use crate::game::ecs::component::position_component::PositionComponent;
+use crate::game::ecs::component::trigger_component::{TriggerComponent, TriggerRegion};
+use crate::game::event::event_action::EventAction;
+use crate::game::event::game_event::{EventContext, GameEvent, GameEventHandler};
+use crate::game::model::object_color::ObjectColor;
+use crate::game::model::tile::TileAxis;
+use crate::game::model::ResourceId;
+
+// Function of the script called for each game event with the event name
+const EVENT_HANDLER_FUNCTION: &str = "on_event";
+
+// Changes requested by the script. Applied after the script returns, when the world can be borrowed
+enum ScriptCommand {
+    Action(EventAction),
+
+    SetLineColor {
+        linemap: ResourceId,
+        line: usize,
+        color: ObjectColor
+    },
+
+    SpawnDoor {
+        position: UVec2,
+        axis: TileAxis
+    },
+
+    SpawnTrigger {
+        from: Vec2,
+        to: Vec2,
+        event_name: String
+    }
+}
+
+struct ScriptTimer {
+    event_name: String,
+    remaining: Duration
+}
+
+// Script state kept in the saved game
+pub struct ScriptSnapshot {
+    pub variables: Map,
+    pub timers: Vec<(String, Duration)>,
+    pub random_state: u64
+}
+
+// State shared between the script functions and the host
+struct ScriptState {
+    commands: Vec<ScriptCommand>,
+    timers: Vec<ScriptTimer>,
+    player_position: Vec2,
+    player_direction: f32,
+
+    // Xorshift generator state, seeded by the game so replays get the same numbers
+    random_state: u64
+}
+
+impl ScriptState {
+    fn push_action(&mut self, action: EventAction) {
+        self.commands.push(ScriptCommand::Action(action));
+    }
+
+    // Number from 0 to 1, excluding 1
+    fn next_random(&mut self) -> f64 {
+        let mut state = self.random_state;
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        self.random_state = state;
+
+        (state.wrapping_mul(0x2545F4914F6CDD1D) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// Rhai script with the level logic. Script reacts to game events in "on_event(name)" function,
+// which keeps level variables in the "this" object map between calls
+pub struct LevelScript {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    level_variables: Dynamic,
+    has_event_handler: bool,
+    state: Rc<RefCell<ScriptState>>
+}
+
+impl LevelScript {
+    pub fn load(path: &Path, seed: u64) -> Result<Self, String> {
+        let source = fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+
+        let state = Rc::new(RefCell::new(ScriptState {
+            commands: vec![],
+            timers: vec![],
+            player_position: zero(),
+            player_direction: 0.0,
+            // Zero state would give only zeros
+            random_state: (seed ^ 0x9E3779B97F4A7C15).max(1)
+        }));
+
+        let engine = create_engine(&state);
+        let ast = engine.compile(source).map_err(|error| format!("{}: {}", path.display(), error))?;
+
+        let has_event_handler = ast.iter_functions()
+            .any(|function| function.name == EVENT_HANDLER_FUNCTION && function.params.len() == 1);
+
+        // Top level statements are run once, on loading
+        let mut scope = Scope::new();
+        engine.run_ast_with_scope(&mut scope, &ast).map_err(|error| format!("{}: {}", path.display(), error))?;
+
+        Ok(Self { engine, ast, scope, level_variables: Dynamic::from_map(Map::new()), has_event_handler, state })
+    }
+
+    pub fn snapshot(&self) -> ScriptSnapshot {
+        let state = self.state.borrow();
+
+        ScriptSnapshot {
+            variables: self.level_variables.clone().try_cast::<Map>().unwrap_or_default(),
+            timers: state.timers.iter().map(|timer| (timer.event_name.clone(), timer.remaining)).collect(),
+            random_state: state.random_state
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: ScriptSnapshot) {
+        let mut state = self.state.borrow_mut();
+
+        self.level_variables = Dynamic::from_map(snapshot.variables);
+        state.timers = snapshot.timers.into_iter().map(|(event_name, remaining)| ScriptTimer { event_name, remaining }).collect();
+        state.random_state = snapshot.random_state.max(1);
+    }
+
+    // Level variables are saved as JSON, so only numbers, strings, booleans, arrays and maps are restored
+    pub fn parse_variables(&self, json: &str) -> Result<Map, String> {
+        self.engine.parse_json(json, true).map_err(|error| error.to_string())
+    }
+
+    // Names of the timer events which have expired
+    pub fn advance_timers(&mut self, time_delta: Duration) -> Vec<String> {
+        let mut state = self.state.borrow_mut();
+
+        for timer in state.timers.iter_mut() {
+            timer.remaining = timer.remaining.saturating_sub(time_delta);
+        }
+
+        let (expired, active) = state.timers.drain(..).partition(|timer| timer.remaining.is_zero());
+        state.timers = active;
+
+        expired.into_iter().map(|timer: ScriptTimer| timer.event_name).collect()
+    }
+
+    fn handle_event(&mut self, event: &GameEvent, context: &mut EventContext) {
+        if !self.has_event_handler {
+            return;
+        }
+
+        {
+            let mut state = self.state.borrow_mut();
+
+            if let Some(position_component) = context.manager.borrow_component::<PositionComponent>(event.activator()) {
+                state.player_position = position_component.position;
+            }
+
+            if let Some(direction_component) = context.manager.borrow_component::<DirectionComponent>(event.activator()) {
+                state.player_direction = direction_component.direction;
+            }
+        }
+
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.level_variables);
+
+        let result = self.engine.call_fn_with_options::<Dynamic>(options, &mut self.scope, &self.ast, EVENT_HANDLER_FUNCTION, (event.name().clone(),));
+
+        if let Err(error) = result {
+            log::error!("Level script has failed on event {}: {}", event.name(), error);
+        }
+
+        let commands: Vec<ScriptCommand> = self.state.borrow_mut().commands.drain(..).collect();
+
+        for command in commands {
+            apply_command(command, event, context);
+        }
+    }
+}
+
+// Delivers all game events to the level script
+pub struct ScriptEventHandler {
+    script: Rc<RefCell<LevelScript>>
+}
+
+impl ScriptEventHandler {
+    pub fn new(script: &Rc<RefCell<LevelScript>>) -> Self {
+        Self { script: script.clone() }
+    }
+}
+
+impl GameEventHandler for ScriptEventHandler {
+    fn handle(&mut self, event: &GameEvent, context: &mut EventContext) {
+        self.script.borrow_mut().handle_event(event, context)
+    }
+}
+
+fn apply_command(command: ScriptCommand, event: &GameEvent, context: &mut EventContext) {
+    match command {
+        ScriptCommand::Action(mut action) => action.handle(event, context),
+
+        ScriptCommand::SetLineColor { linemap, line, color } => {
+            match context.repositories.linemap_repository().borrow_mut().get_resource_mut(&linemap) {
+                None => log::warn!("Linemap {} of level script is not found", linemap),
+                Some(linemap) => linemap.set_line_color(line, color)
+            }
+        }
+
+        ScriptCommand::SpawnDoor { position, axis } => {
+            let door_entity_id = context.manager.create_entity();
+            let binding = DoorBinding::Tile { position, axis };
+
+            context.manager.add_component_to_entity(door_entity_id, DoorComponent::new(DoorKind::Sliding, binding, ObjectColor::BLUE));
+        }
+
+        ScriptCommand::SpawnTrigger { from, to, event_name } => {
+            let trigger_entity_id = context.manager.create_entity();
+            let region = TriggerRegion::Rect { from, to };
+
+            context.manager.add_component_to_entity(trigger_entity_id, TriggerComponent::new(region).on_enter(&event_name));
+        }
+    }
+}
+
+fn create_engine(state: &Rc<RefCell<ScriptState>>) -> Engine {
+    let mut engine = Engine::new();
+
+    engine.on_print(|text| log::info!("Level script: {}", text));
+
+    let shared = state.clone();
+    engine.register_fn("player_x", move || shared.borrow().player_position.x as f64);
+
+    let shared = state.clone();
+    engine.register_fn("player_y", move || shared.borrow().player_position.y as f64);
+
+    let shared = state.clone();
+    engine.register_fn("player_direction", move || shared.borrow().player_direction as f64);
+
+    let shared = state.clone();
+    engine.register_fn("random", move || shared.borrow_mut().next_random());
+
+    let shared = state.clone();
+    engine.register_fn("set_tile", move |tilemap: i64, x: i64, y: i64, tile: i64| {
+        match cell_position(x, y) {
+            None => log::warn!("Cell ({}, {}) of set_tile in level script is out of the map", x, y),
+            Some(position) => shared.borrow_mut().push_action(EventAction::SetTile { tilemap: tilemap as ResourceId, position, tile: tile as ResourceId })
+        }
+    });
+
+    let shared = state.clone();
+    engine.register_fn("set_line_color", move |linemap: i64, line: i64, color_name: &str| {
+        match ObjectColor::from_name(color_name) {
+            None => log::warn!("Unknown color {} in level script", color_name),
+            Some(color) => shared.borrow_mut().commands.push(ScriptCommand::SetLineColor { linemap: linemap as ResourceId, line: line as usize, color })
+        }
+    });
+
+    let shared = state.clone();
+    engine.register_fn("teleport", move |x: f64, y: f64| {
+        shared.borrow_mut().push_action(EventAction::Teleport { position: vec2(x as f32, y as f32), direction: None });
+    });
+
+    let shared = state.clone();
+    engine.register_fn("teleport", move |x: f64, y: f64, direction: f64| {
+        shared.borrow_mut().push_action(EventAction::Teleport { position: vec2(x as f32, y as f32), direction: Some(direction as f32) });
+    });
+
+    let shared = state.clone();
+    engine.register_fn("open_door", move |door: i64| {
+        shared.borrow_mut().push_action(EventAction::OpenDoor { door: door as usize });
+    });
+
+    let shared = state.clone();
+    engine.register_fn("message", move |text: &str| {
+        shared.borrow_mut().push_action(EventAction::ShowMessage { text: text.to_string() });
+    });
+
+    let shared = state.clone();
+    engine.register_fn("end_level", move || {
+        shared.borrow_mut().push_action(EventAction::EndLevel);
+    });
+
+    let shared = state.clone();
+    engine.register_fn("spawn_door", move |x: i64, y: i64, is_vertical: bool| {
+        let axis = if is_vertical { TileAxis::Vertical } else { TileAxis::Horizontal };
+        match cell_position(x, y) {
+            None => log::warn!("Cell ({}, {}) of spawn_door in level script is out of the map", x, y),
+            Some(position) => shared.borrow_mut().commands.push(ScriptCommand::SpawnDoor { position, axis })
+        }
+    });
+
+    let shared = state.clone();
+    engine.register_fn("spawn_trigger", move |from_x: f64, from_y: f64, to_x: f64, to_y: f64, event_name: &str| {
+        shared.borrow_mut().commands.push(ScriptCommand::SpawnTrigger {
+            from: vec2(from_x as f32, from_y as f32),
+            to: vec2(to_x as f32, to_y as f32),
+            event_name: event_name.to_string()
+        });
+    });
+
+    let shared = state.clone();
+    engine.register_fn("set_timer", move |event_name: &str, seconds: f64| {
+        match Duration::try_from_secs_f64(seconds.max(0.0)) {
+            Err(_) => log::warn!("Invalid delay {} of timer {} in level script", seconds, event_name),
+            Ok(remaining) => shared.borrow_mut().timers.push(ScriptTimer { event_name: event_name.to_string(), remaining })
+        }
+    });
+
+    engine
+}
+
+// Negative or too large coordinates don't address any cell
+fn cell_position(x: i64, y: i64) -> Option<UVec2> {
+    match (u32::try_from(x), u32::try_from(y)) {
+        (Ok(x), Ok(y)) => Some(uvec2(x, y)),
+        _ => None
+    }
+}
@@ -1,24 +1,57 @@
 mod game;
 
+use std::env;
+use std::process;
+
 use env_logger::Env;
-use crate::game::Game;
+use crate::game::{Game, LaunchOptions, USAGE};
 
 fn main() {
-    initialize_logging();
+    let options = match LaunchOptions::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if options.is_help_requested {
+        println!("{}", USAGE);
+        return;
+    }
+
+    initialize_logging(options.log_level.as_deref());
     log::info!("Application started");
 
     log::info!("Initializing SDL context");
     let sdl_context = sdl2::init().unwrap();
     log::info!("SDL context has been initialized");
 
-    let mut game = Game::initialize_game(sdl_context);
-    game.run_game_loop()
+    let mut game = match Game::initialize_game(sdl_context, options) {
+        Ok(game) => game,
+        Err(error) => {
+            log::error!("Unable to start the game: {}", error);
+            process::exit(1);
+        }
+    };
+
+    if let Err(error) = game.run_game_loop() {
+        log::error!("{}", error);
+        process::exit(1);
+    }
 }
 
-fn initialize_logging() {
+// Log level option overrides LOG_LEVEL environment variable
+fn initialize_logging(log_level: Option<&str>) {
     let env = Env::default()
         .filter_or("LOG_LEVEL", "trace")
         .write_style_or("LOG_STYLE", "always");
 
-    env_logger::init_from_env(env);
+    let mut builder = env_logger::Builder::from_env(env);
+
+    if let Some(log_level) = log_level {
+        builder.parse_filters(log_level);
+    }
+
+    builder.init();
 }